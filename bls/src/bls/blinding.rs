@@ -0,0 +1,144 @@
+//! Deterministic derivation of the side-channel blinding values used by
+//! `PrivateKey::derive_blinding`, for devices without an RNG.
+//!
+//! The values are HMAC-SHA512(sk, DST || i || hash || entropy) for i in
+//! {0, 1}. Every input has a fixed length, so HMAC is computed directly with
+//! the SHA-512 compression function over precomputed padding: the buffered
+//! `Digest` implementation indexes its block buffer at run time, which keeps
+//! panic paths in the staticlib (see `tests/no_panic.rs`).
+
+use bls12_377::Scalar;
+use sha2::compress512;
+#[cfg(feature = "zeroize")]
+use zeroize_crate::Zeroize;
+
+/// Domain separation tag for the derivation of blinding values.
+const BLINDING_DST: &[u8; 23] = b"BLS12377_SIGN_BLINDING_";
+
+/// The length of DST || i || hash || entropy.
+const MSG_LEN: usize = 23 + 1 + 96 + 32;
+
+/// The SHA-512 block size.
+const BLOCK_LEN: usize = 128;
+
+/// The initial SHA-512 hash value.
+const H512: [u64; 8] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+/// Returns the 128 bytes from which the blinding values for signing `hash`
+/// with `sk` are built.
+pub(crate) fn blinding_bytes(sk: &Scalar, hash: &[u8; 96], entropy: &[u8; 32]) -> [u8; 128] {
+    #[allow(unused_mut)]
+    let mut key = sk.to_bytes();
+    let mut msg = [0u8; MSG_LEN];
+    msg[..23].copy_from_slice(BLINDING_DST);
+    msg[24..120].copy_from_slice(hash);
+    msg[120..].copy_from_slice(entropy);
+
+    let mut okm = [0u8; 128];
+    for (i, chunk) in okm.chunks_mut(64).enumerate() {
+        msg[23] = i as u8;
+        chunk.copy_from_slice(&hmac_sha512(&key, &msg));
+    }
+
+    #[cfg(feature = "zeroize")]
+    {
+        key.zeroize();
+        msg.zeroize();
+    }
+
+    okm
+}
+
+/// HMAC-SHA512 of a `MSG_LEN`-byte message under a 32-byte key.
+fn hmac_sha512(key: &[u8; 32], msg: &[u8; MSG_LEN]) -> [u8; 64] {
+    // Inner hash over (key ^ ipad) || msg, which pads to three blocks.
+    let mut pad = [0x36u8; BLOCK_LEN];
+    for (p, k) in pad.iter_mut().zip(key.iter()) {
+        *p ^= k;
+    }
+    let mut state = H512;
+    compress512(&mut state, &pad);
+
+    let mut blocks = [[0u8; BLOCK_LEN]; 2];
+    for (i, b) in msg.iter().enumerate() {
+        blocks[i / BLOCK_LEN][i % BLOCK_LEN] = *b;
+    }
+    blocks[1][MSG_LEN - BLOCK_LEN] = 0x80;
+    let bits = ((BLOCK_LEN + MSG_LEN) * 8) as u128;
+    blocks[1][BLOCK_LEN - 16..].copy_from_slice(&bits.to_be_bytes());
+    for block in blocks.iter() {
+        compress512(&mut state, block);
+    }
+    let mut inner = [0u8; 64];
+    for (chunk, word) in inner.chunks_mut(8).zip(state.iter()) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+
+    // Outer hash over (key ^ opad) || inner, which pads to two blocks.
+    for p in pad.iter_mut() {
+        *p ^= 0x36 ^ 0x5c;
+    }
+    let mut state = H512;
+    compress512(&mut state, &pad);
+
+    let mut block = [0u8; BLOCK_LEN];
+    block[..64].copy_from_slice(&inner);
+    block[64] = 0x80;
+    let bits = ((BLOCK_LEN + 64) * 8) as u128;
+    block[BLOCK_LEN - 16..].copy_from_slice(&bits.to_be_bytes());
+    compress512(&mut state, &block);
+
+    let mut out = [0u8; 64];
+    for (chunk, word) in out.chunks_mut(8).zip(state.iter()) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+
+    #[cfg(feature = "zeroize")]
+    {
+        pad.zeroize();
+        blocks.zeroize();
+        inner.zeroize();
+        block.zeroize();
+    }
+
+    out
+}
+
+#[test]
+fn test_hmac_sha512() {
+    use hmac::{Hmac, Mac};
+    use sha2::Sha512;
+
+    let key = Scalar::from_raw([1, 2, 3, 4]).to_bytes();
+    let mut msg = [0u8; MSG_LEN];
+    for (i, b) in msg.iter_mut().enumerate() {
+        *b = (i as u8).wrapping_mul(29);
+    }
+
+    let mut mac = Hmac::<Sha512>::new_varkey(&key).unwrap();
+    mac.input(&msg);
+    assert_eq!(&hmac_sha512(&key, &msg)[..], &mac.result().code()[..]);
+}
+
+#[test]
+fn test_blinding_bytes() {
+    let sk = Scalar::from(5);
+    let hash = [3u8; 96];
+    let base = blinding_bytes(&sk, &hash, &[0; 32]);
+
+    // The derivation depends on the key, the hash and the entropy.
+    assert_eq!(&base[..], &blinding_bytes(&sk, &hash, &[0; 32])[..]);
+    assert!(base[..] != blinding_bytes(&Scalar::from(6), &hash, &[0; 32])[..]);
+    assert!(base[..] != blinding_bytes(&sk, &[4; 96], &[0; 32])[..]);
+    assert!(base[..] != blinding_bytes(&sk, &hash, &[1; 32])[..]);
+    assert!(base[..64] != base[64..]);
+}
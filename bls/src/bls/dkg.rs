@@ -0,0 +1,1018 @@
+//! Distributed key generation: the Joint-Feldman protocol of Pedersen, as
+//! analysed by Gennaro, Jarecki, Krawczyk and Rabin ("Secure Distributed Key
+//! Generation for Discrete-Log Based Cryptosystems", J. Cryptology 2007), so
+//! that the shares of a threshold key are made without the key ever existing
+//! in one place.
+//!
+//! Each of the n participants acts as a dealer of a random polynomial f_i of
+//! degree t - 1: it broadcasts Feldman commitments a_ik g2 to the
+//! coefficients and privately sends f_i(j) to participant j, who checks it
+//! against the commitments. A participant given a bad share, or none,
+//! broadcasts a complaint, which the dealer answers by broadcasting the
+//! share. Dealers that fail to answer every complaint against them with a
+//! valid share are disqualified. Participant j's key share is the sum of
+//! f_i(j) over the qualified dealers, and the group key the sum of their
+//! a_i0 g2.
+//!
+//! `Participant` does no I/O: the caller moves the messages between
+//! participants, and must deliver every broadcast message to every
+//! participant, including its sender's own complaints and answers, so that
+//! all agree on the qualified dealers. The protocol runs as follows:
+//!
+//! 1. Each dealer sends the output of `deal`: its `Commitments` to everyone
+//!    and each `Share` to its recipient. Everyone passes what they receive
+//!    to `receive_commitments` and `receive_share`.
+//! 2. Once all dealings are in, or a timeout has passed, each participant
+//!    broadcasts its `complaints`, passed to `receive_complaint`.
+//! 3. Each dealer broadcasts its `answers` to the complaints against it,
+//!    passed to `receive_answer`.
+//! 4. Each participant calls `finish` for its share of the group key.
+//!
+//! As with Joint-Feldman in general, a dealer that sees the others'
+//! commitments before sending its own can bias the group key; the key is
+//! still hard to compute, which is all that BLS signing needs.
+//!
+//! The same protocol changes the shares of an existing key without changing
+//! the key. `Participant::reshare` moves it to a new committee, possibly of
+//! another size and threshold: the old members deal polynomials whose
+//! constant terms are their old shares, so dealers whose a_i0 g2 is not
+//! their old public share are disqualified, and the new members interpolate
+//! the qualified dealings at zero, which needs at least the old threshold of
+//! them. `Participant::refresh` re-randomizes the shares of a committee in
+//! place: every dealer deals a polynomial with constant term zero, which the
+//! members add to their old shares. Shares from before and after a refresh
+//! do not combine, so an attacker must corrupt a threshold of members
+//! between two refreshes.
+
+use bls12_377::{G2Affine, G2Projective, Scalar};
+use core::fmt;
+use rand_core_crate::{CryptoRng, RngCore};
+#[cfg(feature = "serde")]
+use serde_crate::{Deserialize, Deserializer, Serialize, Serializer};
+use std::vec;
+use std::vec::Vec;
+#[cfg(feature = "zeroize")]
+use zeroize_crate::Zeroize;
+
+use crate::bls::keys::{PrivateKey, PublicKey};
+use crate::bls::threshold::{evaluate_commitments, lagrange_coefficient, KeyShare, Polynomial, PublicKeyShare};
+use crate::error::ErrorCode;
+
+/// A dealer's broadcast commitments a_ik g2 to the coefficients of its
+/// polynomial, lowest degree first.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Commitments {
+    dealer: u32,
+    points: Vec<G2Affine>,
+}
+
+impl Commitments {
+    pub fn dealer(&self) -> u32 {
+        self.dealer
+    }
+
+    pub fn points(&self) -> &[G2Affine] {
+        &self.points
+    }
+}
+
+/// The value f_i(j) of dealer i's polynomial for participant j. It is sent
+/// privately, except when the dealer broadcasts it to answer a complaint.
+#[derive(Clone)]
+pub struct Share {
+    dealer: u32,
+    recipient: u32,
+    value: Scalar,
+}
+
+impl Share {
+    pub fn dealer(&self) -> u32 {
+        self.dealer
+    }
+
+    pub fn recipient(&self) -> u32 {
+        self.recipient
+    }
+}
+
+// The value is secret, so it is left out.
+impl fmt::Debug for Share {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Share {{ dealer: {}, recipient: {}, .. }}", self.dealer, self.recipient)
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for Share {
+    fn drop(&mut self) {
+        self.value.zeroize();
+    }
+}
+
+/// A broadcast accusation that `dealer` sent `accuser` a share that does
+/// not match its commitments, or no share at all.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Complaint {
+    accuser: u32,
+    dealer: u32,
+}
+
+impl Complaint {
+    pub fn accuser(&self) -> u32 {
+        self.accuser
+    }
+
+    pub fn dealer(&self) -> u32 {
+        self.dealer
+    }
+}
+
+/// How the dealings of a run combine into the new key.
+enum Kind {
+    /// A fresh key: the dealers' constant terms are random and summed.
+    Fresh,
+    /// A resharing of the key with group polynomial commitments `old`: the
+    /// dealers' constant terms are their old shares, and the qualified
+    /// dealings are weighted with the Lagrange coefficients of the dealers.
+    Reshare { old: Vec<G2Affine> },
+    /// A refresh of the key with group polynomial commitments `old`, of
+    /// which this participant holds the share `share`: the dealers' constant
+    /// terms are zero, and the dealings are added to the old shares.
+    Refresh { old: Vec<G2Affine>, share: Scalar },
+}
+
+/// One participant's view of a run of the protocol.
+pub struct Participant {
+    /// This participant's index among the dealers and its polynomial, if it
+    /// deals.
+    dealer: Option<(u32, Polynomial)>,
+    /// This participant's index in the committee being formed, if it is in
+    /// it.
+    index: Option<u32>,
+    /// The size of the committee being formed.
+    participants: u32,
+    threshold: usize,
+    kind: Kind,
+    /// The commitments of each dealer, by dealer index - 1.
+    commitments: Vec<Option<Vec<G2Affine>>>,
+    /// The shares sent to this participant, by dealer index - 1.
+    shares: Vec<Option<Scalar>>,
+    complaints: Vec<Complaint>,
+    answers: Vec<Share>,
+}
+
+impl Participant {
+    /// Starts a run among the participants with indices 1, ..., `participants`
+    /// as the one with index `index`, for a key that any `threshold` of them
+    /// can sign for. Fails with `ErrorCode::InvalidThreshold` unless
+    /// 1 <= threshold <= participants, and with `ErrorCode::InvalidShareIndex`
+    /// unless 1 <= index <= participants.
+    pub fn new(
+        index: u32,
+        participants: u32,
+        threshold: usize,
+        mut rng: impl RngCore + CryptoRng,
+    ) -> Result<Self, ErrorCode> {
+        check_threshold(threshold, participants)?;
+        check_index(index, participants)?;
+
+        let secret = Scalar::random(&mut rng);
+        let polynomial = Polynomial::random(&secret, threshold - 1, rng);
+        Ok(Self::start(Some((index, polynomial)), Some(index), participants, participants, threshold, Kind::Fresh))
+    }
+
+    /// Starts a resharing of the key whose group polynomial has the
+    /// commitments `old`, from its committee with indices 1, ...,
+    /// `old_participants` to a committee with indices 1, ..., `participants`
+    /// of which any `threshold` can sign. Members of the old committee deal
+    /// and pass their `share`, members of the new committee receive and pass
+    /// their `index`, and members of both pass both.
+    ///
+    /// Fails with `ErrorCode::InvalidThreshold` unless 1 <= threshold <=
+    /// participants and the old threshold fits the old committee, with
+    /// `ErrorCode::InvalidShareIndex` if an index is out of range, with
+    /// `ErrorCode::VerificationFailed` if `share` does not match `old`, and
+    /// with `ErrorCode::UnknownKey` if neither `share` nor `index` is given.
+    pub fn reshare(
+        old: &[G2Affine],
+        old_participants: u32,
+        share: Option<&KeyShare>,
+        index: Option<u32>,
+        participants: u32,
+        threshold: usize,
+        rng: impl RngCore + CryptoRng,
+    ) -> Result<Self, ErrorCode> {
+        check_threshold(old.len(), old_participants)?;
+        check_threshold(threshold, participants)?;
+        if let Some(index) = index {
+            check_index(index, participants)?;
+        }
+
+        let dealer = match share {
+            Some(share) => {
+                check_key_share(old, old_participants, share)?;
+                let polynomial = Polynomial::random(share.private_key().as_scalar(), threshold - 1, rng);
+                Some((share.index(), polynomial))
+            }
+            None if index.is_some() => None,
+            None => return Err(ErrorCode::UnknownKey),
+        };
+
+        let kind = Kind::Reshare { old: old.to_vec() };
+        Ok(Self::start(dealer, index, old_participants, participants, threshold, kind))
+    }
+
+    /// Starts a refresh of the shares of the key whose group polynomial has
+    /// the commitments `old`, held by the committee with indices 1, ...,
+    /// `participants`, all of whom take part with their `share`. The group
+    /// key stays the same, but the new shares do not combine with the old
+    /// ones, which should be erased once the refresh is done.
+    ///
+    /// Fails with `ErrorCode::InvalidThreshold` unless the threshold fits the
+    /// committee, with `ErrorCode::InvalidShareIndex` if the share's index is
+    /// out of range, and with `ErrorCode::VerificationFailed` if `share` does
+    /// not match `old`.
+    pub fn refresh(
+        old: &[G2Affine],
+        participants: u32,
+        share: &KeyShare,
+        rng: impl RngCore + CryptoRng,
+    ) -> Result<Self, ErrorCode> {
+        check_threshold(old.len(), participants)?;
+        check_key_share(old, participants, share)?;
+
+        let index = share.index();
+        let polynomial = Polynomial::random(&Scalar::zero(), old.len() - 1, rng);
+        let kind = Kind::Refresh { old: old.to_vec(), share: *share.private_key().as_scalar() };
+        Ok(Self::start(Some((index, polynomial)), Some(index), participants, participants, old.len(), kind))
+    }
+
+    fn start(
+        dealer: Option<(u32, Polynomial)>,
+        index: Option<u32>,
+        dealers: u32,
+        participants: u32,
+        threshold: usize,
+        kind: Kind,
+    ) -> Self {
+        let mut participant = Participant {
+            dealer,
+            index,
+            participants,
+            threshold,
+            kind,
+            commitments: (0..dealers).map(|_| None).collect(),
+            shares: (0..dealers).map(|_| None).collect(),
+            complaints: Vec::new(),
+            answers: Vec::new(),
+        };
+        if let Some((i, polynomial)) = &participant.dealer {
+            participant.commitments[slot(*i)] = Some(polynomial.commit());
+            if let Some(j) = index {
+                participant.shares[slot(*i)] = Some(polynomial.evaluate(j));
+            }
+        }
+
+        participant
+    }
+
+    /// Returns this participant's index in the committee being formed, or
+    /// `None` if it only deals.
+    pub fn index(&self) -> Option<u32> {
+        self.index
+    }
+
+    /// Returns this participant's index among the dealers, or `None` if it
+    /// only receives.
+    pub fn dealer_index(&self) -> Option<u32> {
+        self.dealer.as_ref().map(|(i, _)| *i)
+    }
+
+    /// Checks that `index` is that of another dealer.
+    fn check_peer(&self, index: u32) -> Result<(), ErrorCode> {
+        if index == 0 || index as usize > self.commitments.len() || Some(index) == self.dealer_index() {
+            return Err(ErrorCode::InvalidProtocolMessage);
+        }
+
+        Ok(())
+    }
+
+    /// Returns this participant's dealing, if it deals: the commitments to
+    /// broadcast and the share to send privately to each other member of
+    /// the committee being formed.
+    pub fn deal(&self) -> Option<(Commitments, Vec<Share>)> {
+        let (i, polynomial) = self.dealer.as_ref()?;
+        let commitments = Commitments { dealer: *i, points: polynomial.commit() };
+        let shares = (1..=self.participants)
+            .filter(|j| Some(*j) != self.index)
+            .map(|j| Share { dealer: *i, recipient: j, value: polynomial.evaluate(j) })
+            .collect();
+
+        Some((commitments, shares))
+    }
+
+    /// Records another dealer's commitments. Fails with
+    /// `ErrorCode::InvalidProtocolMessage` if the dealer is unknown, has
+    /// already sent different commitments, or committed to a polynomial of
+    /// the wrong degree.
+    pub fn receive_commitments(&mut self, msg: &Commitments) -> Result<(), ErrorCode> {
+        self.check_peer(msg.dealer)?;
+        if msg.points.len() != self.threshold {
+            return Err(ErrorCode::InvalidProtocolMessage);
+        }
+
+        let slot = slot(msg.dealer);
+        match &self.commitments[slot] {
+            Some(points) if *points != msg.points => Err(ErrorCode::InvalidProtocolMessage),
+            _ => {
+                self.commitments[slot] = Some(msg.points.clone());
+                Ok(())
+            }
+        }
+    }
+
+    /// Records the share another dealer sent to this participant. It is
+    /// checked against the dealer's commitments by `complaints`. Fails with
+    /// `ErrorCode::InvalidProtocolMessage` if the share is for someone else,
+    /// or the dealer is unknown or has already sent a share.
+    pub fn receive_share(&mut self, msg: &Share) -> Result<(), ErrorCode> {
+        self.check_peer(msg.dealer)?;
+        if Some(msg.recipient) != self.index {
+            return Err(ErrorCode::InvalidProtocolMessage);
+        }
+
+        let slot = slot(msg.dealer);
+        if self.shares[slot].is_some() {
+            return Err(ErrorCode::InvalidProtocolMessage);
+        }
+        self.shares[slot] = Some(msg.value);
+        Ok(())
+    }
+
+    /// Checks the received shares against their dealers' commitments and
+    /// returns a complaint against each dealer whose share is missing or
+    /// wrong, to be broadcast. Dealers that sent no commitments get no
+    /// complaint: every participant disqualifies them anyway.
+    pub fn complaints(&self) -> Vec<Complaint> {
+        let index = match self.index {
+            Some(index) => index,
+            None => return Vec::new(),
+        };
+
+        (1..=self.commitments.len() as u32)
+            .filter(|i| Some(*i) != self.dealer_index())
+            .filter(|i| {
+                let slot = slot(*i);
+                match (&self.commitments[slot], &self.shares[slot]) {
+                    (Some(points), Some(value)) => !check_share(points, index, value),
+                    (Some(_), None) => true,
+                    (None, _) => false,
+                }
+            })
+            .map(|dealer| Complaint { accuser: index, dealer })
+            .collect()
+    }
+
+    /// Records a broadcast complaint, including this participant's own.
+    /// Fails with `ErrorCode::InvalidProtocolMessage` if either party is
+    /// unknown or, unless resharing, the complaint is against the accuser
+    /// itself.
+    pub fn receive_complaint(&mut self, msg: &Complaint) -> Result<(), ErrorCode> {
+        let resharing = matches!(self.kind, Kind::Reshare { .. });
+        if msg.accuser == 0
+            || msg.accuser > self.participants
+            || msg.dealer == 0
+            || msg.dealer as usize > self.commitments.len()
+            || (!resharing && msg.accuser == msg.dealer)
+        {
+            return Err(ErrorCode::InvalidProtocolMessage);
+        }
+
+        if !self.complaints.contains(msg) {
+            self.complaints.push(*msg);
+        }
+        Ok(())
+    }
+
+    /// Returns the answers to the complaints against this participant: the
+    /// accusers' shares, to be broadcast.
+    pub fn answers(&self) -> Vec<Share> {
+        let (i, polynomial) = match &self.dealer {
+            Some(dealer) => dealer,
+            None => return Vec::new(),
+        };
+
+        self.complaints
+            .iter()
+            .filter(|c| c.dealer == *i)
+            .map(|c| Share { dealer: *i, recipient: c.accuser, value: polynomial.evaluate(c.accuser) })
+            .collect()
+    }
+
+    /// Records a dealer's broadcast answer to a complaint, including this
+    /// participant's own. Fails with `ErrorCode::InvalidProtocolMessage` if
+    /// there is no such complaint, the dealer sent no commitments, or the
+    /// complaint has already been answered, and with
+    /// `ErrorCode::VerificationFailed` if the share does not match the
+    /// dealer's commitments. A rejected answer is not recorded, so a valid
+    /// one delivered later still counts.
+    pub fn receive_answer(&mut self, msg: &Share) -> Result<(), ErrorCode> {
+        let complaint = Complaint { accuser: msg.recipient, dealer: msg.dealer };
+        if !self.complaints.contains(&complaint) {
+            return Err(ErrorCode::InvalidProtocolMessage);
+        }
+        if self.answers.iter().any(|a| a.dealer == msg.dealer && a.recipient == msg.recipient) {
+            return Err(ErrorCode::InvalidProtocolMessage);
+        }
+        let points = self.commitments[slot(msg.dealer)].as_ref().ok_or(ErrorCode::InvalidProtocolMessage)?;
+        if !check_share(points, msg.recipient, &msg.value) {
+            return Err(ErrorCode::VerificationFailed);
+        }
+
+        self.answers.push(msg.clone());
+        Ok(())
+    }
+
+    /// Returns whether dealer `i` is qualified: it sent commitments with
+    /// the constant term the run requires, and answered every complaint
+    /// against it with a share that matches them.
+    fn is_qualified(&self, i: u32) -> bool {
+        let points = match &self.commitments[slot(i)] {
+            Some(points) => points,
+            None => return false,
+        };
+        let constant = G2Projective::from(points[0]);
+        let valid_constant = match &self.kind {
+            Kind::Fresh => true,
+            Kind::Reshare { old } => constant == evaluate_commitments(old, i),
+            Kind::Refresh { .. } => bool::from(constant.is_identity()),
+        };
+
+        // Answers are checked against the commitments when received.
+        valid_constant
+            && self
+                .complaints
+                .iter()
+                .filter(|c| c.dealer == i)
+                .all(|c| self.answers.iter().any(|a| a.dealer == i && a.recipient == c.accuser))
+    }
+
+    /// Ends the run, returning this participant's share of the group key.
+    /// Fails with `ErrorCode::UnknownKey` if this participant only deals,
+    /// with `ErrorCode::InvalidThreshold` if fewer dealers than the old
+    /// threshold qualify in a resharing, and with
+    /// `ErrorCode::VerificationFailed` if a qualified dealer's share for
+    /// this participant is missing or wrong, which only happens if this
+    /// participant's complaints were not delivered.
+    pub fn finish(self) -> Result<DkgOutput, ErrorCode> {
+        let index = self.index.ok_or(ErrorCode::UnknownKey)?;
+        let qualified: Vec<u32> = (1..=self.commitments.len() as u32).filter(|i| self.is_qualified(*i)).collect();
+        if let Kind::Reshare { old } = &self.kind {
+            if qualified.len() < old.len() {
+                return Err(ErrorCode::InvalidThreshold);
+            }
+        }
+
+        let mut secret = Scalar::zero();
+        let mut commitments = vec![G2Projective::identity(); self.threshold];
+        for i in qualified.iter() {
+            let slot = slot(*i);
+            let points = self.commitments[slot].as_ref().ok_or(ErrorCode::VerificationFailed)?;
+            let answer = self.answers.iter().find(|a| a.dealer == *i && a.recipient == index);
+            let value = match (answer, &self.shares[slot]) {
+                (Some(a), _) => a.value,
+                (None, Some(value)) => *value,
+                (None, None) => return Err(ErrorCode::VerificationFailed),
+            };
+            if !check_share(points, index, &value) {
+                return Err(ErrorCode::VerificationFailed);
+            }
+
+            // In a resharing, the dealings are shares of the old shares, so
+            // interpolating them at zero gives shares of the old key.
+            if let Kind::Reshare { .. } = self.kind {
+                let l: Scalar = Option::from(lagrange_coefficient(*i, qualified.iter().copied())).ok_or(ErrorCode::InvalidShareIndex)?;
+                secret += value * l;
+                for (c, p) in commitments.iter_mut().zip(points.iter()) {
+                    *c += p * l;
+                }
+            } else {
+                secret += value;
+                for (c, p) in commitments.iter_mut().zip(points.iter()) {
+                    *c += p;
+                }
+            }
+        }
+        if let Kind::Refresh { old, share } = &self.kind {
+            secret += share;
+            for (c, p) in commitments.iter_mut().zip(old.iter()) {
+                *c += p;
+            }
+        }
+
+        let share = KeyShare::new(index, PrivateKey::from_scalar(&secret))?;
+        #[cfg(feature = "zeroize")]
+        secret.zeroize();
+
+        Ok(DkgOutput {
+            share,
+            participants: self.participants,
+            qualified,
+            commitments: commitments.iter().map(G2Affine::from).collect(),
+        })
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for Participant {
+    fn drop(&mut self) {
+        for value in self.shares.iter_mut().flatten() {
+            value.zeroize();
+        }
+        if let Kind::Refresh { share, .. } = &mut self.kind {
+            share.zeroize();
+        }
+    }
+}
+
+/// Checks that a threshold fits a committee of `participants`.
+fn check_threshold(threshold: usize, participants: u32) -> Result<(), ErrorCode> {
+    if threshold == 0 || threshold > participants as usize {
+        return Err(ErrorCode::InvalidThreshold);
+    }
+
+    Ok(())
+}
+
+/// Checks that `index` is that of a member of a committee of
+/// `participants`.
+fn check_index(index: u32, participants: u32) -> Result<(), ErrorCode> {
+    if index == 0 || index > participants {
+        return Err(ErrorCode::InvalidShareIndex);
+    }
+
+    Ok(())
+}
+
+/// Checks that `share` is the share of a member of a committee of
+/// `participants` holding the key whose group polynomial has the
+/// commitments `old`.
+fn check_key_share(old: &[G2Affine], participants: u32, share: &KeyShare) -> Result<(), ErrorCode> {
+    check_index(share.index(), participants)?;
+    if share.private_key().to_public() != PublicKey::from_pk(&evaluate_commitments(old, share.index())) {
+        return Err(ErrorCode::VerificationFailed);
+    }
+
+    Ok(())
+}
+
+/// The position of a participant's entries in `Participant::commitments`
+/// and `Participant::shares`, for an index already checked to be in range.
+fn slot(index: u32) -> usize {
+    index as usize - 1
+}
+
+/// Checks a dealer's share f_i(index) against its commitments.
+fn check_share(commitments: &[G2Affine], index: u32, value: &Scalar) -> bool {
+    G2Projective::generator() * value == evaluate_commitments(commitments, index)
+}
+
+/// The result of a run for one participant.
+pub struct DkgOutput {
+    share: KeyShare,
+    participants: u32,
+    qualified: Vec<u32>,
+    commitments: Vec<G2Affine>,
+}
+
+impl DkgOutput {
+    /// Returns this participant's share of the group key.
+    pub fn share(&self) -> &KeyShare {
+        &self.share
+    }
+
+    /// Returns the size of the committee that holds the key.
+    pub fn participants(&self) -> u32 {
+        self.participants
+    }
+
+    /// Returns the indices of the qualified dealers, on which all
+    /// participants agree.
+    pub fn qualified(&self) -> &[u32] {
+        &self.qualified
+    }
+
+    /// Returns the commitments to the group polynomial, from which the
+    /// group key and the public key of every share follow.
+    pub fn commitments(&self) -> &[G2Affine] {
+        &self.commitments
+    }
+
+    /// Returns the group public key.
+    pub fn group_key(&self) -> PublicKey {
+        PublicKey::from_pk(&G2Projective::from(self.commitments[0]))
+    }
+
+    /// Returns the public key of any participant's share, against which its
+    /// partial signatures are verified.
+    pub fn public_share(&self, index: u32) -> Result<PublicKeyShare, ErrorCode> {
+        PublicKeyShare::new(index, PublicKey::from_pk(&evaluate_commitments(&self.commitments, index)))
+    }
+}
+
+// Messages are serialized as tuples of their fields; see the `serde`
+// feature of `bls12_377` for the encoding of scalars and points.
+#[cfg(feature = "serde")]
+impl Serialize for Commitments {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (self.dealer, &self.points).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Commitments {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (dealer, points) = Deserialize::deserialize(deserializer)?;
+        Ok(Commitments { dealer, points })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Share {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (self.dealer, self.recipient, &self.value).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Share {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (dealer, recipient, value) = Deserialize::deserialize(deserializer)?;
+        Ok(Share { dealer, recipient, value })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Complaint {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (self.accuser, self.dealer).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Complaint {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (accuser, dealer) = Deserialize::deserialize(deserializer)?;
+        Ok(Complaint { accuser, dealer })
+    }
+}
+
+/// Runs the protocol among `parties` in process, letting `tamper` corrupt
+/// each private share and `answer` drop or corrupt each answer, and returns
+/// the results of the members of the committee being formed.
+#[cfg(test)]
+fn run(
+    mut parties: Vec<Participant>,
+    tamper: impl Fn(&mut Share),
+    answer: impl Fn(&mut Share) -> bool,
+) -> Vec<Result<DkgOutput, ErrorCode>> {
+    let dealings: Vec<(Commitments, Vec<Share>)> = parties.iter().filter_map(|p| p.deal()).collect();
+    for (commitments, shares) in dealings.iter() {
+        for p in parties.iter_mut().filter(|p| p.dealer_index() != Some(commitments.dealer())) {
+            p.receive_commitments(commitments).unwrap();
+        }
+        for share in shares.iter() {
+            let mut share = share.clone();
+            tamper(&mut share);
+            let recipient = parties.iter_mut().find(|p| p.index() == Some(share.recipient())).unwrap();
+            recipient.receive_share(&share).unwrap();
+        }
+    }
+
+    let complaints: Vec<Complaint> = parties.iter().flat_map(|p| p.complaints()).collect();
+    for p in parties.iter_mut() {
+        for c in complaints.iter() {
+            p.receive_complaint(c).unwrap();
+        }
+    }
+
+    let mut answers: Vec<Share> = parties.iter().flat_map(|p| p.answers()).collect();
+    answers.retain_mut(|a| answer(a));
+    for p in parties.iter_mut() {
+        for a in answers.iter() {
+            // Corrupted answers are rejected on receipt.
+            assert!(matches!(p.receive_answer(a), Ok(()) | Err(ErrorCode::VerificationFailed)));
+        }
+    }
+
+    parties.into_iter().filter(|p| p.index().is_some()).map(|p| p.finish()).collect()
+}
+
+/// Starts a fresh run among `n` participants with threshold `t`.
+#[cfg(test)]
+fn fresh(n: u32, t: usize) -> Vec<Participant> {
+    (1..=n).map(|i| Participant::new(i, n, t, crate::bls::keys::TestRng(u64::from(i))).unwrap()).collect()
+}
+
+#[cfg(test)]
+fn unwrap_all(results: Vec<Result<DkgOutput, ErrorCode>>) -> Vec<DkgOutput> {
+    results.into_iter().map(|r| r.ok().unwrap()).collect()
+}
+
+#[cfg(test)]
+fn check_outputs(outputs: &[DkgOutput], qualified: &[u32]) {
+    use crate::bls::threshold::{combine, PartialSignature};
+
+    let group_key = outputs[0].group_key();
+    for out in outputs.iter() {
+        assert_eq!(out.qualified(), qualified);
+        assert_eq!(out.participants() as usize, outputs.len());
+        assert_eq!(out.commitments(), outputs[0].commitments());
+        assert_eq!(out.public_share(out.share().index()), Ok(out.share().public_share()));
+    }
+
+    // Any threshold of the shares signs for the group key, and fewer do not.
+    let msg = b"dkg";
+    let partials: Vec<PartialSignature> = outputs.iter().map(|o| o.share().sign(msg).unwrap()).collect();
+    let t = outputs[0].commitments().len();
+    for start in 0..=partials.len() - t {
+        let sig = combine(&partials[start..start + t], t).unwrap();
+        assert_eq!(group_key.verify(msg, &sig), Ok(()));
+    }
+    assert_eq!(combine(&partials[..t - 1], t), Err(ErrorCode::InvalidThreshold));
+    for (out, partial) in outputs.iter().zip(partials.iter()) {
+        assert_eq!(outputs[0].public_share(out.share().index()).unwrap().verify(msg, partial), Ok(()));
+    }
+}
+
+#[test]
+fn test_dkg() {
+    let outputs = unwrap_all(run(fresh(5, 3), |_| {}, |_| true));
+    check_outputs(&outputs, &[1, 2, 3, 4, 5]);
+
+    // The group key is the sum of the dealers' secrets, which none knows.
+    let sum = fresh(5, 3).iter().fold(G2Projective::identity(), |acc, p| acc + p.deal().unwrap().0.points()[0]);
+    assert_eq!(outputs[0].group_key(), PublicKey::from_pk(&sum));
+}
+
+#[test]
+fn test_dkg_complaints() {
+    // Dealer 2 sends participant 4 a bad share but answers the complaint
+    // correctly, so stays qualified. Dealer 3 sends participants 1 and 5 bad
+    // shares and answers one complaint wrongly, so is disqualified.
+    let tamper = |s: &mut Share| {
+        if (s.dealer, s.recipient) == (2, 4) || s.dealer == 3 && (s.recipient == 1 || s.recipient == 5) {
+            s.value += Scalar::one();
+        }
+    };
+    let answer = |s: &mut Share| {
+        if (s.dealer, s.recipient) == (3, 5) {
+            s.value += Scalar::one();
+        }
+        true
+    };
+    check_outputs(&unwrap_all(run(fresh(5, 3), tamper, answer)), &[1, 2, 4, 5]);
+
+    // An unanswered complaint disqualifies the dealer too.
+    let tamper = |s: &mut Share| {
+        if (s.dealer, s.recipient) == (4, 1) {
+            s.value += Scalar::one();
+        }
+    };
+    check_outputs(&unwrap_all(run(fresh(4, 2), tamper, |s| s.dealer != 4)), &[1, 2, 3]);
+}
+
+#[test]
+fn test_dkg_errors() {
+    use crate::bls::keys::TestRng;
+
+    assert_eq!(Participant::new(1, 3, 0, TestRng(1)).err(), Some(ErrorCode::InvalidThreshold));
+    assert_eq!(Participant::new(1, 3, 4, TestRng(1)).err(), Some(ErrorCode::InvalidThreshold));
+    assert_eq!(Participant::new(0, 3, 2, TestRng(1)).err(), Some(ErrorCode::InvalidShareIndex));
+    assert_eq!(Participant::new(4, 3, 2, TestRng(1)).err(), Some(ErrorCode::InvalidShareIndex));
+
+    let mut p1 = Participant::new(1, 3, 2, TestRng(1)).unwrap();
+    let p2 = Participant::new(2, 3, 2, TestRng(2)).unwrap();
+    let p3 = Participant::new(3, 3, 2, TestRng(3)).unwrap();
+    let (commitments, shares) = p2.deal().unwrap();
+    let (own, _) = p1.deal().unwrap();
+
+    assert_eq!(p1.receive_commitments(&own), Err(ErrorCode::InvalidProtocolMessage));
+    assert_eq!(p1.receive_commitments(&commitments), Ok(()));
+    assert_eq!(p1.receive_commitments(&commitments), Ok(()));
+    assert_eq!(p1.receive_commitments(&p3.deal().unwrap().0), Ok(()));
+    let mut other = p3.deal().unwrap().0;
+    other.dealer = 2;
+    assert_eq!(p1.receive_commitments(&other), Err(ErrorCode::InvalidProtocolMessage));
+    other.points.pop();
+    assert_eq!(p1.receive_commitments(&other), Err(ErrorCode::InvalidProtocolMessage));
+
+    assert_eq!(p1.receive_share(&shares[1]), Err(ErrorCode::InvalidProtocolMessage));
+    assert_eq!(p1.receive_share(&shares[0]), Ok(()));
+    assert_eq!(p1.receive_share(&shares[0]), Err(ErrorCode::InvalidProtocolMessage));
+
+    // Dealer 3's share never arrived.
+    assert_eq!(p1.complaints(), vec![Complaint { accuser: 1, dealer: 3 }]);
+    assert_eq!(p1.receive_complaint(&Complaint { accuser: 2, dealer: 2 }), Err(ErrorCode::InvalidProtocolMessage));
+    assert_eq!(p1.receive_complaint(&Complaint { accuser: 4, dealer: 2 }), Err(ErrorCode::InvalidProtocolMessage));
+    assert_eq!(p1.receive_answer(&shares[0]), Err(ErrorCode::InvalidProtocolMessage));
+
+    // The value of a share stays out of debug output.
+    assert_eq!(std::format!("{:?}", shares[0]), "Share { dealer: 2, recipient: 1, .. }");
+}
+
+#[test]
+fn test_dkg_answers() {
+    // A forged answer delivered before the dealer's own is rejected, and
+    // does not lock the dealer's answer out.
+    let mut parties = fresh(3, 2);
+    let dealings: Vec<(Commitments, Vec<Share>)> = parties.iter().filter_map(|p| p.deal()).collect();
+    for (commitments, shares) in dealings.iter() {
+        for p in parties.iter_mut().filter(|p| p.dealer_index() != Some(commitments.dealer())) {
+            p.receive_commitments(commitments).unwrap();
+        }
+        for share in shares.iter().filter(|s| (s.dealer, s.recipient) != (2, 1)) {
+            parties[share.recipient as usize - 1].receive_share(share).unwrap();
+        }
+    }
+
+    let complaint = Complaint { accuser: 1, dealer: 2 };
+    assert_eq!(parties[0].complaints(), vec![complaint]);
+    for p in parties.iter_mut() {
+        p.receive_complaint(&complaint).unwrap();
+    }
+    let answer = parties[1].answers().pop().unwrap();
+    let mut forged = answer.clone();
+    forged.value += Scalar::one();
+    for p in parties.iter_mut() {
+        assert_eq!(p.receive_answer(&forged), Err(ErrorCode::VerificationFailed));
+        assert_eq!(p.receive_answer(&answer), Ok(()));
+        assert_eq!(p.receive_answer(&answer), Err(ErrorCode::InvalidProtocolMessage));
+    }
+    check_outputs(&unwrap_all(parties.into_iter().map(|p| p.finish()).collect()), &[1, 2, 3]);
+}
+
+#[test]
+fn test_reshare() {
+    use crate::bls::keys::TestRng;
+
+    let old = unwrap_all(run(fresh(5, 3), |_| {}, |_| true));
+    let member = |share: Option<&KeyShare>, index: Option<u32>, seed: u64| {
+        Participant::reshare(old[0].commitments(), 5, share, index, 6, 4, TestRng(seed)).unwrap()
+    };
+
+    // Old members 1, 2, 4 and 5 move the key to a committee of six with
+    // threshold four, while old member 3 is offline. Old members 4 and 1
+    // stay on as new members 1 and 2, and old members 2 and 5 leave. Dealer
+    // 2 sends new member 5 a bad share, but answers the complaint.
+    let parties = vec![
+        member(Some(old[3].share()), Some(1), 11),
+        member(Some(old[0].share()), Some(2), 12),
+        member(None, Some(3), 13),
+        member(None, Some(4), 14),
+        member(None, Some(5), 15),
+        member(None, Some(6), 16),
+        member(Some(old[1].share()), None, 17),
+        member(Some(old[4].share()), None, 18),
+    ];
+    let tamper = |s: &mut Share| {
+        if (s.dealer, s.recipient) == (2, 5) {
+            s.value += Scalar::one();
+        }
+    };
+    let new = unwrap_all(run(parties, tamper, |_| true));
+    check_outputs(&new, &[1, 2, 4, 5]);
+    assert_eq!(new[0].group_key(), old[0].group_key());
+    assert_eq!(new[0].commitments().len(), 4);
+
+    // The key can move on again, to a smaller threshold.
+    let parties = (1..=3).map(|j| {
+        let share = new.get(j as usize - 1).map(|o| o.share());
+        Participant::reshare(new[0].commitments(), 6, share, Some(j), 3, 2, TestRng(20 + u64::from(j))).unwrap()
+    });
+    let parties = parties.chain((4..=6).map(|i| {
+        Participant::reshare(new[0].commitments(), 6, Some(new[i - 1].share()), None, 3, 2, TestRng(20 + i as u64)).unwrap()
+    }));
+    let newer = unwrap_all(run(parties.collect(), |_| {}, |_| true));
+    check_outputs(&newer, &[1, 2, 3, 4, 5, 6]);
+    assert_eq!(newer[0].group_key(), old[0].group_key());
+
+    // Two dealers fall short of the old threshold of three.
+    let parties = vec![member(Some(old[0].share()), Some(1), 31), member(Some(old[1].share()), Some(2), 32)];
+    let parties = parties.into_iter().chain((3..=6).map(|j| member(None, Some(j), 30 + u64::from(j))));
+    for result in run(parties.collect(), |_| {}, |_| true) {
+        assert_eq!(result.err(), Some(ErrorCode::InvalidThreshold));
+    }
+}
+
+#[test]
+fn test_reshare_errors() {
+    use crate::bls::keys::TestRng;
+
+    let old = unwrap_all(run(fresh(3, 2), |_| {}, |_| true));
+    let other = unwrap_all(run(fresh(3, 3), |_| {}, |_| true));
+    let commitments = old[0].commitments();
+    let reshare = |share: Option<&KeyShare>, index: Option<u32>, participants: u32, threshold: usize| {
+        Participant::reshare(commitments, 3, share, index, participants, threshold, TestRng(1)).err()
+    };
+
+    assert_eq!(reshare(Some(old[0].share()), None, 4, 3), None);
+    assert_eq!(reshare(None, None, 4, 3), Some(ErrorCode::UnknownKey));
+    assert_eq!(reshare(Some(other[0].share()), None, 4, 3), Some(ErrorCode::VerificationFailed));
+    assert_eq!(reshare(None, Some(5), 4, 3), Some(ErrorCode::InvalidShareIndex));
+    assert_eq!(reshare(None, Some(1), 4, 5), Some(ErrorCode::InvalidThreshold));
+    assert_eq!(
+        Participant::reshare(commitments, 1, None, Some(1), 4, 3, TestRng(1)).err(),
+        Some(ErrorCode::InvalidThreshold)
+    );
+
+    // A member that only deals gets no share.
+    let dealer = Participant::reshare(commitments, 3, Some(old[0].share()), None, 4, 3, TestRng(1)).unwrap();
+    assert_eq!(dealer.complaints(), vec![]);
+    assert_eq!(dealer.finish().err(), Some(ErrorCode::UnknownKey));
+    let recipient = Participant::reshare(commitments, 3, None, Some(1), 4, 3, TestRng(1)).unwrap();
+    assert!(recipient.deal().is_none());
+}
+
+#[test]
+fn test_refresh() {
+    use crate::bls::threshold::combine;
+
+    let old = unwrap_all(run(fresh(4, 2), |_| {}, |_| true));
+
+    // Dealer 3 sends participant 1 a bad share and does not answer the
+    // complaint, so only its dealing is left out.
+    let parties = old.iter().map(|o| {
+        Participant::refresh(o.commitments(), 4, o.share(), crate::bls::keys::TestRng(40 + u64::from(o.share().index())))
+            .unwrap()
+    });
+    let tamper = |s: &mut Share| {
+        if (s.dealer, s.recipient) == (3, 1) {
+            s.value += Scalar::one();
+        }
+    };
+    let new = unwrap_all(run(parties.collect(), tamper, |s| s.dealer != 3));
+    check_outputs(&new, &[1, 2, 4]);
+    assert_eq!(new[0].group_key(), old[0].group_key());
+    assert!(new[0].commitments() != old[0].commitments());
+    for (o, n) in old.iter().zip(new.iter()) {
+        assert!(o.share().private_key().to_bytes() != n.share().private_key().to_bytes());
+    }
+
+    // Shares from before and after the refresh do not combine.
+    let msg = b"refresh";
+    let partials = [old[0].share().sign(msg).unwrap(), new[1].share().sign(msg).unwrap()];
+    assert_eq!(old[0].group_key().verify(msg, &combine(&partials, 2).unwrap()), Err(ErrorCode::VerificationFailed));
+}
+
+#[test]
+fn test_refresh_constant_term() {
+    use crate::bls::keys::TestRng;
+
+    // A dealer whose polynomial does not vanish at zero would change the
+    // group key, and is disqualified.
+    let old = unwrap_all(run(fresh(3, 2), |_| {}, |_| true));
+    let mut parties: Vec<Participant> =
+        old.iter().map(|o| Participant::refresh(o.commitments(), 3, o.share(), TestRng(50 + u64::from(o.share().index()))).unwrap()).collect();
+    let kind = Kind::Refresh { old: old[1].commitments().to_vec(), share: *old[1].share().private_key().as_scalar() };
+    let polynomial = Polynomial::random(&Scalar::one(), 1, TestRng(55));
+    parties[1] = Participant::start(Some((2, polynomial)), Some(2), 3, 3, 2, kind);
+    let new = unwrap_all(run(parties, |_| {}, |_| true));
+    check_outputs(&new, &[1, 3]);
+    assert_eq!(new[0].group_key(), old[0].group_key());
+}
+
+#[test]
+fn test_refresh_errors() {
+    use crate::bls::keys::TestRng;
+
+    // A stored share and commitments are all a refresh needs.
+    let old = unwrap_all(run(fresh(3, 2), |_| {}, |_| true));
+    let other = unwrap_all(run(fresh(3, 3), |_| {}, |_| true));
+    let commitments = old[0].commitments().to_vec();
+    let refresh = |participants: u32, share: &KeyShare| Participant::refresh(&commitments, participants, share, TestRng(1)).err();
+
+    assert_eq!(refresh(3, old[2].share()), None);
+    assert_eq!(refresh(1, old[0].share()), Some(ErrorCode::InvalidThreshold));
+    assert_eq!(refresh(2, old[2].share()), Some(ErrorCode::InvalidShareIndex));
+    assert_eq!(refresh(3, other[0].share()), Some(ErrorCode::VerificationFailed));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_message_serialization() {
+    use crate::bls::keys::TestRng;
+
+    let p = Participant::new(2, 3, 2, TestRng(5)).unwrap();
+    let (commitments, shares) = p.deal().unwrap();
+    let complaint = Complaint { accuser: 1, dealer: 2 };
+
+    let json = serde_json::to_string(&commitments).unwrap();
+    assert_eq!(serde_json::from_str::<Commitments>(&json).unwrap(), commitments);
+    let json = serde_json::to_string(&shares[0]).unwrap();
+    let share = serde_json::from_str::<Share>(&json).unwrap();
+    assert_eq!((share.dealer, share.recipient, share.value), (shares[0].dealer, shares[0].recipient, shares[0].value));
+    let json = serde_json::to_string(&complaint).unwrap();
+    assert_eq!(json, "[1,2]");
+    assert_eq!(serde_json::from_str::<Complaint>(&json).unwrap(), complaint);
+}
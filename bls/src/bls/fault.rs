@@ -0,0 +1,106 @@
+//! Fault-attack countermeasures for signing, enabled by the `fault_check`
+//! feature.
+//!
+//! A single glitched field operation during signing can produce a faulty
+//! signature from which the secret key can be recovered. Before a signature
+//! is released it is therefore recomputed with a scalar multiplication that
+//! processes the scalar in the opposite bit order, checked to be a
+//! non-identity point of the prime-order subgroup, and finally verified with
+//! a pairing against the public key, which catches a fault in the hash point
+//! or in the secret key as read for both multiplications.
+
+use bls12_377::{G1Affine, G1Projective, G2Affine, G2Projective, Scalar};
+use subtle::{Choice, ConditionallySelectable};
+#[cfg(feature = "zeroize")]
+use zeroize_crate::Zeroize;
+
+use crate::bls::keys::pairing_check;
+use crate::error::ErrorCode;
+
+/// Computes [s]p scanning the scalar from its least significant bit,
+/// whereas `G1Projective::multiply` starts from the most significant bit, so
+/// that no intermediate value is shared between the two computations.
+fn mul_lsb_first(p: &G1Affine, s: &Scalar) -> G1Projective {
+    let mut bytes = s.to_bytes();
+    let mut acc = G1Projective::identity();
+    let mut base = G1Projective::from(p);
+    for byte in bytes.iter() {
+        for i in 0..8 {
+            let bit = Choice::from((byte >> i) & 1);
+            acc = G1Projective::conditional_select(&acc, &(acc + base), bit);
+            base = base.double();
+        }
+    }
+
+    #[cfg(feature = "zeroize")]
+    bytes.zeroize();
+
+    acc
+}
+
+/// Checks that `sig` is [sk]hash before it is released, failing with
+/// `ErrorCode::FaultDetected` otherwise.
+pub(crate) fn check_signature(hash: &G1Affine, sk: &Scalar, sig: &G1Projective) -> Result<(), ErrorCode> {
+    if mul_lsb_first(hash, sk) != *sig {
+        return Err(ErrorCode::FaultDetected);
+    }
+
+    let sig = G1Affine::from(sig);
+    if !bool::from(sig.is_on_curve() & sig.is_torsion_free() & !sig.is_identity()) {
+        return Err(ErrorCode::FaultDetected);
+    }
+
+    let pk = G2Affine::from(G2Projective::generator() * sk);
+    if !pairing_check(hash, &pk, &sig) {
+        return Err(ErrorCode::FaultDetected);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_mul_lsb_first() {
+    let p = G1Affine::from(G1Projective::generator() * Scalar::from(3));
+    for s in [Scalar::zero(), Scalar::one(), Scalar::from(0xdead_beef), -Scalar::one()].iter() {
+        assert_eq!(mul_lsb_first(&p, s), p * s);
+    }
+}
+
+#[cfg(feature = "fault_injection")]
+#[test]
+fn test_faults_are_detected() {
+    use crate::bls::keys::PrivateKey;
+    use bls12_377::fp::fault_injection;
+
+    let key = PrivateKey::from_scalar(&Scalar::from_raw([0x0123_4567_89ab_cdef, 0xfedc_ba98_7654_3210, 0x0f1e_2d3c_4b5a_6978, 0x0102_0304_0506_0708]));
+    let hash = G1Affine::from(G1Projective::generator() * Scalar::from(3));
+    let expected_point = hash * key.as_scalar();
+    let expected = key.sign_point(&hash, None).unwrap();
+
+    let mut detected = 0;
+    for n in (1..4000).step_by(97) {
+        // Without the check, the fault reaches the signature.
+        fault_injection::inject_after(n);
+        let faulty = hash * key.as_scalar();
+        let reached = !fault_injection::is_armed() && faulty != expected_point;
+        fault_injection::inject_after(0);
+
+        fault_injection::inject_after(n);
+        let result = key.sign_point(&hash, None);
+        fault_injection::inject_after(0);
+
+        // A fault either lands in a discarded intermediate value and leaves
+        // the signature intact, or it is detected.
+        match result {
+            Ok(sig) => assert!(sig == expected),
+            Err(e) => {
+                assert_eq!(e, ErrorCode::FaultDetected);
+                detected += 1;
+            }
+        }
+        if reached {
+            assert!(result.is_err());
+        }
+    }
+    assert!(detected > 0);
+}
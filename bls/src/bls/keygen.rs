@@ -0,0 +1,186 @@
+use bls12_377::{G2Affine, Scalar};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256, Sha512};
+use sha3::Keccak256;
+#[cfg(feature = "zeroize")]
+use zeroize_crate::Zeroize;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// L = ceil((3 * ceil(log2(r))) / 16), the number of bytes of HKDF output
+/// reduced into a scalar.
+const L: usize = 48;
+
+/// Domain separation tag for the non-hardened derivation tweak.
+const NON_HARDENED_DST: &[u8] = b"BLS12377_NON_HARDENED_DERIVE_";
+
+/// Computes HKDF_mod_r(IKM, key_info) as specified by the KeyGen procedure of
+/// draft-irtf-cfrg-bls-signature (and, with an empty `key_info`, by EIP-2333):
+/// HKDF-SHA256 output is reduced modulo r, re-salting until the result is
+/// non-zero.
+pub(crate) fn hkdf_mod_r(ikm: &[u8], key_info: &[u8]) -> Scalar {
+    let mut salt = Sha256::digest(b"BLS-SIG-KEYGEN-SALT-");
+
+    loop {
+        // PRK = HKDF-Extract(salt, IKM || I2OSP(0, 1))
+        let mut mac = HmacSha256::new_varkey(&salt).unwrap();
+        mac.input(ikm);
+        mac.input(&[0]);
+        let prk = mac.result().code();
+
+        // OKM = HKDF-Expand(PRK, key_info || I2OSP(L, 2), L)
+        let mut okm = [0u8; 64];
+        let mut t = [0u8; 32];
+        for (i, chunk) in okm[..L].chunks_mut(32).enumerate() {
+            let mut mac = HmacSha256::new_varkey(&prk).unwrap();
+            if i > 0 {
+                mac.input(&t);
+            }
+            mac.input(key_info);
+            mac.input(&[(L >> 8) as u8, L as u8]);
+            mac.input(&[(i + 1) as u8]);
+            t.copy_from_slice(&mac.result().code());
+            chunk.copy_from_slice(&t[..chunk.len()]);
+        }
+
+        // SK = OS2IP(OKM) mod r, reading the big-endian OKM into a
+        // little-endian 512-bit buffer.
+        okm[..L].reverse();
+        let sk = Scalar::from_bytes_wide(&okm);
+
+        #[cfg(feature = "zeroize")]
+        {
+            okm.zeroize();
+            t.zeroize();
+        }
+
+        if sk != Scalar::zero() {
+            return sk;
+        }
+
+        salt = Sha256::digest(&salt);
+    }
+}
+
+/// Feeds the hashes of the 255 Lamport secret key chunks that EIP-2333
+/// derives from `ikm` and `salt` into `lamport_pk`.
+fn hash_lamport_sk(ikm: &[u8], salt: &[u8], lamport_pk: &mut Sha256) {
+    // PRK = HKDF-Extract(salt, IKM)
+    let mut mac = HmacSha256::new_varkey(salt).unwrap();
+    mac.input(ikm);
+    let prk = mac.result().code();
+
+    // OKM = HKDF-Expand(PRK, "", 255 * 32), consumed one chunk at a time
+    let mut t = [0u8; 32];
+    for i in 1..=255u8 {
+        let mut mac = HmacSha256::new_varkey(&prk).unwrap();
+        if i > 1 {
+            mac.input(&t);
+        }
+        mac.input(&[i]);
+        t.copy_from_slice(&mac.result().code());
+
+        lamport_pk.input(Sha256::digest(&t));
+    }
+
+    #[cfg(feature = "zeroize")]
+    t.zeroize();
+}
+
+/// Computes the compressed Lamport public key of EIP-2333 for the given
+/// parent secret key and child index.
+fn parent_sk_to_lamport_pk(parent_sk: &Scalar, index: u32) -> [u8; 32] {
+    let salt = index.to_be_bytes();
+
+    // IKM = I2OSP(parent_SK, 32)
+    let mut ikm = parent_sk.to_bytes();
+    ikm.reverse();
+
+    let mut lamport_pk = Sha256::new();
+    hash_lamport_sk(&ikm, &salt, &mut lamport_pk);
+    for b in ikm.iter_mut() {
+        *b = !*b;
+    }
+    hash_lamport_sk(&ikm, &salt, &mut lamport_pk);
+
+    #[cfg(feature = "zeroize")]
+    ikm.zeroize();
+
+    let mut res = [0u8; 32];
+    res.copy_from_slice(&lamport_pk.result());
+    res
+}
+
+/// Derives the hardened child secret key at `index` from `parent_sk`, as
+/// specified by EIP-2333.
+pub(crate) fn derive_child_sk(parent_sk: &Scalar, index: u32) -> Scalar {
+    hkdf_mod_r(&parent_sk_to_lamport_pk(parent_sk, index), b"")
+}
+
+/// Computes the tweak H(pk, i) shared by the non-hardened secret and public
+/// key derivations: SHA-512 over the compressed parent public key and the
+/// index, reduced into a scalar.
+pub(crate) fn non_hardened_tweak(parent_pk: &G2Affine, index: u32) -> Scalar {
+    let mut h = Sha512::new();
+    h.input(NON_HARDENED_DST);
+    h.input(&parent_pk.to_compressed_le()[..]);
+    h.input(index.to_be_bytes());
+
+    let mut buf = [0u8; 64];
+    buf.copy_from_slice(&h.result());
+    Scalar::from_bytes_wide(&buf)
+}
+
+/// Reproduces celo-blockchain's `ECDSAToBLS`: hashes
+/// `"ecdsatobls" || I2OSP(i, 1) || ecdsa_sk` with Keccak-256, masks the result
+/// to 253 bits and returns the first canonical scalar, trying each counter `i`
+/// in turn.
+pub(crate) fn ecdsa_to_bls(ecdsa_sk: &[u8; 32]) -> Option<Scalar> {
+    for i in 0..=255u8 {
+        let mut h = Keccak256::new();
+        h.input(b"ecdsatobls");
+        h.input([i]);
+        h.input(&ecdsa_sk[..]);
+
+        let mut bytes = [0u8; 32];
+        bytes.copy_from_slice(&h.result());
+        bytes[0] &= 0x1f;
+
+        // The hash is read as a big-endian integer.
+        bytes.reverse();
+        let sk = Scalar::from_bytes(&bytes);
+
+        #[cfg(feature = "zeroize")]
+        bytes.zeroize();
+
+        if bool::from(sk.is_some()) {
+            return Some(sk.unwrap());
+        }
+    }
+
+    None
+}
+
+#[test]
+fn test_hkdf_mod_r() {
+    let mut ikm = [0u8; 32];
+    for (i, b) in ikm.iter_mut().enumerate() {
+        *b = i as u8;
+    }
+    assert_eq!(
+        &hkdf_mod_r(&ikm, b"").to_bytes()[..],
+        &[
+            0xa3, 0x15, 0x3b, 0x08, 0xb7, 0x86, 0xe2, 0x23, 0x49, 0x90, 0x82, 0x45, 0x12, 0x06, 0xfc,
+            0x0f, 0x92, 0xdb, 0xa1, 0x70, 0xc3, 0xc6, 0x0c, 0xef, 0x88, 0x88, 0x48, 0x3e, 0x92, 0xf1,
+            0x9f, 0x11
+        ][..]
+    );
+    assert_eq!(
+        &hkdf_mod_r(&ikm, b"BLS12-377 key").to_bytes()[..],
+        &[
+            0x11, 0x59, 0x1d, 0x95, 0xfa, 0xf6, 0xe3, 0x5b, 0xdf, 0x00, 0x22, 0x61, 0xda, 0xfa, 0x21,
+            0x5a, 0x0e, 0x80, 0xa4, 0xe0, 0x86, 0x9c, 0x99, 0x66, 0x30, 0xaf, 0xf8, 0xf9, 0x6e, 0x40,
+            0xb6, 0x0c
+        ][..]
+    );
+}
@@ -1,6 +1,21 @@
-use bls12_377::{Scalar, G1Affine, G2Affine, G1Projective, G2Projective};
+use bls12_377::fp::Fp;
+use bls12_377::hash_to_curve::{ExpandMsgXmd, HashToCurve};
+use bls12_377::{multi_miller_loop, Blinding, Scalar, G1Affine, G2Affine, G1Projective, G2Prepared, G2Projective};
+use crate::bls::blinding::blinding_bytes;
+use crate::bls::keygen::{derive_child_sk, ecdsa_to_bls, hkdf_mod_r, non_hardened_tweak};
+#[cfg(feature = "fault_check")]
+use crate::bls::fault::check_signature;
 use crate::error::ErrorCode;
-use core::ops::Mul;
+use core::ops::{AddAssign, Mul};
+#[cfg(feature = "rand_core")]
+use rand_core_crate::{CryptoRng, RngCore};
+#[cfg(feature = "serde")]
+use serde_crate::{Deserialize, Deserializer, Serialize, Serializer};
+use sha2::Sha256;
+#[cfg(all(feature = "std", feature = "rand_core"))]
+use std::vec::Vec;
+#[cfg(feature = "zeroize")]
+use zeroize_crate::{Zeroize, ZeroizeOnDrop};
 
 pub struct PrivateKey {
     sk: Scalar,
@@ -11,17 +26,329 @@ impl PrivateKey {
         Self { sk: s.clone() }
     }
 
+    /// Decodes a secret key from its canonical 32-byte little-endian
+    /// encoding, as used by Celo. Zero and values of at least r are rejected.
+    pub fn from_bytes(bytes: &[u8; 32]) -> Result<Self, ErrorCode> {
+        let sk: Scalar = Option::from(Scalar::from_bytes(bytes)).ok_or(ErrorCode::NonCanonicalScalar)?;
+        if sk == Scalar::zero() {
+            return Err(ErrorCode::ZeroKey);
+        }
+
+        Ok(Self { sk })
+    }
+
+    /// Returns the canonical 32-byte little-endian encoding of the secret
+    /// key.
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.sk.to_bytes()
+    }
+
+    /// Deterministically derives a secret key from at least 32 bytes of
+    /// input keying material, following the KeyGen procedure of
+    /// draft-irtf-cfrg-bls-signature.
+    pub fn key_gen(ikm: &[u8], key_info: &[u8]) -> Result<Self, ErrorCode> {
+        if ikm.len() < 32 {
+            return Err(ErrorCode::BadLength);
+        }
+
+        Ok(Self { sk: hkdf_mod_r(ikm, key_info) })
+    }
+
+    /// Derives the secret key that celo-blockchain's `ECDSAToBLS` produces
+    /// from a big-endian secp256k1 secret key.
+    pub fn from_ecdsa_private_key(ecdsa_sk: &[u8; 32]) -> Result<Self, ErrorCode> {
+        ecdsa_to_bls(ecdsa_sk)
+            .map(|sk| Self { sk })
+            .ok_or(ErrorCode::NonCanonicalScalar)
+    }
+
+    /// Derives the master secret key of an EIP-2333 key tree from a seed of
+    /// at least 32 bytes.
+    pub fn derive_master_sk(seed: &[u8]) -> Result<Self, ErrorCode> {
+        Self::key_gen(seed, b"")
+    }
+
+    /// Derives the hardened child secret key at `index`, as specified by
+    /// EIP-2333.
+    pub fn derive_child_sk(&self, index: u32) -> Self {
+        Self { sk: derive_child_sk(&self.sk, index) }
+    }
+
+    /// Derives the secret key at `path` in the EIP-2333 key tree rooted at
+    /// `seed`. The path takes the form `m/12381/3600/0/0`, where every
+    /// component is a decimal child index below 2^32.
+    pub fn derive_path(seed: &[u8], path: &str) -> Result<Self, ErrorCode> {
+        let parse_index = |c: &str| -> Result<u32, ErrorCode> {
+            if c.is_empty() || !c.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(ErrorCode::InvalidPath);
+            }
+            c.parse().map_err(|_| ErrorCode::InvalidPath)
+        };
+
+        // Validate the whole path before doing any derivation.
+        let mut components = path.split('/');
+        if components.next() != Some("m") {
+            return Err(ErrorCode::InvalidPath);
+        }
+        for c in components {
+            parse_index(c)?;
+        }
+
+        let mut key = Self::derive_master_sk(seed)?;
+        for c in path.split('/').skip(1) {
+            key = key.derive_child_sk(parse_index(c)?);
+        }
+
+        Ok(key)
+    }
+
+    /// Derives the non-hardened child secret key at `index`, sk + H(pk, i).
+    /// Unlike `derive_child_sk`, the matching public key can be computed from
+    /// the parent public key alone with `PublicKey::derive_child_pk_non_hardened`.
+    /// **A non-hardened child secret key together with the parent public key
+    /// reveals the parent secret key.**
+    pub fn derive_child_sk_non_hardened(&self, index: u32) -> Self {
+        let pk = G2Affine::from(G2Projective::generator() * self.sk);
+        Self { sk: self.sk + non_hardened_tweak(&pk, index) }
+    }
+
+    #[cfg(feature = "rand_core")]
+    pub fn generate(rng: impl RngCore + CryptoRng) -> Self {
+        Self { sk: Scalar::random(rng) }
+    }
+
+    pub(crate) fn as_scalar(&self) -> &Scalar {
+        &self.sk
+    }
+
     pub fn to_public(&self) -> PublicKey {
         PublicKey::from_pk(&(G2Projective::generator() * &self.sk))
     }
 
+    /// Signs a message, which is hashed to G1 with `hash_message`.
+    pub fn sign(&self, msg: &[u8]) -> Result<Signature, ErrorCode> {
+        self.sign_point(&hash_message(msg), None)
+    }
+
+    /// Proves possession of the secret key by signing the public key. Other
+    /// parties check the proof with `PublicKey::verify_possession` before
+    /// aggregating the key in `fast_aggregate_verify`.
+    pub fn prove_possession(&self) -> Result<Signature, ErrorCode> {
+        self.sign_point(&hash_public_key(&self.to_public()), None)
+    }
+
+    /// Signs a hash that has already been mapped to G1, given in the
+    /// uncompressed little-endian encoding of `to_uncompressed_littleendian`.
+    /// The point must be on the curve, in the prime-order subgroup and not
+    /// the identity; anything else is rejected before it meets the secret key.
     #[inline(always)]
     pub fn sign_hash(&self, hash: &[u8; 96]) -> Result<Signature, ErrorCode> {
-       let hash_elem = G1Affine::from_uncompressed_unchecked_vartime(hash).unwrap(); 
-       Ok(Signature::from_sig(&hash_elem.mul(&self.sk)))
+        let hash_elem = decode_hash_point(hash)?;
+        self.sign_point(&hash_elem, None)
+    }
+
+    /// Signs a hash point without checking that it is on the curve or in the
+    /// prime-order subgroup, saving the cost of the subgroup check. The
+    /// encoding is that of `sign_hash`, and the identity is still rejected.
+    /// **This is dangerous to call unless the hash point was computed by
+    /// trusted code: multiplying the secret key by an invalid or small-order
+    /// point can reveal it.** Please consider using `sign_hash()` instead.
+    #[inline(always)]
+    pub fn sign_hash_unchecked(&self, hash: &[u8; 96]) -> Result<Signature, ErrorCode> {
+        let hash_elem: G1Affine = Option::from(G1Affine::from_uncompressed_le_unchecked(hash))
+            .ok_or(ErrorCode::InvalidEncoding)?;
+        if bool::from(hash_elem.is_identity()) {
+            return Err(ErrorCode::IdentityPoint);
+        }
+        self.sign_point(&hash_elem, None)
+    }
+
+    /// Like `sign_hash`, but randomizes the scalar multiplication with
+    /// `blinding` against power and electromagnetic analysis. The signature
+    /// is the same as with `sign_hash`. `blinding` should be fresh for every
+    /// signature: use `Blinding::random`, or `derive_blinding` when no RNG is
+    /// available.
+    #[inline(always)]
+    pub fn sign_hash_blinded(&self, hash: &[u8; 96], blinding: &Blinding) -> Result<Signature, ErrorCode> {
+        let hash_elem = decode_hash_point(hash)?;
+        self.sign_point(&hash_elem, Some(blinding))
+    }
+
+    /// Derives the blinding values for signing `hash` deterministically from
+    /// the secret key, the hash and optional `entropy`, for devices without
+    /// an RNG. Without entropy, repeated signatures of the same hash reuse
+    /// the same blinding, so any entropy available, even of poor quality,
+    /// should be passed in.
+    pub fn derive_blinding(&self, hash: &[u8; 96], entropy: Option<&[u8; 32]>, point_blinding: bool) -> Blinding {
+        #[allow(unused_mut)]
+        let mut okm = blinding_bytes(&self.sk, hash, entropy.unwrap_or(&[0; 32]));
+        let mut bytes = [0u8; 80];
+        bytes.copy_from_slice(&okm[..80]);
+        let mut blinding = Blinding::from_bytes(&bytes);
+        if point_blinding {
+            let mut wide = [0u8; 64];
+            wide[..48].copy_from_slice(&okm[80..]);
+            blinding = blinding.with_point_blinding(&wide);
+
+            #[cfg(feature = "zeroize")]
+            wide.zeroize();
+        }
+
+        #[cfg(feature = "zeroize")]
+        {
+            okm.zeroize();
+            bytes.zeroize();
+        }
+
+        blinding
+    }
+
+    /// Multiplies the hash point by the secret key, blinded if `blinding` is
+    /// given. With the `fault_check` feature the result is checked before it
+    /// is released; see `bls::fault`.
+    #[inline(always)]
+    pub(crate) fn sign_point(&self, hash: &G1Affine, blinding: Option<&Blinding>) -> Result<Signature, ErrorCode> {
+        let sig = match blinding {
+            Some(blinding) => G1Projective::from(hash).mul_blinded(&self.sk, blinding),
+            None => hash.mul(&self.sk),
+        };
+
+        #[cfg(feature = "fault_check")]
+        check_signature(hash, &self.sk, &sig)?;
+
+        Ok(Signature::from_sig(&sig))
+    }
+}
+
+/// Decodes an uncompressed little-endian G1 point, accepting only points of
+/// the prime-order subgroup.
+fn decode_g1_uncompressed(bytes: &[u8; 96]) -> Result<G1Affine, ErrorCode> {
+    let p: G1Affine = Option::from(G1Affine::from_uncompressed_le_unchecked(bytes))
+        .ok_or(ErrorCode::InvalidEncoding)?;
+    if !bool::from(p.is_on_curve()) {
+        return Err(ErrorCode::NotOnCurve);
+    }
+    if !bool::from(p.is_torsion_free()) {
+        return Err(ErrorCode::NotInSubgroup);
+    }
+
+    Ok(p)
+}
+
+/// Decodes a hash point for signing, accepting only non-identity points of
+/// the prime-order subgroup.
+fn decode_hash_point(hash: &[u8; 96]) -> Result<G1Affine, ErrorCode> {
+    let p = decode_g1_uncompressed(hash)?;
+    if bool::from(p.is_identity()) {
+        return Err(ErrorCode::IdentityPoint);
+    }
+
+    Ok(p)
+}
+
+/// Tells why a compressed little-endian point, made of one or two 48-byte
+/// coordinates with the flags in the top bits of the last byte, failed to
+/// decode: malformed flags or a non-canonical x are `InvalidEncoding`, and a
+/// well-formed x with no point of the curve above it is `NotOnCurve`.
+fn compressed_point_error(bytes: &[u8]) -> ErrorCode {
+    // A well-formed encoding of the identity always decodes.
+    match bytes.last() {
+        Some(b) if b & 0b0100_0000 == 0 => {}
+        _ => return ErrorCode::InvalidEncoding,
+    }
+
+    let limbs = bytes.len() / 48;
+    for (i, chunk) in bytes.chunks_exact(48).enumerate() {
+        let mut tmp = [0u8; 48];
+        tmp.copy_from_slice(chunk);
+        if i + 1 == limbs {
+            tmp[47] &= 0b0011_1111;
+        }
+        if Fp::from_bytes_little_endian_vartime(&tmp).is_none() {
+            return ErrorCode::InvalidEncoding;
+        }
+    }
+
+    ErrorCode::NotOnCurve
+}
+
+/// The domain separation tag with which messages are hashed to G1: the
+/// basic scheme of draft-irtf-cfrg-bls-signature over the RFC 9380 suite
+/// `BLS12377G1_XMD:SHA-256_SSWU_RO_`.
+pub const MESSAGE_DST: &[u8] = b"BLS_SIG_BLS12377G1_XMD:SHA-256_SSWU_RO_NUL_";
+
+/// The domain separation tag with which public keys are hashed to G1 for
+/// proofs of possession, as in the proof-of-possession scheme of
+/// draft-irtf-cfrg-bls-signature. It keeps a proof from ever being a
+/// signature of a message.
+pub const POP_DST: &[u8] = b"BLS_POP_BLS12377G1_XMD:SHA-256_SSWU_RO_POP_";
+
+/// Hashes a message to the G1 point that `PrivateKey::sign` signs.
+pub fn hash_message(msg: &[u8]) -> G1Affine {
+    G1Affine::from(<G1Projective as HashToCurve<ExpandMsgXmd<Sha256>>>::hash_to_curve(msg, MESSAGE_DST))
+}
+
+/// Hashes a public key to the G1 point signed by its proof of possession.
+fn hash_public_key(pk: &PublicKey) -> G1Affine {
+    G1Affine::from(<G1Projective as HashToCurve<ExpandMsgXmd<Sha256>>>::hash_to_curve(&pk.to_compressed(), POP_DST))
+}
+
+/// Checks e(sig, g2) = e(hash, pk) with one Miller loop over both pairings
+/// and a single final exponentiation.
+pub(crate) fn pairing_check(hash: &G1Affine, pk: &G2Affine, sig: &G1Affine) -> bool {
+    let neg_g2 = G2Prepared::from(-G2Affine::generator());
+    let pk = G2Prepared::from(*pk);
+    bool::from(multi_miller_loop(&[(sig, &neg_g2), (hash, &pk)]).final_exponentiation().is_identity())
+}
+
+/// The number of terms handed to each multi-scalar multiplication, which
+/// bounds the stack space used for them.
+const MSM_CHUNK: usize = 16;
+
+/// Computes Σ s_i P_i over the `terms` (P_i, s_i) with `msm`, a chunk at a
+/// time so that no allocation is needed. Fails with the first term that is
+/// an error.
+pub(crate) fn msm_chunked<A, P>(
+    mut terms: impl Iterator<Item = Result<(A, Scalar), ErrorCode>>,
+    msm: fn(&[(A, Scalar)]) -> P,
+) -> Result<P, ErrorCode>
+where
+    A: Copy + Default,
+    P: From<A> + AddAssign,
+{
+    let mut acc = P::from(A::default());
+    loop {
+        let mut chunk = [(A::default(), Scalar::zero()); MSM_CHUNK];
+        let mut n = 0;
+        for term in terms.by_ref().take(MSM_CHUNK) {
+            chunk[n] = term?;
+            n += 1;
+        }
+        acc += msm(&chunk[..n]);
+        if n < MSM_CHUNK {
+            return Ok(acc);
+        }
     }
 }
 
+// The secret scalar is wiped when a `PrivateKey` goes out of scope.
+#[cfg(feature = "zeroize")]
+impl Zeroize for PrivateKey {
+    fn zeroize(&mut self) {
+        self.sk.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for PrivateKey {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for PrivateKey {}
+
 #[derive(Copy, Clone, Debug)]
 pub struct PublicKey {
     pk: G2Projective,
@@ -32,10 +359,80 @@ impl PublicKey {
         PublicKey { pk: pk.clone() }
     }
 
+    pub(crate) fn as_point(&self) -> &G2Projective {
+        &self.pk
+    }
+
     #[inline(always)]
     pub fn serialize(&self) -> [u8; 192] {
         G2Affine::from(&self.pk).to_uncompressed_littleendian()
     }
+
+    /// Returns the compressed little-endian encoding of the public key.
+    pub fn to_compressed(&self) -> [u8; 96] {
+        G2Affine::from(&self.pk).to_compressed_le()
+    }
+
+    /// Decodes a compressed little-endian public key, checking that it lies
+    /// in the prime-order subgroup and is not the identity.
+    pub fn from_compressed(bytes: &[u8; 96]) -> Result<PublicKey, ErrorCode> {
+        let pk = G2Affine::from_compressed_le_unchecked(bytes).ok_or_else(|| compressed_point_error(bytes))?;
+        if !bool::from(pk.is_torsion_free()) {
+            return Err(ErrorCode::NotInSubgroup);
+        }
+        if bool::from(pk.is_identity()) {
+            return Err(ErrorCode::IdentityPoint);
+        }
+
+        Ok(PublicKey::from_pk(&G2Projective::from(pk)))
+    }
+
+    /// Derives the non-hardened child public key at `index`, pk + H(pk, i)·g2,
+    /// without knowledge of the secret key. It matches the public key of
+    /// `PrivateKey::derive_child_sk_non_hardened`.
+    pub fn derive_child_pk_non_hardened(&self, index: u32) -> PublicKey {
+        let tweak = non_hardened_tweak(&G2Affine::from(&self.pk), index);
+        PublicKey { pk: self.pk + G2Projective::generator() * tweak }
+    }
+
+    /// Verifies a signature made by `PrivateKey::sign` on `msg`. The
+    /// identity public key is rejected: with the identity signature it
+    /// would verify for every message. So is a signature outside the
+    /// prime-order subgroup, which could otherwise be a valid signature
+    /// plus a point of small order that the pairing does not see.
+    pub fn verify(&self, msg: &[u8], sig: &Signature) -> Result<(), ErrorCode> {
+        if bool::from(self.pk.is_identity()) {
+            return Err(ErrorCode::IdentityPoint);
+        }
+        let sig = G1Affine::from(&sig.sig);
+        if !bool::from(sig.is_torsion_free()) {
+            return Err(ErrorCode::NotInSubgroup);
+        }
+        if !pairing_check(&hash_message(msg), &G2Affine::from(&self.pk), &sig) {
+            return Err(ErrorCode::VerificationFailed);
+        }
+
+        Ok(())
+    }
+
+    /// Checks a proof of possession made by `PrivateKey::prove_possession`,
+    /// returning the key in the form `fast_aggregate_verify` accepts. Like
+    /// signatures in `verify`, proofs outside the prime-order subgroup are
+    /// rejected.
+    pub fn verify_possession(&self, proof: &Signature) -> Result<VerifiedPublicKey, ErrorCode> {
+        if bool::from(self.pk.is_identity()) {
+            return Err(ErrorCode::IdentityPoint);
+        }
+        let proof = G1Affine::from(&proof.sig);
+        if !bool::from(proof.is_torsion_free()) {
+            return Err(ErrorCode::NotInSubgroup);
+        }
+        if !pairing_check(&hash_public_key(self), &G2Affine::from(&self.pk), &proof) {
+            return Err(ErrorCode::VerificationFailed);
+        }
+
+        Ok(VerifiedPublicKey { pk: *self })
+    }
 }
 impl Eq for PublicKey {}
 impl PartialEq for PublicKey {
@@ -51,14 +448,40 @@ pub struct Signature {
 
 impl Signature {
     #[inline(always)]
-    pub fn from_sig(sig: &G1Projective) -> Signature {
+    pub(crate) fn from_sig(sig: &G1Projective) -> Signature {
         Signature { sig: sig.clone() }
     }
 
+    pub(crate) fn as_point(&self) -> &G1Projective {
+        &self.sig
+    }
+
     #[inline(always)]
     pub fn serialize(&self) -> [u8; 96] {
         G1Affine::from(self.sig).to_uncompressed_littleendian()
     }
+
+    /// Decodes an uncompressed little-endian signature, as produced by
+    /// `serialize`, checking that it lies in the prime-order subgroup.
+    pub fn from_uncompressed(bytes: &[u8; 96]) -> Result<Signature, ErrorCode> {
+        decode_g1_uncompressed(bytes).map(|sig| Signature::from_sig(&G1Projective::from(sig)))
+    }
+
+    /// Returns the compressed little-endian encoding of the signature.
+    pub fn to_compressed(&self) -> [u8; 48] {
+        G1Affine::from(self.sig).to_compressed_le()
+    }
+
+    /// Decodes a compressed little-endian signature, checking that it lies
+    /// in the prime-order subgroup.
+    pub fn from_compressed(bytes: &[u8; 48]) -> Result<Signature, ErrorCode> {
+        let sig = G1Affine::from_compressed_le_unchecked(bytes).ok_or_else(|| compressed_point_error(bytes))?;
+        if !bool::from(sig.is_torsion_free()) {
+            return Err(ErrorCode::NotInSubgroup);
+        }
+
+        Ok(Signature::from_sig(&G1Projective::from(sig)))
+    }
 }
 impl Eq for Signature {} impl PartialEq for Signature {
     fn eq(&self, other: &Self) -> bool {
@@ -66,6 +489,247 @@ impl Eq for Signature {} impl PartialEq for Signature {
     }
 }
 
+impl Signature {
+    /// Aggregates signatures into one by adding them up.
+    pub fn aggregate(sigs: &[Signature]) -> Signature {
+        Signature { sig: sigs.iter().fold(G1Projective::identity(), |acc, s| acc + s.sig) }
+    }
+}
+
+/// A public key whose proof of possession has been checked with
+/// `PublicKey::verify_possession`. Only such keys can be aggregated by
+/// `fast_aggregate_verify`: a rogue key, chosen as a function of the other
+/// keys so that the aggregate is a key its owner controls, has no proof.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct VerifiedPublicKey {
+    pk: PublicKey,
+}
+
+impl VerifiedPublicKey {
+    /// Returns the public key.
+    pub fn public_key(&self) -> &PublicKey {
+        &self.pk
+    }
+}
+
+/// A list of pairwise distinct messages, as `aggregate_verify` requires:
+/// without proofs of possession, a rogue key can only forge an aggregate
+/// signature that includes an honest key's signature on the same message.
+#[derive(Copy, Clone, Debug)]
+pub struct DistinctMessages<'a> {
+    msgs: &'a [&'a [u8]],
+}
+
+impl<'a> DistinctMessages<'a> {
+    /// Checks that `msgs` are pairwise distinct, failing with
+    /// `ErrorCode::DuplicateMessage` otherwise. This compares every pair, so
+    /// it takes time quadratic in the number of messages, but needs no
+    /// allocation.
+    pub fn new(msgs: &'a [&'a [u8]]) -> Result<Self, ErrorCode> {
+        for (i, m) in msgs.iter().enumerate() {
+            if msgs[i + 1..].contains(m) {
+                return Err(ErrorCode::DuplicateMessage);
+            }
+        }
+
+        Ok(DistinctMessages { msgs })
+    }
+
+    /// Returns the messages.
+    pub fn as_slice(&self) -> &'a [&'a [u8]] {
+        self.msgs
+    }
+}
+
+/// Verifies an aggregate of signatures made on the same message by every
+/// key in `keys`. The keys are added up, so this takes a single pairing
+/// check whatever their number.
+pub fn fast_aggregate_verify(keys: &[VerifiedPublicKey], msg: &[u8], sig: &Signature) -> Result<(), ErrorCode> {
+    if keys.is_empty() {
+        return Err(ErrorCode::BadLength);
+    }
+
+    let pk = keys.iter().fold(G2Projective::identity(), |acc, k| acc + k.pk.pk);
+    PublicKey::from_pk(&pk).verify(msg, sig)
+}
+
+/// Verifies an aggregate of signatures made by `keys[i]` on `msgs[i]`, with
+/// one Miller loop per key and a single final exponentiation. As in
+/// `PublicKey::verify`, a signature outside the prime-order subgroup is
+/// rejected.
+pub fn aggregate_verify(keys: &[PublicKey], msgs: &DistinctMessages, sig: &Signature) -> Result<(), ErrorCode> {
+    if keys.is_empty() || keys.len() != msgs.msgs.len() {
+        return Err(ErrorCode::BadLength);
+    }
+    if keys.iter().any(|k| bool::from(k.pk.is_identity())) {
+        return Err(ErrorCode::IdentityPoint);
+    }
+    let sig = G1Affine::from(&sig.sig);
+    if !bool::from(sig.is_torsion_free()) {
+        return Err(ErrorCode::NotInSubgroup);
+    }
+
+    let neg_g2 = G2Prepared::from(-G2Affine::generator());
+    let mut f = multi_miller_loop(&[(&sig, &neg_g2)]);
+    for (pk, msg) in keys.iter().zip(msgs.msgs.iter()) {
+        let pk = G2Prepared::from(G2Affine::from(&pk.pk));
+        f += multi_miller_loop(&[(&hash_message(msg), &pk)]);
+    }
+    if !bool::from(f.final_exponentiation().is_identity()) {
+        return Err(ErrorCode::VerificationFailed);
+    }
+
+    Ok(())
+}
+
+/// Verifies a batch of signatures at a fraction of the cost of verifying
+/// them one by one, returning the indices of the entries that do not verify.
+///
+/// Each entry i is weighted by a random 128-bit scalar r_i, and the batch
+/// passes if e(Σ r_i σ_i, -g2) · Π e(r_i H(m_i), pk_i) = 1, which takes one
+/// multi-Miller loop and one final exponentiation. The weights are public,
+/// so they are applied in variable time over their 128 bits only. Without the weights,
+/// invalid signatures could be crafted to cancel out. If the batch fails, it
+/// is split in halves until every invalid entry is found, so a few bad
+/// signatures cost a few extra checks per bad entry rather than a full
+/// verification of every entry.
+#[cfg(all(feature = "std", feature = "rand_core"))]
+pub fn verify_batch(
+    batch: &[(PublicKey, &[u8], Signature)],
+    mut rng: impl RngCore + CryptoRng,
+) -> Result<(), Vec<usize>> {
+    let mut checker = BatchChecker {
+        neg_g2: G2Prepared::from(-G2Affine::generator()),
+        hashes: Vec::with_capacity(batch.len()),
+        keys: Vec::with_capacity(batch.len()),
+        sigs: Vec::with_capacity(batch.len()),
+        valid: Vec::with_capacity(batch.len()),
+    };
+    for (pk, msg, sig) in batch.iter() {
+        // The lowest bit is set so that no entry drops out of the check.
+        let r = u128::from(rng.next_u64() | 1) | u128::from(rng.next_u64()) << 64;
+        checker.hashes.push(G1Affine::from(msm_u128_vartime(&[(hash_message(msg), r)])));
+        checker.keys.push(G2Prepared::from(G2Affine::from(&pk.pk)));
+        let sig = G1Affine::from(&sig.sig);
+        checker.sigs.push((sig, r));
+        checker.valid.push(!bool::from(pk.pk.is_identity()) && bool::from(sig.is_torsion_free()));
+    }
+
+    let mut bad = Vec::new();
+    if !checker.check(0, batch.len()) {
+        checker.bisect(0, batch.len(), &mut bad);
+    }
+
+    if bad.is_empty() {
+        Ok(())
+    } else {
+        Err(bad)
+    }
+}
+
+/// Computes Σ [r_i] P_i over the `terms` (P_i, r_i) with one shared
+/// double-and-add over the bits of the 128-bit r_i. The r_i are public, so
+/// this skips the additions for unset bits and the doublings above the top
+/// bit, and takes time that depends on them.
+#[cfg(all(feature = "std", feature = "rand_core"))]
+fn msm_u128_vartime(terms: &[(G1Affine, u128)]) -> G1Projective {
+    let top = terms.iter().fold(0, |acc, (_, r)| acc | r);
+    let mut acc = G1Projective::identity();
+    for i in (0..128 - top.leading_zeros()).rev() {
+        acc = acc.double();
+        for (p, r) in terms.iter() {
+            if (r >> i) & 1 == 1 {
+                acc = acc.add_mixed(p);
+            }
+        }
+    }
+
+    acc
+}
+
+/// The weighted terms of `verify_batch`, from which any contiguous range of
+/// the batch can be checked.
+#[cfg(all(feature = "std", feature = "rand_core"))]
+struct BatchChecker {
+    neg_g2: G2Prepared,
+    hashes: Vec<G1Affine>,
+    keys: Vec<G2Prepared>,
+    sigs: Vec<(G1Affine, u128)>,
+    /// Whether each key is not the identity and each signature lies in the
+    /// prime-order subgroup, which the pairing check alone cannot tell.
+    valid: Vec<bool>,
+}
+
+#[cfg(all(feature = "std", feature = "rand_core"))]
+impl BatchChecker {
+    /// Checks the entries `start..end`.
+    fn check(&self, start: usize, end: usize) -> bool {
+        if !self.valid[start..end].iter().all(|v| *v) {
+            return false;
+        }
+
+        let sig = G1Affine::from(msm_u128_vartime(&self.sigs[start..end]));
+        let mut terms: Vec<(&G1Affine, &G2Prepared)> = self.hashes[start..end].iter().zip(self.keys[start..end].iter()).collect();
+        terms.push((&sig, &self.neg_g2));
+
+        bool::from(multi_miller_loop(&terms).final_exponentiation().is_identity())
+    }
+
+    /// Adds the invalid entries of `start..end`, which is known to fail, to
+    /// `bad`.
+    fn bisect(&self, start: usize, end: usize, bad: &mut Vec<usize>) {
+        if end - start == 1 {
+            bad.push(start);
+            return;
+        }
+
+        // If the first half passes, the second half must be the one that
+        // fails, and need not be checked.
+        let mid = start + (end - start) / 2;
+        if self.check(start, mid) {
+            self.bisect(mid, end, bad);
+        } else {
+            self.bisect(start, mid, bad);
+            if !self.check(mid, end) {
+                self.bisect(mid, end, bad);
+            }
+        }
+    }
+}
+
+// Keys and signatures are encoded as their compressed affine points; see
+// the `serde` feature of `bls12_377` for the exact representation.
+#[cfg(feature = "serde")]
+impl Serialize for PublicKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        G2Affine::from(&self.pk).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for PublicKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        // Keys go through `from_compressed`, so that serde applies the same
+        // rules, such as rejecting the identity, as every other entry point.
+        let pk = G2Affine::deserialize(deserializer)?;
+        PublicKey::from_compressed(&pk.to_compressed_le()).map_err(serde_crate::de::Error::custom)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Signature {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        G1Affine::from(&self.sig).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Signature {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        G1Affine::deserialize(deserializer).map(|sig| Signature::from_sig(&G1Projective::from(sig)))
+    }
+}
+
 #[test]
 fn test_signature_serialization() {
     let elem = [0, 23, 5, 45, 78, 62, 182, 66, 211, 46, 244, 152, 154, 242, 83, 204, 42, 48, 173, 55, 108, 232, 240, 178, 60, 146, 185, 135, 233, 92, 199, 24, 208, 32, 114, 187, 120, 211, 124, 9, 253, 118, 247, 1, 78, 236, 247, 151, 1, 108, 32, 107, 231, 56, 191, 70, 68, 250, 255, 16, 187, 130, 177, 159, 111, 7, 119, 153, 3, 166, 173, 37, 36, 128, 156, 226, 159, 148, 104, 59, 227, 43, 189, 208, 114, 236, 11, 230, 106, 224, 237, 13, 135, 129, 242, 119];
@@ -97,3 +761,485 @@ fn test_sign_hash() {
     let sig_result = pk.sign_hash(&hash).unwrap();
     assert_eq!(G1Affine::from(sig.sig), G1Affine::from(sig_result.sig));
 }
+
+#[test]
+fn test_public_key_from_compressed() {
+    let pk = PrivateKey::from_scalar(&Scalar::from(5)).to_public();
+    assert_eq!(PublicKey::from_compressed(&pk.to_compressed()), Ok(pk));
+
+    assert_eq!(
+        PublicKey::from_compressed(&G2Affine::identity().to_compressed_le()),
+        Err(ErrorCode::IdentityPoint)
+    );
+
+    // (2, y) is on the curve, but not in the prime-order subgroup.
+    let mut bytes = [0u8; 96];
+    bytes[0] = 2;
+    assert_eq!(PublicKey::from_compressed(&bytes), Err(ErrorCode::NotInSubgroup));
+
+    // x = 0 has no matching y.
+    let bytes = [0u8; 96];
+    assert_eq!(PublicKey::from_compressed(&bytes), Err(ErrorCode::NotOnCurve));
+
+    // Bad flags and a non-canonical x are malformed rather than off the curve.
+    let mut bytes = pk.to_compressed();
+    bytes[95] |= 0b0100_0000;
+    assert_eq!(PublicKey::from_compressed(&bytes), Err(ErrorCode::InvalidEncoding));
+    let mut bytes = G2Affine::identity().to_compressed_le();
+    bytes[95] |= 0b1000_0000;
+    assert_eq!(PublicKey::from_compressed(&bytes), Err(ErrorCode::InvalidEncoding));
+    let mut bytes = [0xff; 96];
+    bytes[95] = 0;
+    assert_eq!(PublicKey::from_compressed(&bytes), Err(ErrorCode::InvalidEncoding));
+    assert_eq!(PublicKey::from_compressed(&[0xff; 96]), Err(ErrorCode::InvalidEncoding));
+}
+
+#[cfg(all(feature = "serde", feature = "std"))]
+#[test]
+fn test_key_serde() {
+    let key = PrivateKey::from_scalar(&Scalar::from(5));
+    let pk = key.to_public();
+    let json = serde_json::to_string(&pk).unwrap();
+    assert_eq!(serde_json::from_str::<PublicKey>(&json).unwrap(), pk);
+    let sig = key.sign(b"serde").unwrap();
+    let json = serde_json::to_string(&sig).unwrap();
+    assert_eq!(serde_json::from_str::<Signature>(&json).unwrap(), sig);
+
+    // The identity is a valid G2 point, but not a valid public key.
+    let json = serde_json::to_string(&G2Affine::identity()).unwrap();
+    assert!(serde_json::from_str::<G2Affine>(&json).is_ok());
+    assert!(serde_json::from_str::<PublicKey>(&json).is_err());
+}
+
+#[test]
+fn test_msm_chunked() {
+    // 2 * MSM_CHUNK + 1 terms cover a full chunk, another and a partial one.
+    let g = G1Affine::generator();
+    let terms = (1..=2 * MSM_CHUNK as u64 + 1).map(|i| Ok((g, Scalar::from(i))));
+    let sum = (2 * MSM_CHUNK as u64 + 1) * (2 * MSM_CHUNK as u64 + 2) / 2;
+    assert_eq!(msm_chunked(terms, G1Projective::msm), Ok(G1Projective::generator() * Scalar::from(sum)));
+
+    let terms = (0..MSM_CHUNK).map(|_| Ok((g, Scalar::one())));
+    assert_eq!(msm_chunked(terms, G1Projective::msm), Ok(G1Projective::generator() * Scalar::from(MSM_CHUNK as u64)));
+    assert_eq!(msm_chunked(core::iter::empty(), G2Projective::msm), Ok(G2Projective::identity()));
+
+    let terms = (0..=MSM_CHUNK).map(|i| match i {
+        MSM_CHUNK => Err(ErrorCode::InvalidShareIndex),
+        _ => Ok((g, Scalar::one())),
+    });
+    assert_eq!(msm_chunked(terms, G1Projective::msm), Err(ErrorCode::InvalidShareIndex));
+}
+
+#[test]
+fn test_sign_hash_validation() {
+    let key = PrivateKey::from_scalar(&Scalar::from(7));
+    let expected = Signature::from_sig(&(G1Projective::generator() * Scalar::from(21)));
+
+    let hash = G1Affine::from(G1Projective::generator() * Scalar::from(3)).to_uncompressed_littleendian();
+    assert_eq!(key.sign_hash(&hash), Ok(expected));
+    assert_eq!(key.sign_hash_unchecked(&hash), Ok(expected));
+
+    assert_eq!(
+        key.sign_hash(&G1Affine::identity().to_uncompressed_littleendian()),
+        Err(ErrorCode::IdentityPoint)
+    );
+    assert_eq!(
+        key.sign_hash_unchecked(&G1Affine::identity().to_uncompressed_littleendian()),
+        Err(ErrorCode::IdentityPoint)
+    );
+
+    let mut off_curve = G1Affine::generator().to_uncompressed_littleendian();
+    off_curve[48] ^= 1;
+    assert_eq!(key.sign_hash(&off_curve), Err(ErrorCode::NotOnCurve));
+
+    // (5, y) is on the curve, but not in the prime-order subgroup.
+    let mut x = [0u8; 48];
+    x[0] = 5;
+    let small_order = G1Affine::from_compressed_le_unchecked(&x).unwrap().to_uncompressed_littleendian();
+    assert_eq!(key.sign_hash(&small_order), Err(ErrorCode::NotInSubgroup));
+    // The unchecked path signs it, unless the fault check catches the
+    // resulting signature outside the subgroup.
+    if cfg!(feature = "fault_check") {
+        assert_eq!(key.sign_hash_unchecked(&small_order), Err(ErrorCode::FaultDetected));
+    } else {
+        assert!(key.sign_hash_unchecked(&small_order).is_ok());
+    }
+
+    assert_eq!(key.sign_hash(&[0xff; 96]), Err(ErrorCode::InvalidEncoding));
+    assert_eq!(key.sign_hash_unchecked(&[0xff; 96]), Err(ErrorCode::InvalidEncoding));
+}
+
+#[test]
+fn test_sign_hash_blinded() {
+    let key = PrivateKey::from_scalar(&Scalar::from_raw([0x0123_4567_89ab_cdef, 0xfedc_ba98_7654_3210, 0x0f1e_2d3c_4b5a_6978, 0x0102_0304_0506_0708]));
+    let hash = G1Affine::from(G1Projective::generator() * Scalar::from(3)).to_uncompressed_littleendian();
+    let expected = key.sign_hash(&hash).unwrap();
+
+    for point_blinding in [false, true].iter() {
+        let blinding = key.derive_blinding(&hash, None, *point_blinding);
+        assert_eq!(key.sign_hash_blinded(&hash, &blinding), Ok(expected));
+        let blinding = key.derive_blinding(&hash, Some(&[0xa5; 32]), *point_blinding);
+        assert_eq!(key.sign_hash_blinded(&hash, &blinding), Ok(expected));
+    }
+
+    // Invalid hash points are rejected as by `sign_hash`.
+    let blinding = key.derive_blinding(&hash, None, true);
+    assert_eq!(
+        key.sign_hash_blinded(&G1Affine::identity().to_uncompressed_littleendian(), &blinding),
+        Err(ErrorCode::IdentityPoint)
+    );
+}
+
+/// A deterministic stand-in for a hardware RNG.
+#[cfg(all(test, feature = "rand_core"))]
+pub(crate) struct TestRng(pub(crate) u64);
+
+#[cfg(all(test, feature = "rand_core"))]
+impl RngCore for TestRng {
+    fn next_u32(&mut self) -> u32 {
+        self.next_u64() as u32
+    }
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        self.0
+    }
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        rand_core_crate::impls::fill_bytes_via_next(self, dest)
+    }
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core_crate::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "rand_core"))]
+impl CryptoRng for TestRng {}
+
+#[cfg(feature = "rand_core")]
+#[test]
+fn test_sign_hash_blinded_random() {
+    let key = PrivateKey::from_scalar(&Scalar::from(0xdead_beef));
+    let hash = G1Affine::from(G1Projective::generator() * Scalar::from(11)).to_uncompressed_littleendian();
+    let expected = key.sign_hash(&hash).unwrap();
+    let mut rng = TestRng(1);
+    for i in 0..4 {
+        let blinding = Blinding::random(&mut rng, i % 2 == 1);
+        assert_eq!(key.sign_hash_blinded(&hash, &blinding), Ok(expected));
+    }
+}
+
+/// A point of small order: [r](5, y) for the point (5, y) outside the
+/// prime-order subgroup. Pairing it with any point of G2 gives one, so it
+/// can be added to a signature without the pairing check noticing.
+#[cfg(test)]
+pub(crate) fn small_order_point() -> G1Projective {
+    let mut x = [0u8; 48];
+    x[0] = 5;
+    let p = G1Projective::from(G1Affine::from_compressed_le_unchecked(&x).unwrap());
+    p * -Scalar::one() + p
+}
+
+#[test]
+fn test_sign_verify() {
+    let key = PrivateKey::from_scalar(&Scalar::from(0x1234_5678));
+    let pk = key.to_public();
+    let sig = key.sign(b"hello").unwrap();
+    assert_eq!(pk.verify(b"hello", &sig), Ok(()));
+
+    // Signing a message is signing its hash point.
+    let hash = hash_message(b"hello").to_uncompressed_littleendian();
+    assert_eq!(key.sign_hash(&hash), Ok(sig));
+
+    assert_eq!(pk.verify(b"hellp", &sig), Err(ErrorCode::VerificationFailed));
+    let other = PrivateKey::from_scalar(&Scalar::from(0x1234_5679)).to_public();
+    assert_eq!(other.verify(b"hello", &sig), Err(ErrorCode::VerificationFailed));
+
+    let identity = PublicKey::from_pk(&G2Projective::identity());
+    let identity_sig = Signature::from_sig(&G1Projective::identity());
+    assert_eq!(identity.verify(b"hello", &identity_sig), Err(ErrorCode::IdentityPoint));
+
+    // A small-order point added to the signature passes the pairing check,
+    // but not verification.
+    let t = small_order_point();
+    assert!(!bool::from(t.is_identity()));
+    let forged = Signature::from_sig(&(sig.sig + t));
+    assert!(pairing_check(&hash_message(b"hello"), &G2Affine::from(&pk.pk), &G1Affine::from(&forged.sig)));
+    assert_eq!(pk.verify(b"hello", &forged), Err(ErrorCode::NotInSubgroup));
+}
+
+#[test]
+fn test_signature_encodings() {
+    let sig = PrivateKey::from_scalar(&Scalar::from(5)).sign(b"encode").unwrap();
+    assert_eq!(Signature::from_uncompressed(&sig.serialize()), Ok(sig));
+    assert_eq!(Signature::from_compressed(&sig.to_compressed()), Ok(sig));
+
+    let forged = Signature::from_sig(&(sig.sig + small_order_point()));
+    assert_eq!(Signature::from_uncompressed(&forged.serialize()), Err(ErrorCode::NotInSubgroup));
+    assert_eq!(Signature::from_compressed(&forged.to_compressed()), Err(ErrorCode::NotInSubgroup));
+    let mut x = [0u8; 48];
+    x[0] = 5;
+    assert_eq!(Signature::from_compressed(&x), Err(ErrorCode::NotInSubgroup));
+
+    let mut off_curve = sig.serialize();
+    off_curve[48] ^= 1;
+    assert_eq!(Signature::from_uncompressed(&off_curve), Err(ErrorCode::NotOnCurve));
+    assert_eq!(Signature::from_uncompressed(&[0xff; 96]), Err(ErrorCode::InvalidEncoding));
+    assert_eq!(Signature::from_compressed(&[0xff; 48]), Err(ErrorCode::InvalidEncoding));
+}
+
+#[test]
+fn test_fast_aggregate_verify() {
+    let keys = [
+        PrivateKey::from_scalar(&Scalar::from(11)),
+        PrivateKey::from_scalar(&Scalar::from(12)),
+        PrivateKey::from_scalar(&Scalar::from(13)),
+    ];
+    let mut verified = [VerifiedPublicKey { pk: keys[0].to_public() }; 3];
+    let mut sigs = [Signature::from_sig(&G1Projective::identity()); 3];
+    for (i, key) in keys.iter().enumerate() {
+        let proof = key.prove_possession().unwrap();
+        verified[i] = key.to_public().verify_possession(&proof).unwrap();
+        sigs[i] = key.sign(b"block 42").unwrap();
+    }
+    let sig = Signature::aggregate(&sigs);
+
+    assert_eq!(fast_aggregate_verify(&verified, b"block 42", &sig), Ok(()));
+    assert_eq!(fast_aggregate_verify(&verified, b"block 43", &sig), Err(ErrorCode::VerificationFailed));
+    assert_eq!(fast_aggregate_verify(&verified[..2], b"block 42", &sig), Err(ErrorCode::VerificationFailed));
+    assert_eq!(fast_aggregate_verify(&[], b"block 42", &sig), Err(ErrorCode::BadLength));
+
+    // A proof is not a signature of the key's encoding, nor the reverse.
+    let pk = keys[0].to_public();
+    let sig_of_pk = keys[0].sign(&pk.to_compressed()).unwrap();
+    assert_eq!(pk.verify_possession(&sig_of_pk), Err(ErrorCode::VerificationFailed));
+    assert_eq!(pk.verify(&pk.to_compressed(), &keys[0].prove_possession().unwrap()), Err(ErrorCode::VerificationFailed));
+
+    // A rogue key a·g2 - pk_0 - pk_1 - pk_2 makes the aggregate a key the
+    // attacker controls, but comes with no proof of possession.
+    let a = Scalar::from(99);
+    let rogue = PublicKey::from_pk(&(G2Projective::generator() * a - verified.iter().fold(G2Projective::identity(), |acc, k| acc + k.pk.pk)));
+    let forged = PrivateKey::from_scalar(&a).sign(b"block 42").unwrap();
+    let rogue_proof = PrivateKey::from_scalar(&a).prove_possession().unwrap();
+    assert_eq!(rogue.verify_possession(&rogue_proof), Err(ErrorCode::VerificationFailed));
+    let aggregate = PublicKey::from_pk(&(G2Projective::generator() * a));
+    assert_eq!(aggregate.verify(b"block 42", &forged), Ok(()));
+
+    assert_eq!(
+        PublicKey::from_pk(&G2Projective::identity()).verify_possession(&Signature::from_sig(&G1Projective::identity())),
+        Err(ErrorCode::IdentityPoint)
+    );
+
+    // A small-order point added to the aggregate or to a proof is caught.
+    let t = small_order_point();
+    let forged = Signature::from_sig(&(sig.sig + t));
+    assert_eq!(fast_aggregate_verify(&verified, b"block 42", &forged), Err(ErrorCode::NotInSubgroup));
+    let proof = Signature::from_sig(&(keys[0].prove_possession().unwrap().sig + t));
+    assert_eq!(pk.verify_possession(&proof), Err(ErrorCode::NotInSubgroup));
+}
+
+#[test]
+fn test_aggregate_verify() {
+    let keys = [
+        PrivateKey::from_scalar(&Scalar::from(21)),
+        PrivateKey::from_scalar(&Scalar::from(22)),
+        PrivateKey::from_scalar(&Scalar::from(23)),
+    ];
+    let pks = [keys[0].to_public(), keys[1].to_public(), keys[2].to_public()];
+    let msgs: [&[u8]; 3] = [b"x", b"y", b"z"];
+    let sig = Signature::aggregate(&[
+        keys[0].sign(msgs[0]).unwrap(),
+        keys[1].sign(msgs[1]).unwrap(),
+        keys[2].sign(msgs[2]).unwrap(),
+    ]);
+
+    let distinct = DistinctMessages::new(&msgs).unwrap();
+    assert_eq!(aggregate_verify(&pks, &distinct, &sig), Ok(()));
+
+    let swapped: [&[u8]; 3] = [b"y", b"x", b"z"];
+    let swapped = DistinctMessages::new(&swapped).unwrap();
+    assert_eq!(aggregate_verify(&pks, &swapped, &sig), Err(ErrorCode::VerificationFailed));
+    assert_eq!(aggregate_verify(&pks[..2], &distinct, &sig), Err(ErrorCode::BadLength));
+    let identity = [pks[0], pks[1], PublicKey::from_pk(&G2Projective::identity())];
+    assert_eq!(aggregate_verify(&identity, &distinct, &sig), Err(ErrorCode::IdentityPoint));
+    let forged = Signature::from_sig(&(sig.sig + small_order_point()));
+    assert_eq!(aggregate_verify(&pks, &distinct, &forged), Err(ErrorCode::NotInSubgroup));
+
+    let repeated: [&[u8]; 3] = [b"x", b"y", b"x"];
+    assert_eq!(DistinctMessages::new(&repeated).err(), Some(ErrorCode::DuplicateMessage));
+    assert!(DistinctMessages::new(&[]).is_ok());
+}
+
+#[cfg(all(feature = "std", feature = "rand_core"))]
+#[test]
+fn test_verify_batch() {
+    let msgs: [&[u8]; 7] = [b"a", b"b", b"c", b"d", b"e", b"f", b"g"];
+    let keys: Vec<PrivateKey> = (1..=7).map(|i| PrivateKey::from_scalar(&Scalar::from(1000 + i))).collect();
+    let batch: Vec<(PublicKey, &[u8], Signature)> = keys
+        .iter()
+        .zip(msgs.iter())
+        .map(|(key, msg)| (key.to_public(), *msg, key.sign(msg).unwrap()))
+        .collect();
+
+    assert_eq!(verify_batch(&batch, TestRng(1)), Ok(()));
+    assert_eq!(verify_batch(&batch[..1], TestRng(2)), Ok(()));
+    assert_eq!(verify_batch(&[], TestRng(3)), Ok(()));
+
+    // Signatures swapped between two entries still sum to the right
+    // aggregate, so only the random weights expose them.
+    let mut swapped = batch.clone();
+    swapped[2].2 = batch[5].2;
+    swapped[5].2 = batch[2].2;
+    let mut swapped_keys = batch.clone();
+    swapped_keys[2].0 = batch[5].0;
+    swapped_keys[5].0 = batch[2].0;
+    for bad in [swapped, swapped_keys].iter() {
+        assert_eq!(verify_batch(bad, TestRng(4)), Err(std::vec![2, 5]));
+    }
+
+    let mut bad = batch.clone();
+    bad[0].1 = b"x";
+    bad[6].2 = Signature::from_sig(&(bad[6].2.sig + G1Projective::generator()));
+    assert_eq!(verify_batch(&bad, TestRng(5)), Err(std::vec![0, 6]));
+
+    // The identity key and signature would pass the pairing check alone.
+    let mut bad = batch.clone();
+    bad[3].0 = PublicKey::from_pk(&G2Projective::identity());
+    bad[3].2 = Signature::from_sig(&G1Projective::identity());
+    assert_eq!(verify_batch(&bad, TestRng(6)), Err(std::vec![3]));
+
+    // So would a small-order point added to a signature.
+    let mut bad = batch.clone();
+    bad[1].2 = Signature::from_sig(&(bad[1].2.sig + small_order_point()));
+    assert_eq!(verify_batch(&bad, TestRng(7)), Err(std::vec![1]));
+
+    // The batch agrees with verifying one by one.
+    for (pk, msg, sig) in batch.iter() {
+        assert_eq!(pk.verify(msg, sig), Ok(()));
+    }
+}
+
+#[cfg(all(feature = "std", feature = "rand_core"))]
+#[test]
+fn test_msm_u128_vartime() {
+    let g = G1Affine::generator();
+    let h = G1Affine::from(G1Projective::generator() * Scalar::from(7));
+    let r = u128::MAX - 12345;
+    let r_scalar = Scalar::from_raw([r as u64, (r >> 64) as u64, 0, 0]);
+    assert_eq!(msm_u128_vartime(&[(g, r)]), g * r_scalar);
+    assert_eq!(msm_u128_vartime(&[(g, r), (h, 5)]), g * r_scalar + h * Scalar::from(5));
+    assert_eq!(msm_u128_vartime(&[(g, 1), (h, 0)]), G1Projective::from(g));
+    assert_eq!(msm_u128_vartime(&[(g, 0)]), G1Projective::identity());
+    assert_eq!(msm_u128_vartime(&[]), G1Projective::identity());
+}
+
+#[test]
+fn test_private_key_bytes() {
+    let key = PrivateKey::from_scalar(&Scalar::from(5));
+    assert_eq!(key.to_bytes()[0], 5);
+    assert!(PrivateKey::from_bytes(&key.to_bytes()).unwrap().sk == key.sk);
+
+    assert_eq!(PrivateKey::from_bytes(&[0; 32]).err(), Some(ErrorCode::ZeroKey));
+
+    // r itself is not canonical.
+    let r = (-Scalar::one()).to_bytes();
+    let mut r_bytes = r;
+    r_bytes[0] += 1;
+    assert!(PrivateKey::from_bytes(&r).is_ok());
+    assert_eq!(PrivateKey::from_bytes(&r_bytes).err(), Some(ErrorCode::NonCanonicalScalar));
+    assert_eq!(PrivateKey::from_bytes(&[0xff; 32]).err(), Some(ErrorCode::NonCanonicalScalar));
+}
+
+#[test]
+fn test_key_gen() {
+    let seed = [0xc5, 0x52, 0x57, 0xc3, 0x60, 0xc0, 0x7c, 0x72, 0x02, 0x9a, 0xeb, 0xc1, 0xb5, 0x3c, 0x05, 0xed, 0x03, 0x62, 0xad, 0xa3, 0x8e, 0xad, 0x3e, 0x3e, 0x9e, 0xfa, 0x37, 0x08, 0xe5, 0x34, 0x95, 0x53, 0x1f, 0x09, 0xa6, 0x98, 0x75, 0x99, 0xd1, 0x82, 0x64, 0xc1, 0xe1, 0xc9, 0x2f, 0x2c, 0xf1, 0x41, 0x63, 0x0c, 0x7a, 0x3c, 0x4a, 0xb7, 0xc8, 0x1b, 0x2f, 0x00, 0x16, 0x98, 0xe7, 0x46, 0x3b, 0x04];
+    let sk = [0x2d, 0x93, 0x01, 0xf7, 0x22, 0x88, 0x08, 0x4d, 0xed, 0x8e, 0x7a, 0x6b, 0x7a, 0x51, 0x27, 0x8c, 0x3c, 0x3d, 0x15, 0x98, 0x71, 0xa2, 0x8b, 0x81, 0xa3, 0xde, 0x18, 0x6f, 0x8c, 0xa8, 0x54, 0x00];
+    let priv_key = PrivateKey::key_gen(&seed, b"").unwrap();
+    assert_eq!(&priv_key.sk.to_bytes()[..], &sk[..]);
+
+    // Different key_info yields an unrelated key.
+    let other = PrivateKey::key_gen(&seed, b"BLS12-377 key").unwrap();
+    assert!(other.sk != priv_key.sk);
+
+    // Input keying material shorter than 32 bytes is rejected.
+    assert_eq!(PrivateKey::key_gen(&seed[..31], b"").err(), Some(ErrorCode::BadLength));
+}
+
+#[test]
+fn test_derive_path() {
+    let seed0 = [0xc5, 0x52, 0x57, 0xc3, 0x60, 0xc0, 0x7c, 0x72, 0x02, 0x9a, 0xeb, 0xc1, 0xb5, 0x3c, 0x05, 0xed, 0x03, 0x62, 0xad, 0xa3, 0x8e, 0xad, 0x3e, 0x3e, 0x9e, 0xfa, 0x37, 0x08, 0xe5, 0x34, 0x95, 0x53, 0x1f, 0x09, 0xa6, 0x98, 0x75, 0x99, 0xd1, 0x82, 0x64, 0xc1, 0xe1, 0xc9, 0x2f, 0x2c, 0xf1, 0x41, 0x63, 0x0c, 0x7a, 0x3c, 0x4a, 0xb7, 0xc8, 0x1b, 0x2f, 0x00, 0x16, 0x98, 0xe7, 0x46, 0x3b, 0x04];
+    let seed1 = [0x31, 0x41, 0x59, 0x26, 0x53, 0x58, 0x97, 0x93, 0x23, 0x84, 0x62, 0x64, 0x33, 0x83, 0x27, 0x95, 0x02, 0x88, 0x41, 0x97, 0x16, 0x93, 0x99, 0x37, 0x51, 0x05, 0x82, 0x09, 0x74, 0x94, 0x45, 0x92];
+    let vectors: [(&[u8], &str, [u8; 32]); 5] = [
+        (&seed0, "m", [0x2d, 0x93, 0x01, 0xf7, 0x22, 0x88, 0x08, 0x4d, 0xed, 0x8e, 0x7a, 0x6b, 0x7a, 0x51, 0x27, 0x8c, 0x3c, 0x3d, 0x15, 0x98, 0x71, 0xa2, 0x8b, 0x81, 0xa3, 0xde, 0x18, 0x6f, 0x8c, 0xa8, 0x54, 0x00]),
+        (&seed0, "m/0", [0xc4, 0x95, 0x46, 0x88, 0x61, 0x58, 0xea, 0xfb, 0x8b, 0xbf, 0x2f, 0xb1, 0x6c, 0x83, 0x07, 0xc5, 0x9a, 0xde, 0x55, 0x74, 0x3c, 0x97, 0xf9, 0x19, 0x51, 0x60, 0xc9, 0x2d, 0xb9, 0x39, 0x5d, 0x05]),
+        (&seed0, "m/12381/3600/0/0", [0xc0, 0x63, 0xed, 0xe7, 0x9c, 0x0c, 0xc6, 0x22, 0x45, 0x64, 0x9e, 0x1d, 0x9e, 0x0f, 0xd8, 0xb4, 0xab, 0x50, 0x0c, 0x3e, 0x4a, 0xae, 0x8e, 0x78, 0x26, 0x99, 0x06, 0x98, 0xce, 0xe5, 0x44, 0x07]),
+        (&seed1, "m/3141592653", [0xb6, 0x9c, 0x4f, 0xd7, 0x2e, 0x2a, 0x99, 0x01, 0x54, 0x23, 0x79, 0xef, 0x0e, 0xf2, 0x70, 0x89, 0x9b, 0x4f, 0x86, 0x49, 0xbb, 0xd2, 0xbb, 0x33, 0x73, 0x5f, 0x49, 0x44, 0xaf, 0xb4, 0x99, 0x09]),
+        (&seed1, "m/12381/3600/1/0/0", [0xb0, 0xe2, 0x57, 0x55, 0xe9, 0x32, 0x7e, 0xda, 0x04, 0xf9, 0x0e, 0x0d, 0x14, 0xfd, 0x1b, 0x3f, 0x3a, 0x4f, 0x5e, 0x20, 0x2c, 0x99, 0xeb, 0xb2, 0x21, 0x06, 0xda, 0x3f, 0x2d, 0x0e, 0x4a, 0x02]),
+    ];
+    for (seed, path, sk) in vectors.iter() {
+        let key = PrivateKey::derive_path(seed, path).unwrap();
+        assert_eq!(&key.sk.to_bytes()[..], &sk[..]);
+    }
+
+    // Deriving step by step matches deriving from the path.
+    let key = PrivateKey::derive_master_sk(&seed0)
+        .unwrap()
+        .derive_child_sk(12381)
+        .derive_child_sk(3600)
+        .derive_child_sk(0)
+        .derive_child_sk(0);
+    assert_eq!(&key.sk.to_bytes()[..], &vectors[2].2[..]);
+
+    for path in ["", "/0", "m/", "n/0", "M/0", "m//0", "m/0/", "m/-1", "m/+1", "m/0x1", "m/4294967296", "m/1'"].iter() {
+        assert_eq!(PrivateKey::derive_path(&seed0, path).err(), Some(ErrorCode::InvalidPath));
+    }
+    assert!(PrivateKey::derive_path(&seed0, "m/4294967295").is_ok());
+    assert_eq!(PrivateKey::derive_path(&seed0[..31], "m/0").err(), Some(ErrorCode::BadLength));
+}
+
+#[test]
+fn test_non_hardened_derivation() {
+    let parent = PrivateKey { sk: Scalar::from_bytes(&[0x2d, 0x93, 0x01, 0xf7, 0x22, 0x88, 0x08, 0x4d, 0xed, 0x8e, 0x7a, 0x6b, 0x7a, 0x51, 0x27, 0x8c, 0x3c, 0x3d, 0x15, 0x98, 0x71, 0xa2, 0x8b, 0x81, 0xa3, 0xde, 0x18, 0x6f, 0x8c, 0xa8, 0x54, 0x00]).unwrap() };
+    let vectors: [(&[u32], [u8; 32]); 3] = [
+        (&[0], [0x6f, 0x59, 0x8f, 0x95, 0x97, 0xc0, 0x8d, 0x92, 0x7d, 0x11, 0x47, 0x73, 0x44, 0xf5, 0xed, 0xb1, 0x35, 0x70, 0xf2, 0xcf, 0x74, 0x12, 0x21, 0x0d, 0x98, 0x2e, 0xf5, 0x18, 0xae, 0x6c, 0xec, 0x11]),
+        (&[42], [0xda, 0xae, 0x2a, 0x1f, 0x7e, 0xdb, 0x9f, 0x36, 0x4c, 0x8a, 0xbb, 0xab, 0xf6, 0x4c, 0x77, 0x5c, 0xb8, 0x05, 0x9b, 0x4a, 0xee, 0x64, 0x4f, 0xc0, 0x99, 0x01, 0x5a, 0x96, 0x95, 0x7c, 0x43, 0x0f]),
+        (&[0, 1], [0xa7, 0x58, 0xfa, 0x89, 0x7f, 0xd2, 0x3b, 0x70, 0x7c, 0x52, 0xd4, 0xf5, 0x90, 0xdb, 0x8f, 0x9b, 0xb5, 0xc9, 0x1a, 0x68, 0x91, 0xc0, 0x35, 0x8f, 0x6e, 0x7d, 0x7d, 0x22, 0x48, 0x17, 0x2d, 0x11]),
+    ];
+    for (path, sk) in vectors.iter() {
+        let mut key = PrivateKey { sk: parent.sk };
+        let mut pk = parent.to_public();
+        for index in path.iter() {
+            key = key.derive_child_sk_non_hardened(*index);
+            pk = pk.derive_child_pk_non_hardened(*index);
+        }
+        assert_eq!(&key.sk.to_bytes()[..], &sk[..]);
+
+        // The watch-only derivation matches the secret one.
+        assert_eq!(key.to_public(), pk);
+    }
+
+    // The non-hardened and hardened trees are unrelated.
+    assert!(parent.derive_child_sk(0).sk != parent.derive_child_sk_non_hardened(0).sk);
+}
+
+#[test]
+fn test_from_ecdsa_private_key() {
+    let mut ecdsa_sk = [0u8; 32];
+    ecdsa_sk[31] = 1;
+    let key = PrivateKey::from_ecdsa_private_key(&ecdsa_sk).unwrap();
+    assert_eq!(&key.sk.to_bytes()[..], &[0xb8, 0x9e, 0x77, 0x84, 0xb3, 0xf2, 0x92, 0xb3, 0xb5, 0x1f, 0xc5, 0x70, 0x36, 0x24, 0xe1, 0xbf, 0x25, 0xa6, 0xde, 0xab, 0x90, 0xc4, 0x69, 0x97, 0xe7, 0xef, 0x32, 0x8a, 0xc3, 0xa5, 0xf6, 0x06][..]);
+
+    let ecdsa_sk = [0xb1, 0xf8, 0xc6, 0xa3, 0xb3, 0xc6, 0xf6, 0xf3, 0xd2, 0xd3, 0xe0, 0xa9, 0xe6, 0xa0, 0xdd, 0xc6, 0xfe, 0x2e, 0x3c, 0x4e, 0x8a, 0x0a, 0xd4, 0xbb, 0xf4, 0xc6, 0xf0, 0x1a, 0x2a, 0x9b, 0x3c, 0x4d];
+    let key = PrivateKey::from_ecdsa_private_key(&ecdsa_sk).unwrap();
+    assert_eq!(&key.sk.to_bytes()[..], &[0x8d, 0x80, 0x5d, 0xa1, 0x18, 0xec, 0x12, 0x91, 0x03, 0xe3, 0xcb, 0x84, 0x4a, 0x08, 0x56, 0xf4, 0x44, 0x55, 0xf9, 0x95, 0x18, 0x93, 0x09, 0x7e, 0x74, 0x91, 0xec, 0xc6, 0x59, 0x82, 0x8d, 0x0a][..]);
+
+    let key = PrivateKey::from_ecdsa_private_key(&[0xff; 32]).unwrap();
+    assert_eq!(&key.sk.to_bytes()[..], &[0x90, 0x61, 0x12, 0x01, 0x85, 0x6d, 0x5d, 0x0f, 0xce, 0xa9, 0xe3, 0xbe, 0xf8, 0x38, 0xe6, 0xf0, 0x9b, 0xf4, 0x65, 0x25, 0x2f, 0x0e, 0xd5, 0xe4, 0xa6, 0x76, 0x5d, 0xbb, 0xb4, 0x7f, 0x9e, 0x08][..]);
+}
+
+#[cfg(feature = "zeroize")]
+#[test]
+fn test_zeroize() {
+    let mut key = PrivateKey::from_scalar(&Scalar::from(5));
+    key.zeroize();
+    assert!(key.sk == Scalar::zero());
+}
@@ -0,0 +1,471 @@
+//! Password-encrypted storage of private keys, following the keystore format
+//! of EIP-2335.
+//!
+//! The secret key is encrypted with AES-128-CTR under a key derived from the
+//! password with PBKDF2-HMAC-SHA256, and a SHA-256 checksum over the second
+//! half of the derived key and the ciphertext detects wrong passwords. As in
+//! EIP-2335 the secret is stored as a 32-byte big-endian integer. The `scrypt`
+//! KDF is not supported.
+//!
+//! `EncryptedKey` holds the cryptographic part of a keystore and is available
+//! without `std`; the `std` feature adds `Keystore`, which carries the public
+//! key and derivation path metadata and converts to and from the EIP-2335 JSON
+//! format.
+
+use aes::Aes128;
+use ctr::cipher::{NewCipher, StreamCipher};
+use ctr::Ctr128BE;
+use hmac::Hmac;
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+#[cfg(feature = "zeroize")]
+use zeroize_crate::Zeroize;
+
+use bls12_377::Scalar;
+use crate::bls::keys::PrivateKey;
+#[cfg(feature = "std")]
+use crate::bls::keys::PublicKey;
+use crate::error::ErrorCode;
+
+#[cfg(feature = "std")]
+use std::string::{String, ToString};
+#[cfg(feature = "std")]
+use serde_json::{json, Value};
+#[cfg(feature = "std")]
+use unicode_normalization::UnicodeNormalization;
+
+type Aes128Ctr = Ctr128BE<Aes128>;
+
+/// The PBKDF2 iteration count recommended by EIP-2335.
+pub const PBKDF2_ITERATIONS: u32 = 262_144;
+
+/// The length of the PBKDF2-derived decryption key.
+const DKLEN: usize = 32;
+
+/// The HMAC-SHA256 block size. Longer passwords are hashed before use as an
+/// HMAC key.
+const HMAC_BLOCK_LEN: usize = 64;
+
+/// The encrypted form of a private key: the parameters of the key derivation
+/// and cipher, the ciphertext, and its checksum.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EncryptedKey {
+    pub salt: [u8; 32],
+    pub iterations: u32,
+    pub iv: [u8; 16],
+    pub ciphertext: [u8; 32],
+    pub checksum: [u8; 32],
+}
+
+impl EncryptedKey {
+    /// Encrypts `key` under `password`. `salt` and `iv` must be freshly
+    /// generated with a cryptographically secure RNG for every encryption.
+    pub fn encrypt(
+        key: &PrivateKey,
+        password: &str,
+        salt: &[u8; 32],
+        iv: &[u8; 16],
+        iterations: u32,
+    ) -> Result<Self, ErrorCode> {
+        let mut dk = derive_key(password, salt, iterations)?;
+
+        // The secret is stored as I2OSP(sk, 32).
+        let mut ciphertext = key.as_scalar().to_bytes();
+        ciphertext.reverse();
+        Aes128Ctr::new(dk[..16].into(), iv.into()).apply_keystream(&mut ciphertext);
+
+        let checksum = checksum(&dk, &ciphertext);
+
+        #[cfg(feature = "zeroize")]
+        dk.zeroize();
+
+        Ok(EncryptedKey {
+            salt: *salt,
+            iterations,
+            iv: *iv,
+            ciphertext,
+            checksum,
+        })
+    }
+
+    /// Decrypts the private key, failing if the password is wrong or the
+    /// decrypted secret is not a canonical, non-zero scalar.
+    pub fn decrypt(&self, password: &str) -> Result<PrivateKey, ErrorCode> {
+        let mut dk = derive_key(password, &self.salt, self.iterations)?;
+
+        if !bool::from(checksum(&dk, &self.ciphertext).ct_eq(&self.checksum)) {
+            #[cfg(feature = "zeroize")]
+            dk.zeroize();
+            return Err(ErrorCode::InvalidPassword);
+        }
+
+        let mut secret = self.ciphertext;
+        Aes128Ctr::new(dk[..16].into(), self.iv[..].into()).apply_keystream(&mut secret);
+        secret.reverse();
+        let sk = Scalar::from_bytes(&secret);
+
+        #[cfg(feature = "zeroize")]
+        {
+            dk.zeroize();
+            secret.zeroize();
+        }
+
+        if !bool::from(sk.is_some()) {
+            return Err(ErrorCode::NonCanonicalScalar);
+        }
+        let sk = sk.unwrap();
+        if sk == Scalar::zero() {
+            return Err(ErrorCode::ZeroKey);
+        }
+
+        Ok(PrivateKey::from_scalar(&sk))
+    }
+}
+
+/// Normalizes the password to NFKD, as EIP-2335 requires. Without `std` the
+/// Unicode tables are not available, and the password must already be in
+/// NFKD form; ASCII passwords always are.
+#[cfg(feature = "std")]
+fn password_chars<'a>(password: &'a str) -> impl Iterator<Item = char> + 'a {
+    password.nfkd()
+}
+
+#[cfg(not(feature = "std"))]
+fn password_chars<'a>(password: &'a str) -> impl Iterator<Item = char> + 'a {
+    password.chars()
+}
+
+/// The C0, C1 and Delete control codes, which EIP-2335 strips from passwords.
+fn is_control_code(c: char) -> bool {
+    c <= '\u{1f}' || ('\u{7f}'..='\u{9f}').contains(&c)
+}
+
+/// Derives the decryption key with PBKDF2-HMAC-SHA256 from the processed
+/// password.
+fn derive_key(password: &str, salt: &[u8; 32], iterations: u32) -> Result<[u8; DKLEN], ErrorCode> {
+    if iterations == 0 {
+        return Err(ErrorCode::InvalidKeystore);
+    }
+
+    // The processed password is only needed as an HMAC key, and HMAC replaces
+    // keys longer than a block with their hash. Hashing it on the fly spares
+    // us from buffering passwords of arbitrary length.
+    let mut block = [0u8; HMAC_BLOCK_LEN];
+    let mut len = 0;
+    let mut long = Sha256::new();
+    for c in password_chars(password).filter(|c| !is_control_code(*c)) {
+        let mut utf8 = [0u8; 4];
+        let bytes = c.encode_utf8(&mut utf8).as_bytes();
+        long.input(bytes);
+        if len + bytes.len() <= HMAC_BLOCK_LEN {
+            block[len..len + bytes.len()].copy_from_slice(bytes);
+        }
+        len += bytes.len();
+    }
+
+    let mut hashed = [0u8; 32];
+    let hmac_key = if len <= HMAC_BLOCK_LEN {
+        &block[..len]
+    } else {
+        hashed.copy_from_slice(&long.result());
+        &hashed[..]
+    };
+
+    let mut dk = [0u8; DKLEN];
+    pbkdf2::pbkdf2::<Hmac<Sha256>>(hmac_key, salt, iterations as usize, &mut dk);
+
+    #[cfg(feature = "zeroize")]
+    {
+        block.zeroize();
+        hashed.zeroize();
+    }
+
+    Ok(dk)
+}
+
+/// checksum = SHA256(DK[16..32] || ciphertext)
+fn checksum(dk: &[u8; DKLEN], ciphertext: &[u8; 32]) -> [u8; 32] {
+    let mut h = Sha256::new();
+    h.input(&dk[16..]);
+    h.input(ciphertext);
+
+    let mut checksum = [0u8; 32];
+    checksum.copy_from_slice(&h.result());
+    checksum
+}
+
+/// An EIP-2335 keystore: an encrypted private key together with its public
+/// key and the path it was derived at.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Keystore {
+    pub crypto: EncryptedKey,
+    pub description: String,
+    pub pubkey: PublicKey,
+    pub path: String,
+    pub uuid: String,
+}
+
+#[cfg(feature = "std")]
+impl Keystore {
+    /// The keystore format version defined by EIP-2335.
+    pub const VERSION: u64 = 4;
+
+    /// Encrypts `key` under `password` with the recommended PBKDF2 iteration
+    /// count. `salt`, `iv` and the version 4 `uuid` must be freshly generated
+    /// for every keystore.
+    pub fn encrypt(
+        key: &PrivateKey,
+        password: &str,
+        path: &str,
+        uuid: &str,
+        salt: &[u8; 32],
+        iv: &[u8; 16],
+    ) -> Result<Self, ErrorCode> {
+        Ok(Keystore {
+            crypto: EncryptedKey::encrypt(key, password, salt, iv, PBKDF2_ITERATIONS)?,
+            description: String::new(),
+            pubkey: key.to_public(),
+            path: path.to_string(),
+            uuid: uuid.to_string(),
+        })
+    }
+
+    /// Decrypts the private key, additionally checking that it matches the
+    /// stored public key.
+    pub fn decrypt(&self, password: &str) -> Result<PrivateKey, ErrorCode> {
+        let key = self.crypto.decrypt(password)?;
+        if key.to_public() != self.pubkey {
+            return Err(ErrorCode::InvalidKeystore);
+        }
+
+        Ok(key)
+    }
+
+    /// Encodes the keystore as EIP-2335 JSON. The public key is the hex
+    /// encoding of its compressed little-endian form.
+    pub fn to_json(&self) -> String {
+        json!({
+            "crypto": {
+                "kdf": {
+                    "function": "pbkdf2",
+                    "params": {
+                        "dklen": DKLEN,
+                        "c": self.crypto.iterations,
+                        "prf": "hmac-sha256",
+                        "salt": hex::encode(self.crypto.salt),
+                    },
+                    "message": "",
+                },
+                "checksum": {
+                    "function": "sha256",
+                    "params": {},
+                    "message": hex::encode(self.crypto.checksum),
+                },
+                "cipher": {
+                    "function": "aes-128-ctr",
+                    "params": {
+                        "iv": hex::encode(self.crypto.iv),
+                    },
+                    "message": hex::encode(self.crypto.ciphertext),
+                },
+            },
+            "description": self.description,
+            "pubkey": hex::encode(&self.pubkey.to_compressed()[..]),
+            "path": self.path,
+            "uuid": self.uuid,
+            "version": Self::VERSION,
+        })
+        .to_string()
+    }
+
+    /// Decodes an EIP-2335 JSON keystore. Keystores using a KDF or cipher
+    /// other than PBKDF2-HMAC-SHA256 and AES-128-CTR, or with a salt of a
+    /// length other than 32 bytes, are rejected.
+    pub fn from_json(json: &str) -> Result<Self, ErrorCode> {
+        let v: Value = serde_json::from_str(json).map_err(|_| ErrorCode::InvalidKeystore)?;
+        if v["version"] != Self::VERSION {
+            return Err(ErrorCode::InvalidKeystore);
+        }
+
+        let kdf = &v["crypto"]["kdf"];
+        let checksum = &v["crypto"]["checksum"];
+        let cipher = &v["crypto"]["cipher"];
+        if kdf["function"] != "pbkdf2"
+            || kdf["params"]["prf"] != "hmac-sha256"
+            || kdf["params"]["dklen"] != DKLEN
+            || checksum["function"] != "sha256"
+            || cipher["function"] != "aes-128-ctr"
+        {
+            return Err(ErrorCode::InvalidKeystore);
+        }
+
+        let mut crypto = EncryptedKey {
+            salt: [0; 32],
+            iterations: 0,
+            iv: [0; 16],
+            ciphertext: [0; 32],
+            checksum: [0; 32],
+        };
+        crypto.iterations = kdf["params"]["c"]
+            .as_u64()
+            .filter(|c| *c > 0 && *c <= u64::from(u32::MAX))
+            .ok_or(ErrorCode::InvalidKeystore)? as u32;
+        decode_hex(&kdf["params"]["salt"], &mut crypto.salt)?;
+        decode_hex(&cipher["params"]["iv"], &mut crypto.iv)?;
+        decode_hex(&cipher["message"], &mut crypto.ciphertext)?;
+        decode_hex(&checksum["message"], &mut crypto.checksum)?;
+
+        let mut pubkey = [0u8; 96];
+        decode_hex(&v["pubkey"], &mut pubkey)?;
+
+        Ok(Keystore {
+            crypto,
+            description: v["description"].as_str().unwrap_or("").to_string(),
+            pubkey: PublicKey::from_compressed(&pubkey)?,
+            path: as_string(&v["path"])?,
+            uuid: as_string(&v["uuid"])?,
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+fn as_string(v: &Value) -> Result<String, ErrorCode> {
+    v.as_str().map(|s| s.to_string()).ok_or(ErrorCode::InvalidKeystore)
+}
+
+/// Decodes a hex string field into `out`, which it must fill exactly.
+#[cfg(feature = "std")]
+fn decode_hex(v: &Value, out: &mut [u8]) -> Result<(), ErrorCode> {
+    let s = v.as_str().ok_or(ErrorCode::InvalidKeystore)?;
+    hex::decode_to_slice(s, out).map_err(|_| ErrorCode::InvalidKeystore)
+}
+
+#[cfg(test)]
+const TEST_SALT: [u8; 32] = [0xd4, 0xe5, 0x67, 0x40, 0xf8, 0x76, 0xae, 0xf8, 0xc0, 0x10, 0xb8, 0x6a, 0x40, 0xd5, 0xf5, 0x67, 0x45, 0xa1, 0x18, 0xd0, 0x90, 0x6a, 0x34, 0xe6, 0x9a, 0xec, 0x8c, 0x0d, 0xb1, 0xcb, 0x8f, 0xa3];
+
+#[cfg(test)]
+const TEST_IV: [u8; 16] = [0x26, 0x4d, 0xaa, 0x3f, 0x30, 0x3d, 0x72, 0x59, 0x50, 0x1c, 0x93, 0xd9, 0x97, 0xd8, 0x4f, 0xe6];
+
+/// The secret of the EIP-2335 test vectors, 0x19d6...e26f.
+#[cfg(test)]
+fn test_key() -> PrivateKey {
+    let mut secret = [0x00, 0x00, 0x00, 0x00, 0x00, 0x19, 0xd6, 0x68, 0x9c, 0x08, 0x5a, 0xe1, 0x65, 0x83, 0x1e, 0x93, 0x4f, 0xf7, 0x63, 0xae, 0x46, 0xa2, 0xa6, 0xc1, 0x72, 0xb3, 0xf1, 0xb6, 0x0a, 0x8c, 0xe2, 0x6f];
+    secret.reverse();
+    PrivateKey::from_scalar(&Scalar::from_bytes(&secret).unwrap())
+}
+
+#[test]
+fn test_encrypt() {
+    // Computed with Python's hashlib.pbkdf2_hmac and OpenSSL's AES-128-CTR.
+    let vectors: [(&str, [u8; 32], [u8; 32]); 2] = [
+        ("testpassword\u{1f511}",
+         [0x88, 0x88, 0x5f, 0x58, 0x36, 0x62, 0x9a, 0xe3, 0x59, 0xf7, 0x95, 0x43, 0x34, 0xcf, 0xa3, 0xa1, 0xcd, 0xd6, 0xcb, 0xdf, 0x54, 0x98, 0x25, 0xf1, 0x8c, 0x05, 0xd0, 0x27, 0xc2, 0x87, 0x4c, 0xc9],
+         [0x28, 0x75, 0x1e, 0x14, 0x11, 0x95, 0x4f, 0xf1, 0xe9, 0x3a, 0x3d, 0xcf, 0x12, 0x8c, 0x0a, 0x44, 0xb2, 0x0a, 0x99, 0x15, 0x9a, 0xc8, 0xaa, 0xd3, 0x9c, 0xa3, 0x4c, 0xb9, 0xa3, 0xcf, 0xd1, 0x3a]),
+        // Control codes are stripped, and the remaining 90 bytes exceed an
+        // HMAC block.
+        ("a\u{7}b\u{85}ca\u{7}b\u{85}ca\u{7}b\u{85}ca\u{7}b\u{85}ca\u{7}b\u{85}ca\u{7}b\u{85}ca\u{7}b\u{85}ca\u{7}b\u{85}ca\u{7}b\u{85}ca\u{7}b\u{85}ca\u{7}b\u{85}ca\u{7}b\u{85}ca\u{7}b\u{85}ca\u{7}b\u{85}ca\u{7}b\u{85}ca\u{7}b\u{85}ca\u{7}b\u{85}ca\u{7}b\u{85}ca\u{7}b\u{85}ca\u{7}b\u{85}ca\u{7}b\u{85}ca\u{7}b\u{85}ca\u{7}b\u{85}ca\u{7}b\u{85}ca\u{7}b\u{85}ca\u{7}b\u{85}ca\u{7}b\u{85}ca\u{7}b\u{85}ca\u{7}b\u{85}ca\u{7}b\u{85}c",
+         [0xa9, 0x40, 0x08, 0xe0, 0x29, 0x87, 0x71, 0x92, 0xde, 0x06, 0xb9, 0x2f, 0x38, 0xee, 0x54, 0x0e, 0x74, 0xf2, 0xf5, 0x2c, 0xe6, 0x49, 0x59, 0x51, 0xed, 0x92, 0x1d, 0x6f, 0x56, 0x2a, 0x5d, 0x15],
+         [0xa6, 0x3e, 0x91, 0x22, 0xe0, 0x97, 0x0e, 0x2a, 0x75, 0x29, 0x02, 0xd2, 0xf1, 0x31, 0x0c, 0xf4, 0x2e, 0x82, 0x3b, 0x02, 0x81, 0x66, 0xbd, 0x09, 0xe2, 0x4e, 0x16, 0x84, 0x45, 0xff, 0xa8, 0x42]),
+    ];
+    for (password, ciphertext, checksum) in vectors.iter() {
+        let crypto = EncryptedKey::encrypt(&test_key(), password, &TEST_SALT, &TEST_IV, 16).unwrap();
+        assert_eq!(crypto.ciphertext, *ciphertext);
+        assert_eq!(crypto.checksum, *checksum);
+
+        let key = crypto.decrypt(password).unwrap();
+        assert!(key.as_scalar() == test_key().as_scalar());
+    }
+
+    let crypto = EncryptedKey::encrypt(&test_key(), "password", &TEST_SALT, &TEST_IV, 16).unwrap();
+    assert!(crypto.decrypt("password").is_ok());
+    assert_eq!(crypto.decrypt("passwore").err(), Some(ErrorCode::InvalidPassword));
+    assert_eq!(crypto.decrypt("").err(), Some(ErrorCode::InvalidPassword));
+
+    // Tampering with any part of the keystore is detected.
+    let mut tampered = crypto.clone();
+    tampered.ciphertext[0] ^= 1;
+    assert!(tampered.decrypt("password").is_err());
+    let mut tampered = crypto.clone();
+    tampered.salt[0] ^= 1;
+    assert!(tampered.decrypt("password").is_err());
+    let mut tampered = crypto.clone();
+    tampered.iterations += 1;
+    assert!(tampered.decrypt("password").is_err());
+
+    assert_eq!(
+        EncryptedKey::encrypt(&test_key(), "password", &TEST_SALT, &TEST_IV, 0).err(),
+        Some(ErrorCode::InvalidKeystore)
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_keystore_json() {
+    // The PBKDF2 test vector of EIP-2335, with the public key of this curve.
+    let password = "\u{1d531}\u{1d522}\u{1d530}\u{1d531}\u{1d52d}\u{1d51e}\u{1d530}\u{1d530}\u{1d534}\u{1d52c}\u{1d52f}\u{1d521}\u{1f511}";
+    let json = r#"{
+        "crypto": {
+            "kdf": {
+                "function": "pbkdf2",
+                "params": {
+                    "dklen": 32,
+                    "c": 262144,
+                    "prf": "hmac-sha256",
+                    "salt": "d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3"
+                },
+                "message": ""
+            },
+            "checksum": {
+                "function": "sha256",
+                "params": {},
+                "message": "8a9f5d9912ed7e75ea794bc5a89bca5f193721d30868ade6f73043c6ea6febf1"
+            },
+            "cipher": {
+                "function": "aes-128-ctr",
+                "params": {
+                    "iv": "264daa3f303d7259501c93d997d84fe6"
+                },
+                "message": "cee03fde2af33149775b7223e7845e4fb2c8ae1792e5f99fe9ecf474cc8c16ad"
+            }
+        },
+        "description": "This is a test keystore that uses PBKDF2 to secure the secret.",
+        "pubkey": "PUBKEY",
+        "path": "m/12381/60/0/0",
+        "uuid": "64625def-3331-4eea-ab6f-782f3ed16a83",
+        "version": 4
+    }"#
+    .replace("PUBKEY", &hex::encode(&test_key().to_public().to_compressed()[..]));
+
+    let keystore = Keystore::from_json(&json).unwrap();
+    assert_eq!(keystore.path, "m/12381/60/0/0");
+    assert_eq!(keystore.uuid, "64625def-3331-4eea-ab6f-782f3ed16a83");
+    let key = keystore.decrypt(password).unwrap();
+    assert!(key.as_scalar() == test_key().as_scalar());
+
+    // Encrypting with the same parameters reproduces the keystore.
+    let encrypted = Keystore::encrypt(&key, password, &keystore.path, &keystore.uuid, &TEST_SALT, &TEST_IV).unwrap();
+    assert_eq!(encrypted.crypto, keystore.crypto);
+
+    // Encoding round-trips.
+    assert_eq!(Keystore::from_json(&keystore.to_json()).unwrap(), keystore);
+
+    // A public key that does not match the secret is rejected.
+    let mut other = keystore.clone();
+    other.pubkey = PrivateKey::from_scalar(&Scalar::from(5)).to_public();
+    assert_eq!(other.decrypt(password).err(), Some(ErrorCode::InvalidKeystore));
+
+    for (from, to) in [
+        ("\"version\": 4", "\"version\": 3"),
+        ("\"function\": \"pbkdf2\"", "\"function\": \"scrypt\""),
+        ("\"aes-128-ctr\"", "\"aes-256-ctr\""),
+        ("\"dklen\": 32", "\"dklen\": 64"),
+        ("\"c\": 262144", "\"c\": 0"),
+        ("\"iv\": \"264daa3f", "\"iv\": \"264daa"),
+        ("\"path\": \"m/12381/60/0/0\",", ""),
+    ]
+    .iter()
+    {
+        assert!(Keystore::from_json(&json.replace(from, to)).is_err());
+    }
+}
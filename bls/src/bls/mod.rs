@@ -1 +1,10 @@
 pub mod keys;
+mod blinding;
+#[cfg(all(feature = "std", feature = "rand_core"))]
+pub mod dkg;
+#[cfg(feature = "fault_check")]
+mod fault;
+mod keygen;
+pub mod keystore;
+pub mod multisig;
+pub mod threshold;
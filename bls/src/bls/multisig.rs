@@ -0,0 +1,196 @@
+//! Multisignatures with the rogue-key defence of Boneh, Drijvers and Neven,
+//! "Compact Multi-Signatures for Smaller Blockchains" (ASIACRYPT 2018), for
+//! committees whose keys come without proofs of possession.
+//!
+//! Each key pk_i of a committee pk_1, ..., pk_n is weighted by
+//! t_i = H(pk_i, {pk_1, ..., pk_n}): the aggregate key is Σ t_i pk_i and the
+//! multisignature is Σ t_i σ_i, where σ_i is the signature of pk_i on the
+//! message. A rogue key chosen to cancel out the honest keys changes every
+//! weight, so the cancellation no longer works.
+//!
+//! The committee is hashed in the order given, so signers and verifiers
+//! must list the keys in the same order. A key listed twice is weighted
+//! twice, and must sign twice.
+
+use bls12_377::hash_to_curve::{ExpandMsgXmd, HashToField};
+use bls12_377::{G1Affine, G1Projective, G2Affine, G2Projective, Scalar};
+use sha2::{Digest, Sha256};
+
+use crate::bls::keys::{msm_chunked, PrivateKey, PublicKey, Signature};
+use crate::error::ErrorCode;
+
+/// The domain separation tag with which the weights t_i are hashed to
+/// scalars.
+pub const COEFFICIENT_DST: &[u8] = b"BLS_BDN_BLS12377_XMD:SHA-256_COEFFICIENT_";
+
+/// Hashes the committee to the digest that stands for {pk_1, ..., pk_n} in
+/// every weight.
+fn committee_digest(keys: &[PublicKey]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    for pk in keys.iter() {
+        hasher.input(&pk.to_compressed()[..]);
+    }
+
+    let mut digest = [0u8; 32];
+    digest.copy_from_slice(&hasher.result());
+    digest
+}
+
+/// Computes the weight t_i of `pk` in the committee with digest `digest`.
+fn coefficient(pk: &PublicKey, digest: &[u8; 32]) -> Scalar {
+    let mut msg = [0u8; 128];
+    msg[..96].copy_from_slice(&pk.to_compressed());
+    msg[96..].copy_from_slice(digest);
+
+    let mut t = [Scalar::zero()];
+    Scalar::hash_to_field::<ExpandMsgXmd<Sha256>>(&msg, COEFFICIENT_DST, &mut t);
+    t[0]
+}
+
+/// Checks that a committee is non-empty and holds no identity key.
+fn check_committee(keys: &[PublicKey]) -> Result<(), ErrorCode> {
+    if keys.is_empty() {
+        return Err(ErrorCode::BadLength);
+    }
+    if keys.iter().any(|pk| bool::from(pk.as_point().is_identity())) {
+        return Err(ErrorCode::IdentityPoint);
+    }
+
+    Ok(())
+}
+
+/// Computes the aggregate key Σ t_i pk_i of a committee.
+pub fn aggregate_key(keys: &[PublicKey]) -> Result<PublicKey, ErrorCode> {
+    check_committee(keys)?;
+
+    let digest = committee_digest(keys);
+    let terms = keys.iter().map(|pk| Ok((G2Affine::from(pk.as_point()), coefficient(pk, &digest))));
+    let acc = msm_chunked(terms, G2Projective::msm)?;
+
+    Ok(PublicKey::from_pk(&acc))
+}
+
+/// Signs `msg` as a member of the committee `keys`, returning the weighted
+/// share t_i σ_i. The shares of all members add up to the multisignature
+/// with `Signature::aggregate`. Fails with `ErrorCode::UnknownKey` if the
+/// key is not in the committee.
+pub fn sign(key: &PrivateKey, keys: &[PublicKey], msg: &[u8]) -> Result<Signature, ErrorCode> {
+    check_committee(keys)?;
+    let pk = key.to_public();
+    if !keys.contains(&pk) {
+        return Err(ErrorCode::UnknownKey);
+    }
+
+    let t = coefficient(&pk, &committee_digest(keys));
+    PrivateKey::from_scalar(&(key.as_scalar() * t)).sign(msg)
+}
+
+/// Combines the plain signatures `sigs[i]` of `keys[i]` on one message, as
+/// made by `PrivateKey::sign`, into the multisignature Σ t_i σ_i. Unlike
+/// `sign`, this lets signers stay unaware of the committee.
+pub fn aggregate_signatures(keys: &[PublicKey], sigs: &[Signature]) -> Result<Signature, ErrorCode> {
+    check_committee(keys)?;
+    if keys.len() != sigs.len() {
+        return Err(ErrorCode::BadLength);
+    }
+
+    let digest = committee_digest(keys);
+    let terms = keys.iter().zip(sigs.iter());
+    let terms = terms.map(|(pk, sig)| Ok((G1Affine::from(sig.as_point()), coefficient(pk, &digest))));
+    let acc = msm_chunked(terms, G1Projective::msm)?;
+
+    Ok(Signature::from_sig(&acc))
+}
+
+/// Verifies a multisignature of the committee `keys` on `msg`.
+pub fn verify(keys: &[PublicKey], msg: &[u8], sig: &Signature) -> Result<(), ErrorCode> {
+    aggregate_key(keys)?.verify(msg, sig)
+}
+
+#[cfg(test)]
+fn committee() -> ([PrivateKey; 4], [PublicKey; 4]) {
+    let keys = [
+        PrivateKey::from_scalar(&Scalar::from(31)),
+        PrivateKey::from_scalar(&Scalar::from(32)),
+        PrivateKey::from_scalar(&Scalar::from(33)),
+        PrivateKey::from_scalar(&Scalar::from(34)),
+    ];
+    let pks = [keys[0].to_public(), keys[1].to_public(), keys[2].to_public(), keys[3].to_public()];
+    (keys, pks)
+}
+
+#[test]
+fn test_multisig() {
+    let (keys, pks) = committee();
+    let msg = b"epoch 7";
+
+    let mut shares = [Signature::from_sig(&G1Projective::identity()); 4];
+    let mut plain = shares;
+    for (i, key) in keys.iter().enumerate() {
+        shares[i] = sign(key, &pks, msg).unwrap();
+        plain[i] = key.sign(msg).unwrap();
+    }
+    let sig = Signature::aggregate(&shares);
+    assert_eq!(aggregate_signatures(&pks, &plain), Ok(sig));
+
+    assert_eq!(verify(&pks, msg, &sig), Ok(()));
+    assert_eq!(aggregate_key(&pks).unwrap().verify(msg, &sig), Ok(()));
+    assert_eq!(verify(&pks, b"epoch 8", &sig), Err(ErrorCode::VerificationFailed));
+    assert_eq!(verify(&pks[..3], msg, &sig), Err(ErrorCode::VerificationFailed));
+
+    // The weights depend on the order of the committee.
+    let reordered = [pks[1], pks[0], pks[2], pks[3]];
+    assert!(aggregate_key(&reordered).unwrap() != aggregate_key(&pks).unwrap());
+    assert_eq!(verify(&reordered, msg, &sig), Err(ErrorCode::VerificationFailed));
+
+    // Unweighted, the plain signatures only verify against the plain sum.
+    assert_eq!(verify(&pks, msg, &Signature::aggregate(&plain)), Err(ErrorCode::VerificationFailed));
+}
+
+#[test]
+fn test_rogue_key() {
+    let (_, pks) = committee();
+    let msg = b"epoch 7";
+
+    // The rogue key a·g2 - pk_0 - pk_1 - pk_2 makes the plain sum of the
+    // committee a·g2, for which the attacker can sign alone.
+    let a = Scalar::from(1234);
+    let honest = pks[..3].iter().fold(G2Projective::identity(), |acc, pk| acc + pk.as_point());
+    let rogue = PublicKey::from_pk(&(G2Projective::generator() * a - honest));
+    let committee = [pks[0], pks[1], pks[2], rogue];
+    let forged = PrivateKey::from_scalar(&a).sign(msg).unwrap();
+
+    let plain_sum = PublicKey::from_pk(&(honest + rogue.as_point()));
+    assert_eq!(plain_sum.verify(msg, &forged), Ok(()));
+    assert_eq!(verify(&committee, msg, &forged), Err(ErrorCode::VerificationFailed));
+}
+
+#[test]
+fn test_large_committee() {
+    // More keys than fit in one multi-scalar multiplication.
+    let mut pks = [PublicKey::from_pk(&G2Projective::identity()); 18];
+    for (i, pk) in pks.iter_mut().enumerate() {
+        *pk = PrivateKey::from_scalar(&Scalar::from(100 + i as u64)).to_public();
+    }
+    let mut shares = [Signature::from_sig(&G1Projective::identity()); 18];
+    for (i, share) in shares.iter_mut().enumerate() {
+        let key = PrivateKey::from_scalar(&Scalar::from(100 + i as u64));
+        *share = sign(&key, &pks, b"m").unwrap();
+    }
+    assert_eq!(verify(&pks, b"m", &Signature::aggregate(&shares)), Ok(()));
+}
+
+#[test]
+fn test_invalid_committees() {
+    let (keys, pks) = committee();
+    let sig = keys[0].sign(b"m").unwrap();
+
+    assert_eq!(aggregate_key(&[]).err(), Some(ErrorCode::BadLength));
+    assert_eq!(aggregate_signatures(&pks, &[sig]).err(), Some(ErrorCode::BadLength));
+    let identity = [pks[0], PublicKey::from_pk(&G2Projective::identity())];
+    assert_eq!(aggregate_key(&identity).err(), Some(ErrorCode::IdentityPoint));
+    assert_eq!(verify(&identity, b"m", &sig), Err(ErrorCode::IdentityPoint));
+
+    let outsider = PrivateKey::from_scalar(&Scalar::from(35));
+    assert_eq!(sign(&outsider, &pks, b"m").err(), Some(ErrorCode::UnknownKey));
+}
@@ -0,0 +1,342 @@
+//! Threshold signatures: a key is split into n Shamir shares so that any t
+//! of them can sign, while fewer than t learn nothing about the key.
+//!
+//! The dealer picks a random polynomial f of degree t - 1 with f(0) = sk and
+//! gives share i the key f(i). Each share signs as an ordinary key, producing
+//! the partial signature f(i) H(m), and any t partials with indices S combine
+//! to Σ λ_i f(i) H(m) = sk H(m), where λ_i are the Lagrange coefficients at
+//! zero for S. The result is the signature `PrivateKey::sign` makes with sk,
+//! and verifies against the group public key like any other.
+
+use bls12_377::{G1Affine, G1Projective, G2Affine, G2Projective, Scalar};
+use subtle::CtOption;
+#[cfg(all(feature = "std", feature = "rand_core"))]
+use rand_core_crate::{CryptoRng, RngCore};
+#[cfg(all(feature = "std", feature = "rand_core"))]
+use std::vec::Vec;
+#[cfg(all(feature = "std", feature = "rand_core", feature = "zeroize"))]
+use zeroize_crate::Zeroize;
+
+use crate::bls::keys::{msm_chunked, PrivateKey, PublicKey, Signature};
+use crate::error::ErrorCode;
+
+/// A share of a threshold key: the value f(index) of the dealer's
+/// polynomial at a non-zero index.
+pub struct KeyShare {
+    index: u32,
+    key: PrivateKey,
+}
+
+impl KeyShare {
+    /// Wraps the key of the share with index `index`, failing with
+    /// `ErrorCode::InvalidShareIndex` if the index is zero, which is where
+    /// the group key itself sits.
+    pub fn new(index: u32, key: PrivateKey) -> Result<Self, ErrorCode> {
+        if index == 0 {
+            return Err(ErrorCode::InvalidShareIndex);
+        }
+
+        Ok(KeyShare { index, key })
+    }
+
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    pub fn private_key(&self) -> &PrivateKey {
+        &self.key
+    }
+
+    /// Returns the public key of the share, against which its partial
+    /// signatures are verified.
+    pub fn public_share(&self) -> PublicKeyShare {
+        PublicKeyShare { index: self.index, pk: self.key.to_public() }
+    }
+
+    /// Signs a message with the share.
+    pub fn sign(&self, msg: &[u8]) -> Result<PartialSignature, ErrorCode> {
+        Ok(PartialSignature { index: self.index, sig: self.key.sign(msg)? })
+    }
+}
+
+/// The public key f(index) g2 of a key share.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PublicKeyShare {
+    index: u32,
+    pk: PublicKey,
+}
+
+impl PublicKeyShare {
+    /// Wraps the public key of the share with index `index`, failing with
+    /// `ErrorCode::InvalidShareIndex` if the index is zero.
+    pub fn new(index: u32, pk: PublicKey) -> Result<Self, ErrorCode> {
+        if index == 0 {
+            return Err(ErrorCode::InvalidShareIndex);
+        }
+
+        Ok(PublicKeyShare { index, pk })
+    }
+
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    pub fn public_key(&self) -> &PublicKey {
+        &self.pk
+    }
+
+    /// Verifies a partial signature on `msg` made by this share. A partial
+    /// signature carrying another index fails.
+    pub fn verify(&self, msg: &[u8], partial: &PartialSignature) -> Result<(), ErrorCode> {
+        if partial.index != self.index {
+            return Err(ErrorCode::VerificationFailed);
+        }
+
+        self.pk.verify(msg, &partial.sig)
+    }
+}
+
+/// A signature made by one key share, tagged with the share's index.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PartialSignature {
+    index: u32,
+    sig: Signature,
+}
+
+impl PartialSignature {
+    /// Wraps the signature of the share with index `index`, failing with
+    /// `ErrorCode::InvalidShareIndex` if the index is zero.
+    pub fn new(index: u32, sig: Signature) -> Result<Self, ErrorCode> {
+        if index == 0 {
+            return Err(ErrorCode::InvalidShareIndex);
+        }
+
+        Ok(PartialSignature { index, sig })
+    }
+
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    pub fn signature(&self) -> &Signature {
+        &self.sig
+    }
+}
+
+/// A random polynomial with a given constant term, as dealt by `split`.
+#[cfg(all(feature = "std", feature = "rand_core"))]
+pub(crate) struct Polynomial {
+    coeffs: Vec<Scalar>,
+}
+
+#[cfg(all(feature = "std", feature = "rand_core"))]
+impl Polynomial {
+    /// Picks a random polynomial of degree `degree` with f(0) = `secret`.
+    pub(crate) fn random(secret: &Scalar, degree: usize, mut rng: impl RngCore + CryptoRng) -> Self {
+        let mut coeffs = Vec::with_capacity(degree + 1);
+        coeffs.push(*secret);
+        for _ in 0..degree {
+            coeffs.push(Scalar::random(&mut rng));
+        }
+
+        Polynomial { coeffs }
+    }
+
+    /// Evaluates the polynomial at `index`.
+    pub(crate) fn evaluate(&self, index: u32) -> Scalar {
+        let x = Scalar::from(u64::from(index));
+        self.coeffs.iter().rev().fold(Scalar::zero(), |acc, c| acc * x + c)
+    }
+
+    /// Returns the Feldman commitments a_k g2 to the coefficients, from
+    /// which anyone can compute f(i) g2 with `evaluate_commitments`.
+    pub(crate) fn commit(&self) -> Vec<G2Affine> {
+        self.coeffs.iter().map(|c| G2Affine::from(G2Projective::generator() * c)).collect()
+    }
+}
+
+#[cfg(all(feature = "std", feature = "rand_core", feature = "zeroize"))]
+impl Drop for Polynomial {
+    fn drop(&mut self) {
+        for c in self.coeffs.iter_mut() {
+            c.zeroize();
+        }
+    }
+}
+
+/// Evaluates a polynomial in the exponent: given the commitments a_k g2 to
+/// the coefficients of f, computes f(index) g2.
+#[cfg(all(feature = "std", feature = "rand_core"))]
+pub(crate) fn evaluate_commitments(commitments: &[G2Affine], index: u32) -> G2Projective {
+    let x = Scalar::from(u64::from(index));
+    commitments.iter().rev().fold(G2Projective::identity(), |acc, c| acc * x + c)
+}
+
+/// Splits `key` into `shares` shares with indices 1, ..., `shares`, any
+/// `threshold` of which can sign for it. Fails with
+/// `ErrorCode::InvalidThreshold` unless 1 <= threshold <= shares.
+///
+/// The dealer sees the whole key, so it must be trusted and should erase
+/// the key once the shares are handed out.
+#[cfg(all(feature = "std", feature = "rand_core"))]
+pub fn split(
+    key: &PrivateKey,
+    threshold: usize,
+    shares: u32,
+    rng: impl RngCore + CryptoRng,
+) -> Result<Vec<KeyShare>, ErrorCode> {
+    if threshold == 0 || threshold > shares as usize {
+        return Err(ErrorCode::InvalidThreshold);
+    }
+
+    let f = Polynomial::random(key.as_scalar(), threshold - 1, rng);
+    Ok((1..=shares)
+        .map(|index| KeyShare { index, key: PrivateKey::from_scalar(&f.evaluate(index)) })
+        .collect())
+}
+
+/// Checks that `indices` are non-zero and pairwise distinct.
+pub(crate) fn check_indices(indices: impl Iterator<Item = u32> + Clone) -> Result<(), ErrorCode> {
+    for (k, i) in indices.clone().enumerate() {
+        if i == 0 || indices.clone().skip(k + 1).any(|j| j == i) {
+            return Err(ErrorCode::InvalidShareIndex);
+        }
+    }
+
+    Ok(())
+}
+
+/// Computes the Lagrange coefficient λ_i = Π_{j ≠ i} x_j / (x_j - x_i) of
+/// the value at `index` in the value at zero of the polynomial interpolated
+/// through `indices`, which must be non-zero, distinct and include `index`.
+/// Returns none if the denominator vanishes, which distinct indices below
+/// r rule out.
+pub(crate) fn lagrange_coefficient(index: u32, indices: impl Iterator<Item = u32>) -> CtOption<Scalar> {
+    let x_i = Scalar::from(u64::from(index));
+    let mut num = Scalar::one();
+    let mut den = Scalar::one();
+    for j in indices.filter(|j| *j != index) {
+        let x_j = Scalar::from(u64::from(j));
+        num *= x_j;
+        den *= x_j - x_i;
+    }
+
+    den.invert().map(|inv| num * inv)
+}
+
+/// Combines partial signatures on one message from at least `threshold`
+/// distinct shares into the group's signature. Fails with
+/// `ErrorCode::InvalidThreshold` if there are fewer than `threshold`
+/// partials or `threshold` is zero, and with `ErrorCode::InvalidShareIndex`
+/// if two partials carry the same index.
+///
+/// A single invalid partial spoils the result without any error, so the
+/// partials should be checked with `PublicKeyShare::verify` first, or the
+/// result verified against the group public key.
+pub fn combine(partials: &[PartialSignature], threshold: usize) -> Result<Signature, ErrorCode> {
+    if threshold == 0 || partials.len() < threshold {
+        return Err(ErrorCode::InvalidThreshold);
+    }
+    let indices = partials.iter().map(|p| p.index);
+    check_indices(indices.clone())?;
+
+    let terms = partials.iter().map(|partial| {
+        let l = Option::from(lagrange_coefficient(partial.index, indices.clone())).ok_or(ErrorCode::InvalidShareIndex)?;
+        Ok((G1Affine::from(partial.sig.as_point()), l))
+    });
+    let acc = msm_chunked(terms, G1Projective::msm)?;
+
+    Ok(Signature::from_sig(&acc))
+}
+
+/// Recovers the group public key from the public keys of at least
+/// `threshold` distinct shares. Fails like `combine` on too few shares or
+/// repeated indices.
+pub fn combine_public_keys(shares: &[PublicKeyShare], threshold: usize) -> Result<PublicKey, ErrorCode> {
+    if threshold == 0 || shares.len() < threshold {
+        return Err(ErrorCode::InvalidThreshold);
+    }
+    let indices = shares.iter().map(|s| s.index);
+    check_indices(indices.clone())?;
+
+    let terms = shares.iter().map(|share| {
+        let l = Option::from(lagrange_coefficient(share.index, indices.clone())).ok_or(ErrorCode::InvalidShareIndex)?;
+        Ok((G2Affine::from(share.pk.as_point()), l))
+    });
+    let acc = msm_chunked(terms, G2Projective::msm)?;
+
+    Ok(PublicKey::from_pk(&acc))
+}
+
+#[test]
+fn test_lagrange_coefficient() {
+    // f(x) = 5 + 3x + 2x^2 through x = 1, 2, 4.
+    let f = |x: u64| Scalar::from(5 + 3 * x + 2 * x * x);
+    let indices = [1u32, 2, 4];
+    let value = indices.iter().fold(Scalar::zero(), |acc, i| {
+        acc + lagrange_coefficient(*i, indices.iter().copied()).unwrap() * f(u64::from(*i))
+    });
+    assert_eq!(value, Scalar::from(5));
+
+    assert_eq!(check_indices(indices.iter().copied()), Ok(()));
+    assert_eq!(check_indices([1u32, 2, 1].iter().copied()), Err(ErrorCode::InvalidShareIndex));
+    assert_eq!(check_indices([0u32, 2].iter().copied()), Err(ErrorCode::InvalidShareIndex));
+}
+
+#[cfg(all(feature = "std", feature = "rand_core"))]
+#[test]
+fn test_threshold_sign() {
+    use crate::bls::keys::TestRng;
+
+    let key = PrivateKey::from_scalar(&Scalar::from(0x7e57_0001));
+    let pk = key.to_public();
+    let msg = b"block 42";
+    let shares = split(&key, 3, 5, TestRng(7)).unwrap();
+    let public: Vec<PublicKeyShare> = shares.iter().map(|s| s.public_share()).collect();
+    let partials: Vec<PartialSignature> = shares.iter().map(|s| s.sign(msg).unwrap()).collect();
+
+    for (share, partial) in public.iter().zip(partials.iter()) {
+        assert_eq!(share.verify(msg, partial), Ok(()));
+        assert_eq!(share.verify(b"block 43", partial), Err(ErrorCode::VerificationFailed));
+    }
+    assert_eq!(public[0].verify(msg, &partials[1]), Err(ErrorCode::VerificationFailed));
+
+    // Any three partials give the signature of the undivided key.
+    let expected = key.sign(msg).unwrap();
+    for subset in [[0, 1, 2], [0, 2, 4], [4, 3, 1]].iter() {
+        let chosen: Vec<PartialSignature> = subset.iter().map(|i| partials[*i]).collect();
+        let sig = combine(&chosen, 3).unwrap();
+        assert_eq!(sig, expected);
+        assert_eq!(pk.verify(msg, &sig), Ok(()));
+
+        let chosen: Vec<PublicKeyShare> = subset.iter().map(|i| public[*i]).collect();
+        assert_eq!(combine_public_keys(&chosen, 3), Ok(pk));
+    }
+    assert_eq!(combine(&partials, 3), Ok(expected));
+
+    // Two partials are not enough.
+    assert_eq!(combine(&partials[..2], 3), Err(ErrorCode::InvalidThreshold));
+    assert_eq!(combine_public_keys(&public[..2], 3), Err(ErrorCode::InvalidThreshold));
+}
+
+#[cfg(all(feature = "std", feature = "rand_core"))]
+#[test]
+fn test_threshold_errors() {
+    use crate::bls::keys::TestRng;
+
+    let key = PrivateKey::from_scalar(&Scalar::from(0x7e57_0002));
+    assert_eq!(split(&key, 0, 3, TestRng(1)).err(), Some(ErrorCode::InvalidThreshold));
+    assert_eq!(split(&key, 4, 3, TestRng(1)).err(), Some(ErrorCode::InvalidThreshold));
+
+    // A 1-of-n split hands every share the key itself.
+    let shares = split(&key, 1, 3, TestRng(1)).unwrap();
+    assert!(shares.iter().all(|s| s.private_key().to_bytes() == key.to_bytes()));
+
+    let shares = split(&key, 2, 3, TestRng(1)).unwrap();
+    let partial = shares[0].sign(b"m").unwrap();
+    assert_eq!(combine(&[], 2), Err(ErrorCode::InvalidThreshold));
+    assert_eq!(combine(&[partial], 0), Err(ErrorCode::InvalidThreshold));
+    assert_eq!(combine(&[partial, partial], 2), Err(ErrorCode::InvalidShareIndex));
+    assert_eq!(PartialSignature::new(0, *partial.signature()).err(), Some(ErrorCode::InvalidShareIndex));
+    assert_eq!(KeyShare::new(0, PrivateKey::from_scalar(&Scalar::one())).err(), Some(ErrorCode::InvalidShareIndex));
+}
@@ -1,5 +1,142 @@
-#[derive(Debug)]
+use core::fmt;
+
+/// The errors returned by this crate. Across the C ABI they are reported as
+/// the non-zero `int` status code given by their discriminant, with `0`
+/// meaning success; `bls_error_string` describes a status code.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 #[repr(u16)]
 pub enum ErrorCode {
-    Error = 1,
+    /// A secret key or scalar is not the canonical encoding of an integer
+    /// below r.
+    NonCanonicalScalar = 1,
+    /// A secret key is zero.
+    ZeroKey = 2,
+    /// A point encoding is malformed: invalid flags or a coordinate that is
+    /// not a canonical field element.
+    InvalidEncoding = 3,
+    /// A point does not lie on the curve.
+    NotOnCurve = 4,
+    /// A point lies on the curve but outside the prime-order subgroup.
+    NotInSubgroup = 5,
+    /// A point is the identity where a non-trivial point is required.
+    IdentityPoint = 6,
+    /// An input has the wrong length.
+    BadLength = 7,
+    /// Hashing to the curve failed.
+    HashToCurveFailed = 8,
+    /// A signature or proof did not verify.
+    VerificationFailed = 9,
+    /// An output buffer is too small for the result.
+    BufferTooSmall = 10,
+    /// A key derivation path is malformed.
+    InvalidPath = 11,
+    /// A keystore is malformed or uses unsupported parameters.
+    InvalidKeystore = 12,
+    /// A keystore checksum did not match, most likely because the password
+    /// is wrong.
+    InvalidPassword = 13,
+    /// A pointer passed across the C ABI is null.
+    NullPointer = 14,
+    /// A fault-attack countermeasure found a computed result to be wrong;
+    /// nothing was released.
+    FaultDetected = 15,
+    /// Messages that must be distinct contain a duplicate.
+    DuplicateMessage = 16,
+    /// A key is not a member of the committee it is used with.
+    UnknownKey = 17,
+    /// A threshold is zero or exceeds the number of shares.
+    InvalidThreshold = 18,
+    /// A share index is zero, or repeated where shares must be distinct.
+    InvalidShareIndex = 19,
+    /// A protocol message is addressed to another party, comes from an
+    /// unknown one, or does not fit the protocol's parameters.
+    InvalidProtocolMessage = 20,
+}
+
+impl ErrorCode {
+    /// Returns the status code reported across the C ABI.
+    pub fn code(self) -> i32 {
+        self as i32
+    }
+
+    /// Looks up the error for a non-zero C ABI status code.
+    pub fn from_code(code: i32) -> Option<ErrorCode> {
+        use ErrorCode::*;
+        let errors = [
+            NonCanonicalScalar,
+            ZeroKey,
+            InvalidEncoding,
+            NotOnCurve,
+            NotInSubgroup,
+            IdentityPoint,
+            BadLength,
+            HashToCurveFailed,
+            VerificationFailed,
+            BufferTooSmall,
+            InvalidPath,
+            InvalidKeystore,
+            InvalidPassword,
+            NullPointer,
+            FaultDetected,
+            DuplicateMessage,
+            UnknownKey,
+            InvalidThreshold,
+            InvalidShareIndex,
+            InvalidProtocolMessage,
+        ];
+        errors.iter().copied().find(|e| e.code() == code)
+    }
+
+    /// A NUL-terminated description of the error, shared with the C ABI.
+    pub(crate) fn as_c_str(self) -> &'static str {
+        match self {
+            ErrorCode::NonCanonicalScalar => "non-canonical scalar\0",
+            ErrorCode::ZeroKey => "zero secret key\0",
+            ErrorCode::InvalidEncoding => "invalid point encoding\0",
+            ErrorCode::NotOnCurve => "point not on curve\0",
+            ErrorCode::NotInSubgroup => "point not in prime-order subgroup\0",
+            ErrorCode::IdentityPoint => "identity point\0",
+            ErrorCode::BadLength => "bad input length\0",
+            ErrorCode::HashToCurveFailed => "hash to curve failed\0",
+            ErrorCode::VerificationFailed => "verification failed\0",
+            ErrorCode::BufferTooSmall => "output buffer too small\0",
+            ErrorCode::InvalidPath => "invalid derivation path\0",
+            ErrorCode::InvalidKeystore => "invalid keystore\0",
+            ErrorCode::InvalidPassword => "invalid keystore password\0",
+            ErrorCode::NullPointer => "null pointer\0",
+            ErrorCode::FaultDetected => "fault detected\0",
+            ErrorCode::DuplicateMessage => "duplicate message\0",
+            ErrorCode::UnknownKey => "key not in committee\0",
+            ErrorCode::InvalidThreshold => "invalid threshold\0",
+            ErrorCode::InvalidShareIndex => "invalid share index\0",
+            ErrorCode::InvalidProtocolMessage => "invalid protocol message\0",
+        }
+    }
+
+    /// A short description of the error.
+    pub fn as_str(self) -> &'static str {
+        let s = self.as_c_str();
+        &s[..s.len() - 1]
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ErrorCode {}
+
+#[test]
+fn test_error_codes() {
+    for code in 1..=20 {
+        let e = ErrorCode::from_code(code).unwrap();
+        assert_eq!(e.code(), code);
+        assert!(e.as_c_str().ends_with('\0'));
+        assert!(!e.as_str().contains('\0'));
+    }
+    assert!(ErrorCode::from_code(0).is_none());
+    assert!(ErrorCode::from_code(21).is_none());
 }
@@ -1,63 +1,327 @@
+//! BLS signing over BLS12-377 for embedded devices, exported through a C ABI.
+//!
+//! Every exported function takes explicit buffer lengths and returns `BLS_OK`
+//! or an `ErrorCode` status. All encodings are little-endian, matching Celo:
+//! private keys are canonical 32-byte little-endian scalars (zero and values
+//! of at least r are rejected), and points use the uncompressed
+//! little-endian form of `to_uncompressed_littleendian`, with the flags in
+//! the last byte.
+
 #![cfg_attr(not(gen_header), no_std)]
 #![no_std]
 extern crate libc;
+#[cfg(feature = "std")]
+extern crate std;
 
 pub mod bls;
 pub mod error;
 
-use bls12_377::Scalar;
-use crate::bls::keys::PrivateKey;
-use subtle::CtOption;
-use core::ptr::copy;
-
-use core::slice;
+use crate::bls::keys::{PrivateKey, Signature};
+use crate::error::ErrorCode;
+use libc::{c_char, c_int};
+use core::ptr::copy_nonoverlapping;
+#[cfg(feature = "zeroize")]
+use zeroize_crate::Zeroize;
 
-#[cfg(not(gen_header))]
+// With `no_panic_check`, the panic handler calls a function that is defined
+// nowhere, so any panic path the optimizer fails to remove from the release
+// build shows up as an undefined reference to it. See `tests/no_panic.rs`.
+#[cfg(all(not(gen_header), not(test), not(feature = "std")))]
 #[panic_handler]
 fn my_panic(_info: &core::panic::PanicInfo) -> ! {
+    #[cfg(feature = "no_panic_check")]
+    {
+        extern "C" {
+            fn bls_embedded_panic_is_reachable() -> !;
+        }
+        unsafe { bls_embedded_panic_is_reachable() }
+    }
+
+    #[cfg(not(feature = "no_panic_check"))]
     loop {}
 }
 
-fn convert_result_to_bool<T, E, F: Fn() -> Result<T, E>>(f: F) -> bool {
-    match f() {
-        Err(e) => {
-            false
-        }
-        _ => true,
+/// The status code returned by the C ABI on success. Failures return the
+/// non-zero code of an `ErrorCode`.
+pub const BLS_OK: c_int = 0;
+
+fn status(result: Result<(), ErrorCode>) -> c_int {
+    match result {
+        Ok(()) => BLS_OK,
+        Err(e) => e.code(),
     }
 }
 
+/// Returns a static, NUL-terminated description of a status code returned
+/// by this library.
 #[no_mangle]
-pub extern "C" fn is_valid_key(in_private_key: *const u8) -> bool {
-   let pk_array = in_private_key as *const [u8; 32];
-   let priv_key = unsafe { Scalar::from_bytes(&*pk_array) } ;
-   bool::from(CtOption::is_some(&priv_key))
+pub extern "C" fn bls_error_string(status: c_int) -> *const c_char {
+    let s = match ErrorCode::from_code(status) {
+        Some(e) => e.as_c_str(),
+        None if status == BLS_OK => "success\0",
+        None => "unknown error\0",
+    };
+    s.as_ptr() as *const c_char
+}
+
+/// Copies a caller-supplied input buffer, which must be exactly `out.len()`
+/// bytes long, into `out`.
+unsafe fn read_input(ptr: *const u8, len: usize, out: &mut [u8]) -> Result<(), ErrorCode> {
+    if ptr.is_null() {
+        return Err(ErrorCode::NullPointer);
+    }
+    if len != out.len() {
+        return Err(ErrorCode::BadLength);
+    }
+
+    copy_nonoverlapping(ptr, out.as_mut_ptr(), out.len());
+    Ok(())
+}
+
+/// Copies `data` into a caller-supplied output buffer of `len` bytes.
+unsafe fn write_output(ptr: *mut u8, len: usize, data: &[u8]) -> Result<(), ErrorCode> {
+    if ptr.is_null() {
+        return Err(ErrorCode::NullPointer);
+    }
+    if len < data.len() {
+        return Err(ErrorCode::BufferTooSmall);
+    }
+
+    copy_nonoverlapping(data.as_ptr(), ptr, data.len());
+    Ok(())
+}
+
+/// Reads a private key in its canonical 32-byte little-endian encoding.
+unsafe fn read_private_key(ptr: *const u8, len: usize) -> Result<PrivateKey, ErrorCode> {
+    let mut bytes = [0u8; 32];
+    read_input(ptr, len, &mut bytes)?;
+    let key = PrivateKey::from_bytes(&bytes);
+
+    #[cfg(feature = "zeroize")]
+    bytes.zeroize();
+
+    key
+}
+
+/// Checks whether the 32 bytes at `in_private_key` are a valid private key:
+/// a canonical little-endian scalar other than zero. Returns `BLS_OK` if so,
+/// and otherwise the code of the reason the key is rejected.
+///
+/// # Safety
+///
+/// `in_private_key` must be null or point to `in_private_key_len` readable
+/// bytes.
+#[no_mangle]
+pub unsafe extern "C" fn is_valid_key(in_private_key: *const u8, in_private_key_len: usize) -> c_int {
+    status(read_private_key(in_private_key, in_private_key_len).map(|_| ()))
+}
+
+unsafe fn sign_hash_with(
+    sign: fn(&PrivateKey, &[u8; 96]) -> Result<Signature, ErrorCode>,
+    in_private_key: *const u8,
+    in_private_key_len: usize,
+    in_hash: *const u8,
+    in_hash_len: usize,
+    out_signature: *mut u8,
+    out_signature_len: usize,
+) -> c_int {
+    status((|| {
+        let private_key = read_private_key(in_private_key, in_private_key_len)?;
+        let mut hash = [0u8; 96];
+        read_input(in_hash, in_hash_len, &mut hash)?;
+        let sig = sign(&private_key, &hash)?;
+        write_output(out_signature, out_signature_len, &sig.serialize())
+    })())
 }
 
+/// Signs a 96-byte uncompressed G1 hash point, writing the 96-byte
+/// uncompressed signature to `out_signature`. `in_private_key_len` must be
+/// 32 and `in_hash_len` 96; `out_signature_len` must be at least 96. Hash
+/// points that are off the curve, outside the prime-order subgroup or the
+/// identity are rejected.
+///
+/// # Safety
+///
+/// Every pointer must be null or point to a buffer of the given length.
 #[no_mangle]
-pub extern "C" fn sign_hash(
-    in_private_key: *mut u64,
-    in_hash: *mut u8,
+pub unsafe extern "C" fn sign_hash(
+    in_private_key: *const u8,
+    in_private_key_len: usize,
+    in_hash: *const u8,
+    in_hash_len: usize,
     out_signature: *mut u8,
-) -> bool {
-    let pk_array = in_private_key as *mut [u64; 4];
-    let private_key = unsafe { PrivateKey::from_scalar(&Scalar::from_raw(*pk_array)) };
-    let hash = unsafe { slice::from_raw_parts(in_hash, 96) };
-    let mut hash_arr: [u8; 96] = [0; 96];
-    hash_arr.copy_from_slice(&hash[0..96]);
-    let sig = private_key.sign_hash(&hash_arr).unwrap();
-    let sig_arr = sig.serialize();
-    unsafe { copy(sig_arr.as_ptr(), out_signature, 96); };
-    true
+    out_signature_len: usize,
+) -> c_int {
+    sign_hash_with(
+        PrivateKey::sign_hash,
+        in_private_key,
+        in_private_key_len,
+        in_hash,
+        in_hash_len,
+        out_signature,
+        out_signature_len,
+    )
 }
 
+/// Like `sign_hash`, but without validating the hash point. Only exported
+/// with the `unchecked_sign_hash` feature, for hosts that compute the hash
+/// point themselves and cannot afford the subgroup check.
+///
+/// # Safety
+///
+/// As for `sign_hash`. In addition, the hash point must come from trusted
+/// code: signing an invalid point can reveal the private key.
+#[cfg(feature = "unchecked_sign_hash")]
 #[no_mangle]
-pub extern "C" fn get_pubkey(
-    in_private_key: *mut u64,
+pub unsafe extern "C" fn sign_hash_unchecked(
+    in_private_key: *const u8,
+    in_private_key_len: usize,
+    in_hash: *const u8,
+    in_hash_len: usize,
+    out_signature: *mut u8,
+    out_signature_len: usize,
+) -> c_int {
+    sign_hash_with(
+        PrivateKey::sign_hash_unchecked,
+        in_private_key,
+        in_private_key_len,
+        in_hash,
+        in_hash_len,
+        out_signature,
+        out_signature_len,
+    )
+}
+
+/// Like `sign_hash`, but randomizes the scalar multiplication against power
+/// and electromagnetic analysis, with scalar blinding, randomized projective
+/// coordinates and, if `point_blinding` is set, point blinding. The blinding
+/// values are derived from the key, the hash and the 32 bytes of
+/// `in_entropy`, which should come from the device RNG when there is one;
+/// pass null and 0 to derive them from the key and hash alone. The
+/// signature is the same as with `sign_hash`.
+///
+/// # Safety
+///
+/// Every pointer must be null or point to a buffer of the given length.
+#[no_mangle]
+pub unsafe extern "C" fn sign_hash_blinded(
+    in_private_key: *const u8,
+    in_private_key_len: usize,
+    in_hash: *const u8,
+    in_hash_len: usize,
+    in_entropy: *const u8,
+    in_entropy_len: usize,
+    point_blinding: bool,
+    out_signature: *mut u8,
+    out_signature_len: usize,
+) -> c_int {
+    status((|| {
+        let mut entropy = [0u8; 32];
+        let has_entropy = !(in_entropy.is_null() && in_entropy_len == 0);
+        if has_entropy {
+            read_input(in_entropy, in_entropy_len, &mut entropy)?;
+        }
+        let private_key = read_private_key(in_private_key, in_private_key_len)?;
+        let mut hash = [0u8; 96];
+        read_input(in_hash, in_hash_len, &mut hash)?;
+
+        let entropy_ref = if has_entropy { Some(&entropy) } else { None };
+        #[allow(unused_mut)]
+        let mut blinding = private_key.derive_blinding(&hash, entropy_ref, point_blinding);
+        let sig = private_key.sign_hash_blinded(&hash, &blinding);
+
+        #[cfg(feature = "zeroize")]
+        {
+            entropy.zeroize();
+            blinding.zeroize();
+        }
+
+        write_output(out_signature, out_signature_len, &sig?.serialize())
+    })())
+}
+
+/// Writes the 192-byte uncompressed public key of a private key to
+/// `out_public_key`. `in_private_key_len` must be 32 and
+/// `out_public_key_len` at least 192.
+///
+/// # Safety
+///
+/// Every pointer must be null or point to a buffer of the given length.
+#[no_mangle]
+pub unsafe extern "C" fn get_pubkey(
+    in_private_key: *const u8,
+    in_private_key_len: usize,
     out_public_key: *mut u8,
-) -> bool {
-    let private_key = unsafe { PrivateKey::from_scalar(&Scalar::from_raw(*(in_private_key as *mut [u64; 4]))) }; 
-    let pub_arr = private_key.to_public().serialize();
-    unsafe { copy(pub_arr.as_ptr(), out_public_key, 192) };
-    true
+    out_public_key_len: usize,
+) -> c_int {
+    status((|| {
+        let private_key = read_private_key(in_private_key, in_private_key_len)?;
+        write_output(out_public_key, out_public_key_len, &private_key.to_public().serialize())
+    })())
+}
+
+#[test]
+fn test_bls_error_string() {
+    use core::ffi::CStr;
+
+    let describe = |status| unsafe { CStr::from_ptr(bls_error_string(status)) }.to_str().unwrap();
+    assert_eq!(describe(BLS_OK), "success");
+    assert_eq!(describe(ErrorCode::NotInSubgroup.code()), "point not in prime-order subgroup");
+    assert_eq!(describe(-1), "unknown error");
+    assert_eq!(describe(1000), "unknown error");
+}
+
+#[test]
+fn test_ffi_buffers() {
+    use bls12_377::Scalar;
+    use core::ptr::{null, null_mut};
+
+    let sk = Scalar::from(5).to_bytes();
+    let sk_ptr = sk.as_ptr();
+    let mut pk = [0u8; 193];
+    unsafe {
+        assert_eq!(get_pubkey(sk_ptr, 32, pk.as_mut_ptr(), 193), BLS_OK);
+        assert_eq!(&pk[..192], &PrivateKey::from_scalar(&Scalar::from(5)).to_public().serialize()[..]);
+
+        assert_eq!(get_pubkey(null(), 32, pk.as_mut_ptr(), 192), ErrorCode::NullPointer.code());
+        assert_eq!(get_pubkey(sk_ptr, 32, null_mut(), 192), ErrorCode::NullPointer.code());
+        assert_eq!(get_pubkey(sk_ptr, 31, pk.as_mut_ptr(), 192), ErrorCode::BadLength.code());
+        assert_eq!(get_pubkey(sk_ptr, 32, pk.as_mut_ptr(), 191), ErrorCode::BufferTooSmall.code());
+
+        // The generator is a valid hash point.
+        let hash = bls12_377::G1Affine::generator().to_uncompressed_littleendian();
+        let mut sig = [0u8; 96];
+        assert_eq!(sign_hash(sk_ptr, 32, hash.as_ptr(), 96, sig.as_mut_ptr(), 96), BLS_OK);
+        assert_eq!(sign_hash(sk_ptr, 32, null(), 96, sig.as_mut_ptr(), 96), ErrorCode::NullPointer.code());
+        assert_eq!(sign_hash(sk_ptr, 32, hash.as_ptr(), 95, sig.as_mut_ptr(), 96), ErrorCode::BadLength.code());
+        assert_eq!(sign_hash(sk_ptr, 32, hash.as_ptr(), 96, sig.as_mut_ptr(), 95), ErrorCode::BufferTooSmall.code());
+
+        // A malformed or invalid hash is reported rather than panicking.
+        let bad_hash = [0xffu8; 96];
+        assert_eq!(sign_hash(sk_ptr, 32, bad_hash.as_ptr(), 96, sig.as_mut_ptr(), 96), ErrorCode::InvalidEncoding.code());
+        let identity = bls12_377::G1Affine::identity().to_uncompressed_littleendian();
+        assert_eq!(sign_hash(sk_ptr, 32, identity.as_ptr(), 96, sig.as_mut_ptr(), 96), ErrorCode::IdentityPoint.code());
+
+        // Blinded signatures match, with or without entropy.
+        let mut blinded = [0u8; 96];
+        assert_eq!(sign_hash_blinded(sk_ptr, 32, hash.as_ptr(), 96, null(), 0, false, blinded.as_mut_ptr(), 96), BLS_OK);
+        assert_eq!(blinded, sig);
+        let entropy = [7u8; 32];
+        assert_eq!(sign_hash_blinded(sk_ptr, 32, hash.as_ptr(), 96, entropy.as_ptr(), 32, true, blinded.as_mut_ptr(), 96), BLS_OK);
+        assert_eq!(blinded, sig);
+        assert_eq!(sign_hash_blinded(sk_ptr, 32, hash.as_ptr(), 96, null(), 32, true, blinded.as_mut_ptr(), 96), ErrorCode::NullPointer.code());
+        assert_eq!(sign_hash_blinded(sk_ptr, 32, hash.as_ptr(), 96, entropy.as_ptr(), 16, true, blinded.as_mut_ptr(), 96), ErrorCode::BadLength.code());
+
+        assert_eq!(is_valid_key(sk_ptr, 32), BLS_OK);
+        assert_eq!(is_valid_key(sk_ptr, 31), ErrorCode::BadLength.code());
+        assert_eq!(is_valid_key(null(), 32), ErrorCode::NullPointer.code());
+        assert_eq!(is_valid_key([0xffu8; 32].as_ptr(), 32), ErrorCode::NonCanonicalScalar.code());
+        assert_eq!(is_valid_key([0u8; 32].as_ptr(), 32), ErrorCode::ZeroKey.code());
+
+        // Non-canonical and zero keys are rejected by every entry point.
+        let r = [0x01, 0x00, 0x00, 0x00, 0x00, 0x80, 0x11, 0x0a, 0x01, 0x00, 0x00, 0xd0, 0xfe, 0x76, 0xaa, 0x59, 0x01, 0xb0, 0x37, 0x5c, 0x1e, 0x4d, 0xb4, 0x60, 0x56, 0xa5, 0x2c, 0x9a, 0x5e, 0x65, 0xab, 0x12u8];
+        assert_eq!(is_valid_key(r.as_ptr(), 32), ErrorCode::NonCanonicalScalar.code());
+        assert_eq!(get_pubkey(r.as_ptr(), 32, pk.as_mut_ptr(), 192), ErrorCode::NonCanonicalScalar.code());
+        assert_eq!(sign_hash([0u8; 32].as_ptr(), 32, hash.as_ptr(), 96, sig.as_mut_ptr(), 96), ErrorCode::ZeroKey.code());
+    }
 }
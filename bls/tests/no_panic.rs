@@ -0,0 +1,41 @@
+//! Checks that no panic path remains in the release staticlib.
+//!
+//! The library panics with `loop {}`, so on the device any reachable panic
+//! hangs it forever. Built with the `no_panic_check` feature, the panic
+//! handler calls an undefined function instead; once LTO has removed every
+//! panic path the optimizer can prove unreachable, the archive must not
+//! reference that function. Requires `nm` on the host.
+
+use std::path::Path;
+use std::process::Command;
+
+const PANIC_SYMBOL: &str = "bls_embedded_panic_is_reachable";
+
+#[test]
+fn test_no_panic_in_staticlib() {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let target_dir = manifest_dir.join("target").join("no-panic");
+
+    let build = Command::new(env!("CARGO"))
+        .args(["build", "--release", "--lib", "--features", "no_panic_check unchecked_sign_hash fault_check"])
+        .arg("--manifest-path")
+        .arg(manifest_dir.join("Cargo.toml"))
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .status()
+        .expect("failed to run cargo");
+    assert!(build.success(), "release build with no_panic_check failed");
+
+    let nm = Command::new("nm")
+        .arg("--undefined-only")
+        .arg(target_dir.join("release").join("libbls_embedded.a"))
+        .output()
+        .expect("failed to run nm");
+    assert!(nm.status.success());
+
+    let undefined = String::from_utf8_lossy(&nm.stdout);
+    assert!(
+        !undefined.contains(PANIC_SYMBOL),
+        "a panic path is reachable from the exported functions"
+    );
+}
@@ -137,6 +137,38 @@ fn criterion_benchmark(c: &mut Criterion) {
             })
         });
     }
+    // Multi-scalar multiplication
+    {
+        const N: usize = 64;
+        let s = Scalar::from_raw([1, 2, 3, 4]);
+        let t1 = vec![(G1Affine::generator(), s); N];
+        let t2 = vec![(G2Affine::generator(), s); N];
+        c.bench_function(&format!("G1Projective_msm_n={}", N), move |b| {
+            b.iter(|| G1Projective::msm(black_box(&t1)))
+        });
+        c.bench_function(&format!("G2Projective_msm_n={}", N), move |b| {
+            b.iter(|| G2Projective::msm(black_box(&t2)))
+        });
+    }
+    // Pairings
+    {
+        let g = G1Affine::generator();
+        let h = G2Affine::generator();
+        let prepared = G2Prepared::from(h);
+        let ml = multi_miller_loop(&[(&g, &prepared)]);
+        c.bench_function("full_pairing", move |b| {
+            b.iter(|| pairing(black_box(&g), black_box(&h)))
+        });
+        c.bench_function("G2_preparation", move |b| {
+            b.iter(|| G2Prepared::from(black_box(h)))
+        });
+        c.bench_function("miller_loop", move |b| {
+            b.iter(|| multi_miller_loop(&[(black_box(&g), black_box(&prepared))]))
+        });
+        c.bench_function("final_exponentiation", move |b| {
+            b.iter(|| black_box(ml).final_exponentiation())
+        });
+    }
     // Fp Arithmetic
     {
        let x = Fp::one();
@@ -0,0 +1,214 @@
+//! Randomization of scalar multiplication against power and electromagnetic
+//! side-channel analysis.
+//!
+//! Constant-time code hides secrets from timing, but not from differential
+//! power analysis, which correlates many traces of the same computation.
+//! `G1Projective::mul_blinded` and `G2Projective::mul_blinded` randomize
+//! every run with the values of a `Blinding`:
+//!
+//! * additive scalar blinding: the scalar s is replaced by the integer
+//!   s + k·q for a random 128-bit k, which leaves the result unchanged on the
+//!   q-order subgroup;
+//! * randomized projective coordinates: the base point (X : Y : Z) is
+//!   replaced by (λ²X : λ³Y : λZ) for a random non-zero λ;
+//! * optionally, point blinding: [e]P is computed as [e](P + R) - [e]R for a
+//!   random point R of the subgroup, at roughly twice the cost.
+//!
+//! The results are only guaranteed to match the unblinded multiplication for
+//! points of the q-order subgroup.
+
+use core::fmt;
+
+#[cfg(feature = "rand_core")]
+use rand_core::{CryptoRng, RngCore};
+use subtle::{ConditionallySelectable, ConstantTimeEq};
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
+
+use crate::fp::Fp;
+use crate::util::{adc, mac, wipe};
+use crate::Scalar;
+
+/// The order q of the scalar field, as little-endian 64-bit limbs.
+const Q: [u64; 4] = [
+    0x0a11800000000001,
+    0x59aa76fed0000001,
+    0x60b44d1e5c37b001,
+    0x12ab655e9a2ca556,
+];
+
+/// The random values used to blind one scalar multiplication. A fresh
+/// `Blinding` should be used for every multiplication. With the `zeroize`
+/// feature the values are wiped when it is dropped.
+#[derive(Clone)]
+pub struct Blinding {
+    mask: [u64; 2],
+    pub(crate) z: Fp,
+    pub(crate) point: Option<Scalar>,
+}
+
+impl Blinding {
+    /// Builds the scalar mask k and the coordinate randomizer λ from 80
+    /// uniformly random bytes: the first 16 bytes are k, and the remaining 64
+    /// are reduced into λ. Point blinding is off.
+    pub fn from_bytes(bytes: &[u8; 80]) -> Blinding {
+        let mut mask = [0u64; 2];
+        for (limb, chunk) in mask.iter_mut().zip(bytes[..16].chunks(8)) {
+            for (i, b) in chunk.iter().enumerate() {
+                *limb |= u64::from(*b) << (8 * i);
+            }
+        }
+
+        let mut wide = [0u8; 64];
+        wide.copy_from_slice(&bytes[16..]);
+        let z = Fp::from_bytes_wide(&wide);
+        wipe(&mut wide);
+
+        // λ = 0 has negligible probability, but would lose the point.
+        let z = Fp::conditional_select(&z, &Fp::one(), z.ct_eq(&Fp::zero()));
+
+        Blinding {
+            mask,
+            z,
+            point: None,
+        }
+    }
+
+    /// Enables point blinding, with the random point R = [t]G derived from
+    /// 64 uniformly random bytes reduced into t.
+    pub fn with_point_blinding(mut self, bytes: &[u8; 64]) -> Blinding {
+        self.point = Some(Scalar::from_bytes_wide(bytes));
+        self
+    }
+
+    /// Draws fresh blinding values from `rng`, with point blinding if
+    /// `point_blinding` is set.
+    #[cfg(feature = "rand_core")]
+    pub fn random(mut rng: impl RngCore + CryptoRng, point_blinding: bool) -> Blinding {
+        let mut bytes = [0u8; 80];
+        rng.fill_bytes(&mut bytes);
+        let mut blinding = Blinding::from_bytes(&bytes);
+        wipe(&mut bytes);
+
+        if point_blinding {
+            blinding.point = Some(Scalar::random(rng));
+        }
+        blinding
+    }
+
+    /// Returns the little-endian encoding of the integer s + k·q, which is
+    /// below 2^381.
+    pub(crate) fn blind_scalar(&self, s: &Scalar) -> [u8; 48] {
+        let mut bytes = s.to_bytes();
+        let mut s = [0u64; 4];
+        for (limb, chunk) in s.iter_mut().zip(bytes.chunks(8)) {
+            for (i, b) in chunk.iter().enumerate() {
+                *limb |= u64::from(*b) << (8 * i);
+            }
+        }
+        wipe(&mut bytes);
+
+        // t = k·q
+        let mut t = [0u64; 6];
+        for (i, k) in self.mask.iter().enumerate() {
+            let mut carry = 0;
+            for (j, q) in Q.iter().enumerate() {
+                let (v, c) = mac(t[i + j], *k, *q, carry);
+                t[i + j] = v;
+                carry = c;
+            }
+            t[i + 4] = carry;
+        }
+
+        // t += s
+        let mut carry = 0;
+        for (i, limb) in t.iter_mut().enumerate() {
+            let (v, c) = adc(*limb, if i < 4 { s[i] } else { 0 }, carry);
+            *limb = v;
+            carry = c;
+        }
+
+        let mut out = [0u8; 48];
+        for (limb, chunk) in t.iter().zip(out.chunks_mut(8)) {
+            for (i, b) in chunk.iter_mut().enumerate() {
+                *b = (limb >> (8 * i)) as u8;
+            }
+        }
+
+        #[cfg(feature = "zeroize")]
+        {
+            s.zeroize();
+            t.zeroize();
+        }
+
+        out
+    }
+}
+
+// The values are as secret as the scalar they blind, so they are left out.
+impl fmt::Debug for Blinding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Blinding {{ point_blinding: {}, .. }}", self.point.is_some())
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Zeroize for Blinding {
+    fn zeroize(&mut self) {
+        self.mask.zeroize();
+        self.z.zeroize();
+        if let Some(t) = self.point.as_mut() {
+            t.zeroize();
+        }
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for Blinding {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+#[test]
+fn test_blind_scalar() {
+    let s = -Scalar::one();
+
+    // With k = 0 the scalar is unchanged.
+    let blinding = Blinding::from_bytes(&[0; 80]);
+    assert_eq!(&blinding.blind_scalar(&s)[..32], &s.to_bytes()[..]);
+    assert_eq!(&blinding.blind_scalar(&s)[32..], &[0; 16][..]);
+
+    // With k = 1 it is s + q = 2q - 1.
+    let mut bytes = [0u8; 80];
+    bytes[0] = 1;
+    let e = Blinding::from_bytes(&bytes).blind_scalar(&s);
+    let two_q_minus_one = [
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x23, 0x14, 0x02, 0x00, 0x00, 0xa0, 0xfd, 0xed, 0x54,
+        0xb3, 0x02, 0x60, 0x6f, 0xb8, 0x3c, 0x9a, 0x68, 0xc1, 0xac, 0x4a, 0x59, 0x34, 0xbd, 0xca,
+        0x56, 0x25,
+    ];
+    assert_eq!(&e[..32], &two_q_minus_one[..]);
+    assert_eq!(&e[32..], &[0; 16][..]);
+
+    // The largest mask stays below 2^381.
+    let mut bytes = [0u8; 80];
+    for b in bytes[..16].iter_mut() {
+        *b = 0xff;
+    }
+    let e = Blinding::from_bytes(&bytes).blind_scalar(&s);
+    assert!(e[47] < 0x20);
+
+    // A zero λ is replaced by one.
+    assert_eq!(Blinding::from_bytes(&[0; 80]).z, Fp::one());
+}
+
+#[test]
+fn test_debug_redacted() {
+    let mut bytes = [0u8; 80];
+    bytes[0] = 0x5a;
+    let blinding = Blinding::from_bytes(&bytes);
+    assert_eq!(format!("{:?}", blinding), "Blinding { point_blinding: false, .. }");
+    let blinding = blinding.with_point_blinding(&[1; 64]);
+    assert_eq!(format!("{:?}", blinding), "Blinding { point_blinding: true, .. }");
+}
@@ -5,7 +5,11 @@ use core::fmt;
 use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 
 use byteorder::{BigEndian, ByteOrder, LittleEndian};
+#[cfg(feature = "rand_core")]
+use rand_core::{CryptoRng, RngCore};
 use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
 
 use crate::util::{adc, mac, sbb, LegendreSymbol};
 
@@ -64,6 +68,13 @@ impl PartialEq for Fp {
     }
 }
 
+#[cfg(feature = "zeroize")]
+impl Zeroize for Fp {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
 impl ConditionallySelectable for Fp {
     fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
         Fp([
@@ -97,7 +108,7 @@ const fn inv() -> u64 {
     9586122913090633727u64
 }
 
-const fn two_adicity() -> u32 {
+pub(crate) const fn two_adicity() -> u32 {
     46u32
 }
 
@@ -125,6 +136,18 @@ const fn r_squared() -> Fp {
     ])
 }
 
+/// R3 = 2^(384*3) mod p
+const fn r_cubed() -> Fp {
+    Fp([
+        0x581f532f8815de20,
+        0xe50f4148be329585,
+        0x2be8b1180449f513,
+        0x6a2a9516c804a20e,
+        0x3f72540713590cb9,
+        0x1065ab4c0e7dda5,
+    ])
+}
+
 /// c^t, where p - 1 = 2^s*t and t odd
 const fn root_of_unity() -> Fp {
    Fp([
@@ -137,7 +160,7 @@ const fn root_of_unity() -> Fp {
    ])
 }
 
-const fn t_minus_one_div_two() -> [u64; 6] {
+pub(crate) const fn t_minus_one_div_two() -> [u64; 6] {
     [
         0xba88600000010a11,
         0xc45f741290002e16,
@@ -264,6 +287,40 @@ impl Fp {
         CtOption::new(tmp, Choice::from(is_some))
     }
 
+    /// Attempts to convert a little-endian byte representation of
+    /// a scalar into an `Fp`, failing if the input is not canonical.
+    #[inline(always)]
+    pub fn from_bytes_little_endian(bytes: &[u8; 48]) -> CtOption<Fp> {
+        let mut tmp = Fp([0, 0, 0, 0, 0, 0]);
+        let modulus = modulus();
+
+        tmp.0[0] = LittleEndian::read_u64(&bytes[0..8]);
+        tmp.0[1] = LittleEndian::read_u64(&bytes[8..16]);
+        tmp.0[2] = LittleEndian::read_u64(&bytes[16..24]);
+        tmp.0[3] = LittleEndian::read_u64(&bytes[24..32]);
+        tmp.0[4] = LittleEndian::read_u64(&bytes[32..40]);
+        tmp.0[5] = LittleEndian::read_u64(&bytes[40..48]);
+
+        // Try to subtract the modulus
+        let (_, borrow) = sbb(tmp.0[0], modulus[0], 0);
+        let (_, borrow) = sbb(tmp.0[1], modulus[1], borrow);
+        let (_, borrow) = sbb(tmp.0[2], modulus[2], borrow);
+        let (_, borrow) = sbb(tmp.0[3], modulus[3], borrow);
+        let (_, borrow) = sbb(tmp.0[4], modulus[4], borrow);
+        let (_, borrow) = sbb(tmp.0[5], modulus[5], borrow);
+
+        // If the element is smaller than MODULUS then the
+        // subtraction will underflow, producing a borrow value
+        // of 0xffff...ffff. Otherwise, it'll be zero.
+        let is_some = (borrow as u8) & 1;
+
+        // Convert to Montgomery form by computing
+        // (a.R^0 * R^2) / R = a.R
+        tmp *= &r_squared();
+
+        CtOption::new(tmp, Choice::from(is_some))
+    }
+
     /// Attempts to convert a little-endian byte representation of
     /// a scalar into an `Fp`, failing if the input is not canonical.
     /// This is not constant time
@@ -342,6 +399,47 @@ impl Fp {
         res
     }
 
+    /// Returns a uniformly random element of the field, obtained by
+    /// reducing 512 bits drawn from `rng` by the modulus.
+    #[cfg(feature = "rand_core")]
+    pub fn random(mut rng: impl RngCore + CryptoRng) -> Fp {
+        let mut buf = [0; 64];
+        rng.fill_bytes(&mut buf);
+        Fp::from_bytes_wide(&buf)
+    }
+
+    /// Converts a 512-bit big endian integer into
+    /// an `Fp` by reducing by the modulus.
+    pub fn from_bytes_wide(bytes: &[u8; 64]) -> Fp {
+        Fp::from_u512([
+            BigEndian::read_u64(&bytes[56..64]),
+            BigEndian::read_u64(&bytes[48..56]),
+            BigEndian::read_u64(&bytes[40..48]),
+            BigEndian::read_u64(&bytes[32..40]),
+            BigEndian::read_u64(&bytes[24..32]),
+            BigEndian::read_u64(&bytes[16..24]),
+            BigEndian::read_u64(&bytes[8..16]),
+            BigEndian::read_u64(&bytes[0..8]),
+        ])
+    }
+
+    fn from_u512(limbs: [u64; 8]) -> Fp {
+        // We reduce an arbitrary 512-bit number by decomposing it into a 384-bit digit
+        // and a 128-bit digit, with the higher bits multiplied by 2^384. Thus, we perform
+        // two reductions
+        //
+        // 1. the lower bits are multiplied by R^2, as normal
+        // 2. the upper bits are multiplied by R^2 * 2^384 = R^3
+        //
+        // and computing their sum in the field. Both products are smaller than R
+        // multiplied by the modulus, which is all the Montgomery reduction requires,
+        // even though the lower digit may itself exceed the modulus.
+        let d0 = Fp([limbs[0], limbs[1], limbs[2], limbs[3], limbs[4], limbs[5]]);
+        let d1 = Fp([limbs[6], limbs[7], 0, 0, 0, 0]);
+        // Convert to Montgomery form
+        d0 * r_squared() + d1 * r_cubed()
+    }
+
     /// Returns whether or not this element is strictly lexicographically
     /// larger than its negation.
     pub fn lexicographically_largest(&self) -> Choice {
@@ -717,10 +815,48 @@ impl Fp {
     #[inline(always)]
     pub fn mul(&self, rhs: &Fp) -> Fp {
         let res = self.mul_helper(&rhs);
+        #[cfg(feature = "fault_injection")]
+        let res = fault_injection::corrupt(res);
         Fp(res).subtract_p()
     }
 }
 
+/// A test-only hook that corrupts the result of a chosen `Fp::mul`, used to
+/// check that fault-attack countermeasures notice glitched arithmetic. The
+/// countdown is per thread, so concurrently running tests do not interfere.
+#[cfg(feature = "fault_injection")]
+#[doc(hidden)]
+pub mod fault_injection {
+    use core::cell::Cell;
+
+    std::thread_local! {
+        static COUNTDOWN: Cell<u64> = const { Cell::new(0) };
+    }
+
+    /// Arms a single fault: the `n`th `Fp::mul` from now on this thread
+    /// returns a result with a bit flipped. Zero disarms the fault.
+    pub fn inject_after(n: u64) {
+        COUNTDOWN.with(|c| c.set(n));
+    }
+
+    /// Returns true if an armed fault has not been triggered yet.
+    pub fn is_armed() -> bool {
+        COUNTDOWN.with(|c| c.get() != 0)
+    }
+
+    pub(crate) fn corrupt(mut res: [u64; 6]) -> [u64; 6] {
+        COUNTDOWN.with(|c| match c.get() {
+            0 => {}
+            1 => {
+                c.set(0);
+                res[0] ^= 1;
+            }
+            n => c.set(n - 1),
+        });
+        res
+    }
+}
+
 #[test]
 fn test_conditional_selection() {
     let a = Fp([1, 2, 3, 4, 5, 6]);
@@ -1071,3 +1207,82 @@ fn test_lexicographic_largest() {
         .lexicographically_largest()
     ));
 }
+
+#[test]
+fn test_from_u512_zero() {
+    let modulus = modulus();
+    assert_eq!(
+        Fp::zero(),
+        Fp::from_u512([
+            modulus[0], modulus[1], modulus[2], modulus[3], modulus[4], modulus[5], 0, 0
+        ])
+    );
+}
+
+#[test]
+fn test_from_u512_r() {
+    assert_eq!(r1(), Fp::from_u512([1, 0, 0, 0, 0, 0, 0, 0]));
+}
+
+#[test]
+fn test_from_u512_r_squared() {
+    assert_eq!(r_squared(), Fp::from_u512([0, 0, 0, 0, 0, 0, 1, 0]));
+}
+
+#[test]
+fn test_from_u512_max() {
+    let max_u64 = 0xffffffffffffffff;
+    assert_eq!(
+        Fp::from_raw_unchecked([
+            0x95f72a240d77eb28,
+            0x153194f53171dce3,
+            0x26fbc80d32354c3b,
+            0x1025a1162af6acd4,
+            0x32233c4440dd182f,
+            0x7f7ea6e5cc6702,
+        ]),
+        Fp::from_u512([max_u64, max_u64, max_u64, max_u64, max_u64, max_u64, max_u64, max_u64])
+    );
+}
+
+#[test]
+fn test_from_bytes_wide_negative_one() {
+    let mut bytes = [0u8; 64];
+    bytes[16..].copy_from_slice(&(-&Fp::one()).to_bytes());
+    assert_eq!(-&Fp::one(), Fp::from_bytes_wide(&bytes));
+}
+
+#[test]
+fn test_from_bytes_wide_max() {
+    assert_eq!(
+        Fp::from_u512([0xffffffffffffffff; 8]),
+        Fp::from_bytes_wide(&[0xff; 64])
+    );
+}
+
+#[cfg(feature = "rand_core")]
+#[test]
+fn test_random() {
+    use rand_chacha::rand_core::SeedableRng;
+
+    let mut rng = rand_chacha::ChaChaRng::from_seed([0u8; 32]);
+    let a = Fp::random(&mut rng);
+    let b = Fp::random(&mut rng);
+    assert!(a != b);
+    assert!(a != Fp::zero());
+    assert!(b != Fp::zero());
+}
+
+#[cfg(feature = "fault_injection")]
+#[test]
+fn test_fault_injection() {
+    let a = Fp::one() + Fp::one();
+    let expected = a * a;
+
+    fault_injection::inject_after(2);
+    assert_eq!(a * a, expected);
+    assert!(fault_injection::is_armed());
+    assert!(a * a != expected);
+    assert!(!fault_injection::is_armed());
+    assert_eq!(a * a, expected);
+}
@@ -0,0 +1,310 @@
+//! This module implements arithmetic over the extension field
+//! Fp12 = Fp6[w] / (w^2 - v).
+
+use core::fmt;
+use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+
+use crate::fp::Fp;
+use crate::fp2::Fp2;
+use crate::fp6::Fp6;
+
+/// u^((p - 1) / 6), by which the Frobenius map multiplies c1.
+const FROBENIUS_COEFF_C1: Fp = Fp::from_raw_unchecked([
+    0x6ec47a04a3f7ca9e,
+    0xa42e0cb968c1fa44,
+    0x578d5187fbd2bd23,
+    0x930eeb0ac79dd4bd,
+    0xa24883de1e09a9ee,
+    0xdaa7058067d46f,
+]);
+
+/// This represents an element $c_0 + c_1 w$ of $\mathbb{F}_{p^12} = \mathbb{F}_{p^6} / w^2 - v$.
+#[derive(Copy, Clone)]
+pub struct Fp12 {
+    pub c0: Fp6,
+    pub c1: Fp6,
+}
+
+impl From<Fp> for Fp12 {
+    fn from(f: Fp) -> Fp12 {
+        Fp12 {
+            c0: Fp6::from(Fp2::from(f)),
+            c1: Fp6::zero(),
+        }
+    }
+}
+
+impl From<Fp2> for Fp12 {
+    fn from(f: Fp2) -> Fp12 {
+        Fp12 {
+            c0: Fp6::from(f),
+            c1: Fp6::zero(),
+        }
+    }
+}
+
+impl From<Fp6> for Fp12 {
+    fn from(f: Fp6) -> Fp12 {
+        Fp12 {
+            c0: f,
+            c1: Fp6::zero(),
+        }
+    }
+}
+
+impl PartialEq for Fp12 {
+    fn eq(&self, other: &Fp12) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Eq for Fp12 {}
+
+impl Default for Fp12 {
+    fn default() -> Self {
+        Fp12::zero()
+    }
+}
+
+impl fmt::Debug for Fp12 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?} + ({:?})*w", self.c0, self.c1)
+    }
+}
+
+impl ConditionallySelectable for Fp12 {
+    #[inline(always)]
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Fp12 {
+            c0: Fp6::conditional_select(&a.c0, &b.c0, choice),
+            c1: Fp6::conditional_select(&a.c1, &b.c1, choice),
+        }
+    }
+}
+
+impl ConstantTimeEq for Fp12 {
+    #[inline(always)]
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.c0.ct_eq(&other.c0) & self.c1.ct_eq(&other.c1)
+    }
+}
+
+impl Fp12 {
+    #[inline]
+    pub fn zero() -> Self {
+        Fp12 {
+            c0: Fp6::zero(),
+            c1: Fp6::zero(),
+        }
+    }
+
+    #[inline]
+    pub fn one() -> Self {
+        Fp12 {
+            c0: Fp6::one(),
+            c1: Fp6::zero(),
+        }
+    }
+
+    /// Multiplies by the sparse element $c_0 + (c_3 + c_4 v) w$, the form
+    /// of the line functions of the Miller loop.
+    pub fn mul_by_034(&self, c0: &Fp2, c3: &Fp2, c4: &Fp2) -> Fp12 {
+        let a = Fp6 {
+            c0: self.c0.c0 * c0,
+            c1: self.c0.c1 * c0,
+            c2: self.c0.c2 * c0,
+        };
+        let b = self.c1.mul_by_01(c3, c4);
+        let e = (self.c0 + self.c1).mul_by_01(&(c0 + c3), c4);
+
+        Fp12 {
+            c0: b.mul_by_nonresidue() + a,
+            c1: e - (a + b),
+        }
+    }
+
+    pub fn is_zero(&self) -> Choice {
+        self.c0.is_zero() & self.c1.is_zero()
+    }
+
+    #[inline(always)]
+    pub fn conjugate(&self) -> Self {
+        Fp12 {
+            c0: self.c0,
+            c1: -self.c1,
+        }
+    }
+
+    /// Raises this element to p.
+    #[inline(always)]
+    pub fn frobenius_map(&self) -> Self {
+        Fp12 {
+            c0: self.c0.frobenius_map(),
+            c1: self.c1.frobenius_map().mul_by_fp(&FROBENIUS_COEFF_C1),
+        }
+    }
+
+    #[inline]
+    pub fn square(&self) -> Self {
+        let ab = self.c0 * self.c1;
+        let c0c1 = self.c0 + self.c1;
+        let c0 = (self.c1.mul_by_nonresidue() + self.c0) * c0c1 - ab - ab.mul_by_nonresidue();
+
+        Fp12 { c0, c1: ab + ab }
+    }
+
+    pub fn invert(&self) -> CtOption<Self> {
+        (self.c0.square() - self.c1.square().mul_by_nonresidue())
+            .invert()
+            .map(|t| Fp12 {
+                c0: self.c0 * t,
+                c1: self.c1 * -t,
+            })
+    }
+}
+
+impl<'b> Mul<&'b Fp12> for &Fp12 {
+    type Output = Fp12;
+
+    #[inline]
+    fn mul(self, other: &'b Fp12) -> Self::Output {
+        let aa = self.c0 * other.c0;
+        let bb = self.c1 * other.c1;
+        let o = other.c0 + other.c1;
+        let c1 = (self.c1 + self.c0) * o - aa - bb;
+        let c0 = bb.mul_by_nonresidue() + aa;
+
+        Fp12 { c0, c1 }
+    }
+}
+
+impl<'b> Add<&'b Fp12> for &Fp12 {
+    type Output = Fp12;
+
+    #[inline]
+    fn add(self, rhs: &'b Fp12) -> Self::Output {
+        Fp12 {
+            c0: self.c0 + rhs.c0,
+            c1: self.c1 + rhs.c1,
+        }
+    }
+}
+
+impl Neg for &Fp12 {
+    type Output = Fp12;
+
+    #[inline]
+    fn neg(self) -> Self::Output {
+        Fp12 {
+            c0: -self.c0,
+            c1: -self.c1,
+        }
+    }
+}
+
+impl Neg for Fp12 {
+    type Output = Fp12;
+
+    #[inline]
+    fn neg(self) -> Self::Output {
+        -&self
+    }
+}
+
+impl<'b> Sub<&'b Fp12> for &Fp12 {
+    type Output = Fp12;
+
+    #[inline]
+    fn sub(self, rhs: &'b Fp12) -> Self::Output {
+        Fp12 {
+            c0: self.c0 - rhs.c0,
+            c1: self.c1 - rhs.c1,
+        }
+    }
+}
+
+impl_binops_additive!(Fp12, Fp12);
+impl_binops_multiplicative!(Fp12, Fp12);
+
+#[cfg(test)]
+fn test_element(seed: u64) -> Fp12 {
+    let fp = |i: u64| {
+        let mut bytes = [0u8; 64];
+        for (j, b) in bytes.iter_mut().enumerate() {
+            *b = (seed.wrapping_mul(0x9e37_79b9_7f4a_7c15).wrapping_add(i * 131 + j as u64) >> 7) as u8;
+        }
+        Fp::from_bytes_wide(&bytes)
+    };
+    let fp2 = |i: u64| Fp2 { c0: fp(2 * i), c1: fp(2 * i + 1) };
+    Fp12 {
+        c0: Fp6 { c0: fp2(0), c1: fp2(1), c2: fp2(2) },
+        c1: Fp6 { c0: fp2(3), c1: fp2(4), c2: fp2(5) },
+    }
+}
+
+#[cfg(test)]
+fn pow_vartime(f: &Fp12, by: &[u64]) -> Fp12 {
+    let mut res = Fp12::one();
+    for e in by.iter().rev() {
+        for i in (0..64).rev() {
+            res = res.square();
+            if ((*e >> i) & 1) == 1 {
+                res *= f;
+            }
+        }
+    }
+    res
+}
+
+#[test]
+fn test_arithmetic() {
+    let a = test_element(1);
+    let b = test_element(2);
+    let c = test_element(3);
+
+    // w^2 = v
+    let w = Fp12 {
+        c0: Fp6::zero(),
+        c1: Fp6::one(),
+    };
+    let v = Fp6 {
+        c0: Fp2::zero(),
+        c1: Fp2::one(),
+        c2: Fp2::zero(),
+    };
+    assert_eq!(w * w, Fp12::from(v));
+
+    assert_eq!(a.square(), a * a);
+    assert_eq!((a + b) * c, a * c + b * c);
+    assert_eq!(a * b, b * a);
+    assert_eq!(a - a, Fp12::zero());
+    assert_eq!(a * a.invert().unwrap(), Fp12::one());
+    assert!(bool::from(Fp12::zero().invert().is_none()));
+
+    let sparse = Fp12 {
+        c0: Fp6::from(b.c0.c0),
+        c1: Fp6 {
+            c0: b.c1.c0,
+            c1: b.c1.c1,
+            c2: Fp2::zero(),
+        },
+    };
+    assert_eq!(a.mul_by_034(&b.c0.c0, &b.c1.c0, &b.c1.c1), a * sparse);
+}
+
+#[test]
+fn test_frobenius() {
+    let a = test_element(4);
+
+    // The Frobenius map raises to p.
+    assert_eq!(a.frobenius_map(), pow_vartime(&a, &crate::fp::modulus()));
+
+    // The conjugate is the p^6-th power.
+    let mut f = a;
+    for _ in 0..6 {
+        f = f.frobenius_map();
+    }
+    assert_eq!(f, a.conjugate());
+}
@@ -3,7 +3,11 @@
 use core::fmt;
 use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Not, Sub, SubAssign};
 
+#[cfg(feature = "rand_core")]
+use rand_core::{CryptoRng, RngCore};
 use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
 
 use crate::fp::{Fp};
 use crate::util::LegendreSymbol;
@@ -63,6 +67,14 @@ impl PartialEq for Fp2 {
     }
 }
 
+#[cfg(feature = "zeroize")]
+impl Zeroize for Fp2 {
+    fn zeroize(&mut self) {
+        self.c0.zeroize();
+        self.c1.zeroize();
+    }
+}
+
 impl ConditionallySelectable for Fp2 {
     #[inline]
     fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
@@ -143,6 +155,15 @@ impl Fp2 {
         self.c0.is_zero() & self.c1.is_zero()
     }
 
+    /// Returns a uniformly random element of the field.
+    #[cfg(feature = "rand_core")]
+    pub fn random(mut rng: impl RngCore + CryptoRng) -> Fp2 {
+        Fp2 {
+            c0: Fp::random(&mut rng),
+            c1: Fp::random(&mut rng),
+        }
+    }
+
     /// Raises this element to p.
     pub fn frobenius_map(&self) -> Self {
         // This is always just a conjugation
@@ -168,6 +189,28 @@ impl Fp2 {
         }
     }
 
+    /// Multiplies by u, the nonresidue over which Fp6 is built.
+    pub fn mul_by_u(&self) -> Fp2 {
+        // Multiply a + bu by u, getting
+        // au + bu^2
+        // and because u^2 = \beta, we get
+        // \beta b + au
+
+        Fp2 {
+            c0: nonresidue() * self.c1,
+            c1: self.c0,
+        }
+    }
+
+    /// Multiplies by an element of the base field.
+    #[inline(always)]
+    pub(crate) fn mul_by_fp(&self, rhs: &Fp) -> Fp2 {
+        Fp2 {
+            c0: self.c0 * rhs,
+            c1: self.c1 * rhs,
+        }
+    }
+
     /// Returns whether or not this element is strictly lexicographically
     /// larger than its negation.
     pub fn lexicographically_largest(&self) -> Choice {
@@ -322,6 +365,23 @@ impl Fp2 {
         }
         res
     }
+
+    /// Exponentiates `self` by `by`, where `by` is a little-endian
+    /// order integer of arbitrary length. Like `pow_vartime`, this is
+    /// only variable time with respect to the exponent.
+    pub fn pow_vartime_extended(&self, by: &[u64]) -> Self {
+        let mut res = Self::one();
+        for e in by.iter().rev() {
+            for i in (0..64).rev() {
+                res = res.square();
+
+                if ((*e >> i) & 1) == 1 {
+                    res *= self;
+                }
+            }
+        }
+        res
+    }
 }
 
 #[test]
@@ -523,6 +583,21 @@ fn test_multiplication() {
     assert_eq!(a * b, c);
 }
 
+#[test]
+fn test_mul_by_u() {
+    let a = Fp2 {
+        c0: Fp::from_raw_unchecked([1, 2, 3, 4, 5, 6]),
+        c1: Fp::from_raw_unchecked([7, 8, 9, 10, 11, 12]),
+    };
+    let u = Fp2 {
+        c0: Fp::zero(),
+        c1: Fp::one(),
+    };
+
+    assert_eq!(a.mul_by_u(), a * u);
+    assert_eq!(u.mul_by_u(), Fp2::from(nonresidue()));
+}
+
 #[test]
 fn test_addition() {
     let a = Fp2 {
@@ -805,3 +880,16 @@ fn test_lexicographic_largest() {
         .lexicographically_largest()
     ));
 }
+
+#[cfg(feature = "rand_core")]
+#[test]
+fn test_random() {
+    use rand_chacha::rand_core::SeedableRng;
+
+    let mut rng = rand_chacha::ChaChaRng::from_seed([0u8; 32]);
+    let a = Fp2::random(&mut rng);
+    let b = Fp2::random(&mut rng);
+    assert!(a != b);
+    assert!(a != Fp2::zero());
+    assert!(b != Fp2::zero());
+}
@@ -0,0 +1,359 @@
+//! This module implements arithmetic over the sextic extension field
+//! Fp6 = Fp2[v] / (v^3 - u).
+
+use core::fmt;
+use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+
+use crate::fp::Fp;
+use crate::fp2::Fp2;
+
+/// u^((p - 1) / 3), by which the Frobenius map multiplies c1.
+const FROBENIUS_COEFF_C1: Fp = Fp::from_raw_unchecked([
+    0x5892506da58478da,
+    0x133366940ac2a74b,
+    0x9b64a150cdf726cf,
+    0x5cc426090a9c587e,
+    0x5cf848adfdcd640c,
+    0x4702bf3ac02380,
+]);
+
+/// u^(2(p - 1) / 3), by which the Frobenius map multiplies c2.
+const FROBENIUS_COEFF_C2: Fp = Fp::from_raw_unchecked([
+    0xdacd106da5847973,
+    0xd8fe2454bac2a79a,
+    0x1ada4fd6fd832edc,
+    0xfb9868449d150908,
+    0xd63eb8aeea32285e,
+    0x167d6a36f873fd0,
+]);
+
+/// This represents an element $c_0 + c_1 v + c_2 v^2$ of $\mathbb{F}_{p^6} = \mathbb{F}_{p^2} / v^3 - u$.
+#[derive(Copy, Clone)]
+pub struct Fp6 {
+    pub c0: Fp2,
+    pub c1: Fp2,
+    pub c2: Fp2,
+}
+
+impl From<Fp2> for Fp6 {
+    fn from(f: Fp2) -> Fp6 {
+        Fp6 {
+            c0: f,
+            c1: Fp2::zero(),
+            c2: Fp2::zero(),
+        }
+    }
+}
+
+impl PartialEq for Fp6 {
+    fn eq(&self, other: &Fp6) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Eq for Fp6 {}
+
+impl fmt::Debug for Fp6 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?} + ({:?})*v + ({:?})*v^2", self.c0, self.c1, self.c2)
+    }
+}
+
+impl ConditionallySelectable for Fp6 {
+    #[inline(always)]
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Fp6 {
+            c0: Fp2::conditional_select(&a.c0, &b.c0, choice),
+            c1: Fp2::conditional_select(&a.c1, &b.c1, choice),
+            c2: Fp2::conditional_select(&a.c2, &b.c2, choice),
+        }
+    }
+}
+
+impl ConstantTimeEq for Fp6 {
+    #[inline(always)]
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.c0.ct_eq(&other.c0) & self.c1.ct_eq(&other.c1) & self.c2.ct_eq(&other.c2)
+    }
+}
+
+impl Default for Fp6 {
+    fn default() -> Self {
+        Fp6::zero()
+    }
+}
+
+impl Fp6 {
+    #[inline]
+    pub fn zero() -> Self {
+        Fp6 {
+            c0: Fp2::zero(),
+            c1: Fp2::zero(),
+            c2: Fp2::zero(),
+        }
+    }
+
+    #[inline]
+    pub fn one() -> Self {
+        Fp6 {
+            c0: Fp2::one(),
+            c1: Fp2::zero(),
+            c2: Fp2::zero(),
+        }
+    }
+
+    pub fn is_zero(&self) -> Choice {
+        self.c0.is_zero() & self.c1.is_zero() & self.c2.is_zero()
+    }
+
+    /// Multiplies by $c_1 v$.
+    pub fn mul_by_1(&self, c1: &Fp2) -> Fp6 {
+        Fp6 {
+            c0: (self.c2 * c1).mul_by_u(),
+            c1: self.c0 * c1,
+            c2: self.c1 * c1,
+        }
+    }
+
+    /// Multiplies by $c_0 + c_1 v$.
+    pub fn mul_by_01(&self, c0: &Fp2, c1: &Fp2) -> Fp6 {
+        let a_a = self.c0 * c0;
+        let b_b = self.c1 * c1;
+
+        let t1 = (((self.c1 + self.c2) * c1) - b_b).mul_by_u() + a_a;
+        let t2 = (c0 + c1) * (self.c0 + self.c1) - a_a - b_b;
+        let t3 = (self.c0 + self.c2) * c0 - a_a + b_b;
+
+        Fp6 {
+            c0: t1,
+            c1: t2,
+            c2: t3,
+        }
+    }
+
+    /// Multiplies by the nonresidue v over which Fp12 is built.
+    pub fn mul_by_nonresidue(&self) -> Self {
+        // Given a + bv + cv^2, this produces
+        //     av + bv^2 + cv^3
+        // but because v^3 = u, we have
+        //     cu + av + bv^2
+
+        Fp6 {
+            c0: self.c2.mul_by_u(),
+            c1: self.c0,
+            c2: self.c1,
+        }
+    }
+
+    /// Multiplies each coefficient by an element of the base field.
+    #[inline(always)]
+    pub(crate) fn mul_by_fp(&self, rhs: &Fp) -> Fp6 {
+        Fp6 {
+            c0: self.c0.mul_by_fp(rhs),
+            c1: self.c1.mul_by_fp(rhs),
+            c2: self.c2.mul_by_fp(rhs),
+        }
+    }
+
+    /// Raises this element to p.
+    #[inline(always)]
+    pub fn frobenius_map(&self) -> Self {
+        Fp6 {
+            c0: self.c0.frobenius_map(),
+            c1: self.c1.frobenius_map().mul_by_fp(&FROBENIUS_COEFF_C1),
+            c2: self.c2.frobenius_map().mul_by_fp(&FROBENIUS_COEFF_C2),
+        }
+    }
+
+    #[inline]
+    pub fn square(&self) -> Self {
+        // CH-SQR2 from "Multiplication and Squaring on Pairing-Friendly
+        // Fields" (Devegili, O hEigeartaigh, Scott and Dahab).
+        let s0 = self.c0.square();
+        let ab = self.c0 * self.c1;
+        let s1 = ab + ab;
+        let s2 = (self.c0 - self.c1 + self.c2).square();
+        let bc = self.c1 * self.c2;
+        let s3 = bc + bc;
+        let s4 = self.c2.square();
+
+        Fp6 {
+            c0: s3.mul_by_u() + s0,
+            c1: s4.mul_by_u() + s1,
+            c2: s1 + s2 + s3 - s0 - s4,
+        }
+    }
+
+    #[inline]
+    pub fn invert(&self) -> CtOption<Self> {
+        let c0 = self.c0.square() - (self.c1 * self.c2).mul_by_u();
+        let c1 = self.c2.square().mul_by_u() - self.c0 * self.c1;
+        let c2 = self.c1.square() - self.c0 * self.c2;
+
+        let tmp = ((self.c1 * c2) + (self.c2 * c1)).mul_by_u() + self.c0 * c0;
+
+        tmp.invert().map(|t| Fp6 {
+            c0: t * c0,
+            c1: t * c1,
+            c2: t * c2,
+        })
+    }
+
+    #[inline]
+    pub fn mul(&self, b: &Fp6) -> Fp6 {
+        // Karatsuba multiplication, reducing v^3 = u.
+        let aa = self.c0 * b.c0;
+        let bb = self.c1 * b.c1;
+        let cc = self.c2 * b.c2;
+
+        let c0 = ((self.c1 + self.c2) * (b.c1 + b.c2) - bb - cc).mul_by_u() + aa;
+        let c1 = (self.c0 + self.c1) * (b.c0 + b.c1) - aa - bb + cc.mul_by_u();
+        let c2 = (self.c0 + self.c2) * (b.c0 + b.c2) - aa + bb - cc;
+
+        Fp6 { c0, c1, c2 }
+    }
+
+    #[inline]
+    pub fn add(&self, rhs: &Fp6) -> Fp6 {
+        Fp6 {
+            c0: self.c0 + rhs.c0,
+            c1: self.c1 + rhs.c1,
+            c2: self.c2 + rhs.c2,
+        }
+    }
+
+    #[inline]
+    pub fn neg(&self) -> Fp6 {
+        Fp6 {
+            c0: -self.c0,
+            c1: -self.c1,
+            c2: -self.c2,
+        }
+    }
+
+    #[inline]
+    pub fn sub(&self, rhs: &Fp6) -> Fp6 {
+        Fp6 {
+            c0: self.c0 - rhs.c0,
+            c1: self.c1 - rhs.c1,
+            c2: self.c2 - rhs.c2,
+        }
+    }
+}
+
+impl<'b> Mul<&'b Fp6> for &Fp6 {
+    type Output = Fp6;
+
+    #[inline]
+    fn mul(self, other: &'b Fp6) -> Self::Output {
+        self.mul(other)
+    }
+}
+
+impl<'b> Add<&'b Fp6> for &Fp6 {
+    type Output = Fp6;
+
+    #[inline]
+    fn add(self, rhs: &'b Fp6) -> Self::Output {
+        self.add(rhs)
+    }
+}
+
+impl Neg for &Fp6 {
+    type Output = Fp6;
+
+    #[inline]
+    fn neg(self) -> Self::Output {
+        self.neg()
+    }
+}
+
+impl Neg for Fp6 {
+    type Output = Fp6;
+
+    #[inline]
+    fn neg(self) -> Self::Output {
+        -&self
+    }
+}
+
+impl<'b> Sub<&'b Fp6> for &Fp6 {
+    type Output = Fp6;
+
+    #[inline]
+    fn sub(self, rhs: &'b Fp6) -> Self::Output {
+        self.sub(rhs)
+    }
+}
+
+impl_binops_additive!(Fp6, Fp6);
+impl_binops_multiplicative!(Fp6, Fp6);
+
+#[cfg(test)]
+fn test_element(seed: u64) -> Fp6 {
+    let fp = |i: u64| {
+        let mut bytes = [0u8; 64];
+        for (j, b) in bytes.iter_mut().enumerate() {
+            *b = (seed.wrapping_mul(0x9e37_79b9_7f4a_7c15).wrapping_add(i * 131 + j as u64) >> 7) as u8;
+        }
+        Fp::from_bytes_wide(&bytes)
+    };
+    Fp6 {
+        c0: Fp2 { c0: fp(0), c1: fp(1) },
+        c1: Fp2 { c0: fp(2), c1: fp(3) },
+        c2: Fp2 { c0: fp(4), c1: fp(5) },
+    }
+}
+
+#[test]
+fn test_arithmetic() {
+    let a = test_element(1);
+    let b = test_element(2);
+    let c = test_element(3);
+
+    // v^3 = u
+    let v = Fp6 {
+        c0: Fp2::zero(),
+        c1: Fp2::one(),
+        c2: Fp2::zero(),
+    };
+    let u = Fp2 {
+        c0: Fp::zero(),
+        c1: Fp::one(),
+    };
+    assert_eq!(v * v * v, Fp6::from(u));
+    assert_eq!(a.mul_by_nonresidue(), a * v);
+
+    assert_eq!(a.square(), a * a);
+    assert_eq!((a + b) * c, a * c + b * c);
+    assert_eq!(a * b, b * a);
+    assert_eq!(a - a, Fp6::zero());
+    assert_eq!(a + (-a), Fp6::zero());
+
+    assert_eq!(a * a.invert().unwrap(), Fp6::one());
+    assert!(bool::from(Fp6::zero().invert().is_none()));
+
+    assert_eq!(a.mul_by_1(&b.c1), a * Fp6 { c0: Fp2::zero(), c1: b.c1, c2: Fp2::zero() });
+    assert_eq!(a.mul_by_01(&b.c0, &b.c1), a * Fp6 { c0: b.c0, c1: b.c1, c2: Fp2::zero() });
+}
+
+#[test]
+fn test_frobenius() {
+    let a = test_element(4);
+    let b = test_element(5);
+
+    // The Frobenius map is a ring homomorphism of order 6.
+    assert_eq!((a * b).frobenius_map(), a.frobenius_map() * b.frobenius_map());
+    let mut f = a;
+    for _ in 0..6 {
+        f = f.frobenius_map();
+    }
+    assert_eq!(f, a);
+    assert!(a.frobenius_map() != a);
+
+    // It raises to p, which by Fermat fixes Fp.
+    let x = Fp6::from(Fp2::from(a.c0.c0));
+    assert_eq!(x.frobenius_map(), x);
+}
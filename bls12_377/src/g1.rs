@@ -2,9 +2,15 @@
 
 use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 
+#[cfg(feature = "rand_core")]
+use rand_core::{CryptoRng, RngCore};
 use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
 
 use crate::fp::Fp;
+use crate::blinding::Blinding;
+use crate::util::wipe;
 use crate::Scalar;
 
 /// This is an element of $\mathbb{G}_1$ represented in the affine coordinate space.
@@ -235,7 +241,7 @@ impl G1Affine {
     }
 
     /// Serializes this element into uncompressed form in
-    /// big-endian byte order. Assumes the point given is not infinity.
+    /// little-endian byte order.
     #[inline(always)]
     pub fn to_uncompressed_littleendian(&self) -> [u8; 96] {
         let mut res = [0; 96];
@@ -247,6 +253,10 @@ impl G1Affine {
             &Fp::conditional_select(&self.y, &Fp::zero(), self.infinity).to_bytes_littleendian()[..],
         );
 
+        // Is this point at infinity? If so, set the second-most significant bit
+        // of the last byte.
+        res[95] |= u8::conditional_select(&0u8, &(1u8 << 6), self.infinity);
+
         res
     }
 
@@ -425,6 +435,143 @@ impl G1Affine {
         }
     }
 
+    /// Serializes this element into compressed form in little-endian byte
+    /// order, as used by arkworks and Celo. The flags live in the two most
+    /// significant bits of the last byte: bit 7 is set if the y-coordinate is
+    /// the lexicographically largest, and bit 6 is set for the point at infinity.
+    pub fn to_compressed_le(&self) -> [u8; 48] {
+        let mut res =
+            Fp::conditional_select(&self.x, &Fp::zero(), self.infinity).to_bytes_littleendian();
+
+        // Is this point at infinity? If so, set the second-most significant bit.
+        res[47] |= u8::conditional_select(&0u8, &(1u8 << 6), self.infinity);
+
+        // Is the y-coordinate the lexicographically largest of the two associated with the
+        // x-coordinate? If so, set the most significant bit so long as this is not
+        // the point at infinity.
+        res[47] |= u8::conditional_select(
+            &0u8,
+            &(1u8 << 7),
+            (!self.infinity) & self.y.lexicographically_largest(),
+        );
+
+        res
+    }
+
+    /// Attempts to deserialize a compressed element in the little-endian
+    /// format produced by `to_compressed_le()`. This is not constant time.
+    pub fn from_compressed_le(bytes: &[u8; 48]) -> Option<Self> {
+        // We already know the point is on the curve because this is established
+        // by the y-coordinate recovery procedure in from_compressed_le_unchecked().
+
+        Self::from_compressed_le_unchecked(bytes).and_then(|p| {
+            match bool::from(p.is_torsion_free()) {
+                true => Some(p),
+                _ => None,
+            }
+        })
+    }
+
+    /// Attempts to deserialize a compressed element in the little-endian
+    /// format produced by `to_compressed_le()`, not checking if the element is
+    /// in the correct subgroup. This is not constant time.
+    /// **This is dangerous to call unless you trust the bytes you are reading; otherwise,
+    /// API invariants may be broken.** Please consider using `from_compressed_le()` instead.
+    pub fn from_compressed_le_unchecked(bytes: &[u8; 48]) -> Option<Self> {
+        // Obtain the two flags from the end of the byte sequence
+        let sort_flag_set = Choice::from((bytes[47] >> 7) & 1);
+        let infinity_flag_set = Choice::from((bytes[47] >> 6) & 1);
+
+        // Attempt to obtain the x-coordinate
+        let x = {
+            let mut tmp = [0; 48];
+            tmp.copy_from_slice(&bytes[0..48]);
+
+            // Mask away the flag bits
+            tmp[47] &= 0b0011_1111;
+
+            Fp::from_bytes_little_endian_vartime(&tmp)
+        }?;
+
+        // If the infinity flag is set, the x-coordinate should be zero and
+        // the sort flag should not be set.
+        if bool::from(infinity_flag_set) {
+            return match bool::from((!sort_flag_set) & x.is_zero()) {
+                true => Some(G1Affine::identity()),
+                _ => None,
+            };
+        }
+
+        // Recover a y-coordinate given x by y = sqrt_vartime(x^3 + 1)
+        ((x.square() * x) + b()).sqrt_vartime().map(|y| {
+            // Switch to the correct y-coordinate if necessary.
+            let y = Fp::conditional_select(&y, &-y, y.lexicographically_largest() ^ sort_flag_set);
+
+            G1Affine {
+                x,
+                y,
+                infinity: Choice::from(0u8),
+            }
+        })
+    }
+
+    /// Attempts to deserialize an uncompressed element in little-endian byte
+    /// order, as used by arkworks and Celo.
+    pub fn from_uncompressed_le(bytes: &[u8; 96]) -> CtOption<Self> {
+        Self::from_uncompressed_le_unchecked(bytes)
+            .and_then(|p| CtOption::new(p, p.is_on_curve() & p.is_torsion_free()))
+    }
+
+    /// Attempts to deserialize an uncompressed element in little-endian byte
+    /// order, not checking if the element is on the curve and not checking if
+    /// it is in the correct subgroup. The sort flag is ignored.
+    /// **This is dangerous to call unless you trust the bytes you are reading; otherwise,
+    /// API invariants may be broken.** Please consider using `from_uncompressed_le()` instead.
+    pub fn from_uncompressed_le_unchecked(bytes: &[u8; 96]) -> CtOption<Self> {
+        // Obtain the infinity flag from the end of the byte sequence
+        let infinity_flag_set = Choice::from((bytes[95] >> 6) & 1);
+
+        // Attempt to obtain the x-coordinate
+        let x = {
+            let mut tmp = [0; 48];
+            tmp.copy_from_slice(&bytes[0..48]);
+
+            Fp::from_bytes_little_endian(&tmp)
+        };
+
+        // Attempt to obtain the y-coordinate
+        let y = {
+            let mut tmp = [0; 48];
+            tmp.copy_from_slice(&bytes[48..96]);
+
+            // Mask away the flag bits
+            tmp[47] &= 0b0011_1111;
+
+            Fp::from_bytes_little_endian(&tmp)
+        };
+
+        x.and_then(|x| {
+            y.and_then(|y| {
+                // Create a point representing this value
+                let p = G1Affine::conditional_select(
+                    &G1Affine {
+                        x,
+                        y,
+                        infinity: infinity_flag_set,
+                    },
+                    &G1Affine::identity(),
+                    infinity_flag_set,
+                );
+
+                CtOption::new(
+                    p,
+                    // If the infinity flag is set, the x and y coordinates should have been zero.
+                    (!infinity_flag_set) | (infinity_flag_set & x.is_zero() & y.is_zero()),
+                )
+            })
+        })
+    }
+
     /// Returns true if this element is the identity (the point at infinity).
     pub fn is_identity(&self) -> Choice {
         self.infinity
@@ -456,9 +603,9 @@ impl G1Affine {
 /// This is an element of $\mathbb{G}_1$ represented in the projective coordinate space.
 #[derive(Copy, Clone, Debug)]
 pub struct G1Projective {
-    x: Fp,
-    y: Fp,
-    z: Fp,
+    pub(crate) x: Fp,
+    pub(crate) y: Fp,
+    pub(crate) z: Fp,
 }
 
 impl<'a> From<&'a G1Affine> for G1Projective {
@@ -497,6 +644,15 @@ impl ConstantTimeEq for G1Projective {
     }
 }
 
+#[cfg(feature = "zeroize")]
+impl Zeroize for G1Projective {
+    fn zeroize(&mut self) {
+        self.x.zeroize();
+        self.y.zeroize();
+        self.z.zeroize();
+    }
+}
+
 impl ConditionallySelectable for G1Projective {
     fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
         G1Projective {
@@ -554,7 +710,10 @@ impl<'a, 'b> Mul<&'b Scalar> for &'a G1Projective {
     type Output = G1Projective;
 
     fn mul(self, other: &'b Scalar) -> Self::Output {
-        self.multiply(&other.to_bytes())
+        let mut by = other.to_bytes();
+        let acc = self.multiply(&by);
+        wipe(&mut by);
+        acc
     }
 }
 
@@ -562,7 +721,10 @@ impl<'a, 'b> Mul<&'b Scalar> for &'a G1Affine {
     type Output = G1Projective;
 
     fn mul(self, other: &'b Scalar) -> Self::Output {
-        G1Projective::from(self).multiply(&other.to_bytes())
+        let mut by = other.to_bytes();
+        let acc = G1Projective::from(self).multiply(&by);
+        wipe(&mut by);
+        acc
     }
 }
 
@@ -589,6 +751,13 @@ impl G1Projective {
         }
     }
 
+    /// Returns a uniformly random element of the $q$-order subgroup,
+    /// computed as a random multiple of the generator.
+    #[cfg(feature = "rand_core")]
+    pub fn random(rng: impl RngCore + CryptoRng) -> G1Projective {
+        G1Projective::generator() * Scalar::random(rng)
+    }
+
     /// Computes the doubling of this point.
     pub fn double(&self) -> G1Projective {
         // http://www.hyperelliptic.org/EFD/g1p/auto-shortw-jacobian-0.html#doubling-dbl-2009-l
@@ -757,7 +926,7 @@ impl G1Projective {
         G1Projective::conditional_select(&res, &tmp, (!f1) & (!f2) & (!f3))
     }
 
-    fn multiply(&self, by: &[u8; 32]) -> G1Projective {
+    fn multiply(&self, by: &[u8]) -> G1Projective {
         let mut acc = G1Projective::identity();
 
         // This is a simple double-and-add implementation of point
@@ -765,7 +934,7 @@ impl G1Projective {
         // significant bit of the scalar.
         //
         // We skip the leading bit because it's always unset for Fq
-        // elements.
+        // elements and for blinded scalars.
         for bit in by
             .iter()
             .rev()
@@ -779,6 +948,92 @@ impl G1Projective {
         acc
     }
 
+    /// Multiplies by `by` with scalar blinding, randomized projective
+    /// coordinates and, if enabled, point blinding, all drawn from
+    /// `blinding`. For points of the q-order subgroup the result is the same
+    /// as `self * by`.
+    pub fn mul_blinded(&self, by: &Scalar, blinding: &Blinding) -> G1Projective {
+        let mut e = blinding.blind_scalar(by);
+        let p = self.randomize_z(&blinding.z);
+
+        let acc = match blinding.point {
+            None => p.multiply(&e),
+            Some(t) => {
+                let r = G1Projective::generator() * t;
+                (p + r).multiply(&e) - r.multiply(&e)
+            }
+        };
+
+        wipe(&mut e);
+        acc
+    }
+
+    /// Returns the same point with its coordinates (X : Y : Z) replaced by
+    /// (λ²X : λ³Y : λZ).
+    fn randomize_z(&self, lambda: &Fp) -> G1Projective {
+        let lambda2 = lambda.square();
+        G1Projective {
+            x: self.x * lambda2,
+            y: self.y * lambda2 * lambda,
+            z: self.z * lambda,
+        }
+    }
+
+    /// Computes $\sum_i [s_i] P_i$ over the `terms` $(P_i, s_i)$. The terms
+    /// are processed in groups that share their doublings, which is several
+    /// times faster than summing the products.
+    pub fn msm(terms: &[(G1Affine, Scalar)]) -> G1Projective {
+        const CHUNK: usize = 16;
+
+        let mut acc = G1Projective::identity();
+        for terms in terms.chunks(CHUNK) {
+            let mut bytes = [[0u8; 32]; CHUNK];
+            for (b, (_, s)) in bytes.iter_mut().zip(terms.iter()) {
+                *b = s.to_bytes();
+            }
+
+            // Interleaved double-and-add over the bits of all scalars, skipping
+            // the leading bit, which is always unset for Fq elements.
+            let mut sum = G1Projective::identity();
+            for i in (0..255).rev() {
+                sum = sum.double();
+                for ((p, _), b) in terms.iter().zip(bytes.iter()) {
+                    let bit = Choice::from((b[i / 8] >> (i % 8)) & 1u8);
+                    sum = G1Projective::conditional_select(&sum, &sum.add_mixed(p), bit);
+                }
+            }
+            acc += sum;
+
+            for b in bytes.iter_mut() {
+                wipe(b);
+            }
+        }
+
+        acc
+    }
+
+    /// Multiplies by the cofactor $h$ of the curve, mapping any point on the
+    /// curve into the $q$-order subgroup $\mathbb{G}_1$.
+    pub fn clear_cofactor(&self) -> G1Projective {
+        // h = (x - 1)^2 / 3 = 0x170b5d44300000000000000000000000, whose lower
+        // 64 bits are zero. The bits of h are public, so branching on them does
+        // not leak anything about the point.
+        const H_HI: u64 = 0x170b5d4430000000;
+
+        let mut acc = G1Projective::identity();
+        for i in (0..61).rev() {
+            acc = acc.double();
+            if (H_HI >> i) & 1 == 1 {
+                acc += self;
+            }
+        }
+        for _ in 0..64 {
+            acc = acc.double();
+        }
+
+        acc
+    }
+
     /// Converts a batch of `G1Projective` elements into `G1Affine` elements. This
     /// function will panic if `p.len() != q.len()`.
     pub fn batch_normalize(p: &[Self], q: &mut [G1Affine]) {
@@ -1324,3 +1579,201 @@ fn test_batch_normalize() {
         }
     }
 }
+
+#[test]
+fn test_clear_cofactor() {
+    // (5, y) is on the curve, but not in the q-order subgroup.
+    let mut x = [0u8; 48];
+    x[47] = 5;
+    let a = G1Affine {
+        x: Fp::from_bytes(&x).unwrap(),
+        y: Fp::from_raw_unchecked([
+            0x6e4afdabee34d851,
+            0x69c13bea93737b5f,
+            0xf3294fe0c183756a,
+            0xba3a21e74673178b,
+            0x1e9f60e027a57132,
+            0x0144bebe07c365e8,
+        ]),
+        infinity: Choice::from(0u8),
+    };
+    assert!(bool::from(a.is_on_curve()));
+    assert!(!bool::from(a.is_torsion_free()));
+
+    let b = G1Affine::from(G1Projective::from(a).clear_cofactor());
+    assert!(bool::from(b.is_on_curve()));
+    assert!(bool::from(b.is_torsion_free()));
+    assert_eq!(
+        &b.to_uncompressed()[..],
+        &hex::decode("01104808ef5d9d4012d2e196fed665e918655562587f4c8a16c7cc4ca8817272281a5d23542f0e780173719b9a933f9f0000120cce1dad933b62f9bb83cd6aa7c1cf417c684836295d8757bfd1728f165792b415b51a58a127646003b1c3175b").unwrap()[..]
+    );
+
+    assert!(bool::from(
+        G1Projective::identity().clear_cofactor().is_identity()
+    ));
+}
+
+#[test]
+fn test_compressed_le() {
+    // The generator's y-coordinate is the lexicographically largest, so the
+    // sort flag is set in the last byte.
+    assert_eq!(
+        &G1Affine::generator().to_compressed_le()[..],
+        &hex::decode("efe91bb26eb1b9ea4e39cdff121548d55ccb37bdc8828218bb419daa2c1e958554ff87bf2562fcc8670a74fede488880").unwrap()[..]
+    );
+
+    let mut p = G1Projective::generator();
+    for _ in 0..10 {
+        for q in [G1Affine::from(p), G1Affine::from(-p)].iter() {
+            let bytes = q.to_compressed_le();
+            assert_eq!(G1Affine::from_compressed_le(&bytes).unwrap(), *q);
+            assert_eq!(G1Affine::from_compressed_le_unchecked(&bytes).unwrap(), *q);
+        }
+        p = p.double() + G1Projective::generator();
+    }
+
+    let bytes = G1Affine::identity().to_compressed_le();
+    assert_eq!(bytes[47], 1u8 << 6);
+    assert!(bool::from(
+        G1Affine::from_compressed_le(&bytes).unwrap().is_identity()
+    ));
+
+    // The infinity flag requires a zero x-coordinate and a clear sort flag.
+    let mut bad = bytes;
+    bad[0] = 1;
+    assert!(G1Affine::from_compressed_le(&bad).is_none());
+    let mut bad = bytes;
+    bad[47] |= 1u8 << 7;
+    assert!(G1Affine::from_compressed_le(&bad).is_none());
+
+    // Non-canonical x-coordinates are rejected.
+    let mut bad = [0xffu8; 48];
+    bad[47] = 0x3f;
+    assert!(G1Affine::from_compressed_le_unchecked(&bad).is_none());
+}
+
+#[test]
+fn test_uncompressed_le() {
+    let mut p = G1Projective::generator();
+    for _ in 0..10 {
+        let q = G1Affine::from(p);
+        let mut bytes = q.to_uncompressed_littleendian();
+        assert_eq!(G1Affine::from_uncompressed_le(&bytes).unwrap(), q);
+
+        // arkworks also sets the sort flag on uncompressed points; it is ignored.
+        bytes[95] |= 1u8 << 7;
+        assert_eq!(G1Affine::from_uncompressed_le(&bytes).unwrap(), q);
+        p = p.double() + G1Projective::generator();
+    }
+
+    let bytes = G1Affine::identity().to_uncompressed_littleendian();
+    assert!(bool::from(
+        G1Affine::from_uncompressed_le(&bytes).unwrap().is_identity()
+    ));
+    let mut bad = bytes;
+    bad[0] = 1;
+    assert!(bool::from(G1Affine::from_uncompressed_le(&bad).is_none()));
+
+    // Points off the curve are rejected.
+    let mut bad = G1Affine::generator().to_uncompressed_littleendian();
+    bad[48] ^= 1;
+    assert!(bool::from(G1Affine::from_uncompressed_le(&bad).is_none()));
+    assert!(bool::from(
+        G1Affine::from_uncompressed_le_unchecked(&bad).is_some()
+    ));
+}
+
+#[test]
+fn test_le_encodings_subgroup_check() {
+    // (5, y) is on the curve, but not in the q-order subgroup.
+    let mut x = [0u8; 48];
+    x[0] = 5;
+    let a = G1Affine::from_compressed_le_unchecked(&x).unwrap();
+    assert!(bool::from(a.is_on_curve()));
+    assert!(!bool::from(a.is_torsion_free()));
+
+    let bytes = a.to_compressed_le();
+    assert!(G1Affine::from_compressed_le(&bytes).is_none());
+    assert_eq!(G1Affine::from_compressed_le_unchecked(&bytes).unwrap(), a);
+
+    let bytes = a.to_uncompressed_littleendian();
+    assert!(bool::from(G1Affine::from_uncompressed_le(&bytes).is_none()));
+    assert_eq!(G1Affine::from_uncompressed_le_unchecked(&bytes).unwrap(), a);
+}
+
+#[cfg(feature = "rand_core")]
+#[test]
+fn test_random() {
+    use rand_chacha::rand_core::SeedableRng;
+
+    let mut rng = rand_chacha::ChaChaRng::from_seed([0u8; 32]);
+    let a = G1Projective::random(&mut rng);
+    let b = G1Projective::random(&mut rng);
+    assert!(a != b);
+    for p in [a, b].iter() {
+        let p = G1Affine::from(p);
+        assert!(!bool::from(p.is_identity()));
+        assert!(bool::from(p.is_on_curve()));
+        assert!(bool::from(p.is_torsion_free()));
+    }
+}
+
+#[cfg(feature = "zeroize")]
+#[test]
+fn test_zeroize() {
+    use zeroize::Zeroize;
+
+    let mut a = G1Projective::generator();
+    a.zeroize();
+    assert!(bool::from(a.is_identity()));
+}
+
+#[test]
+fn test_mul_blinded() {
+    let p = G1Projective::generator() * Scalar::from(7);
+    let s = Scalar::from_raw([
+        0x1234_5678_9abc_def0,
+        0x0fed_cba9_8765_4321,
+        0x1111_2222_3333_4444,
+        0x0555_6666_7777_8888,
+    ]);
+
+    let mut bytes = [0u8; 80];
+    for (i, b) in bytes.iter_mut().enumerate() {
+        *b = (i as u8).wrapping_mul(37).wrapping_add(11);
+    }
+    let blinding = Blinding::from_bytes(&bytes);
+    assert_eq!(p.mul_blinded(&s, &blinding), p * s);
+    assert_eq!(p.mul_blinded(&-Scalar::one(), &blinding), -p);
+    assert_eq!(p.mul_blinded(&Scalar::zero(), &blinding), G1Projective::identity());
+
+    let blinding = blinding.with_point_blinding(&[0x5a; 64]);
+    assert_eq!(p.mul_blinded(&s, &blinding), p * s);
+    assert_eq!(G1Projective::identity().mul_blinded(&s, &blinding), G1Projective::identity());
+
+    // With an all-zero mask and λ = 1, only the point blinding is active.
+    let blinding = Blinding::from_bytes(&[0; 80]).with_point_blinding(&[1; 64]);
+    assert_eq!(p.mul_blinded(&s, &blinding), p * s);
+}
+
+#[test]
+fn test_msm() {
+    let g = G1Projective::generator();
+    let mut terms = [(G1Affine::identity(), Scalar::zero()); 20];
+    let mut expected = G1Projective::identity();
+    for (i, (p, s)) in terms.iter_mut().enumerate() {
+        let i = i as u64;
+        *p = G1Affine::from(g * Scalar::from(i * 7 + 3));
+        *s = Scalar::from_raw([i.wrapping_mul(0x9e37_79b9_7f4a_7c15), i, !i, i << 32]);
+        expected += *p * *s;
+    }
+    // An identity point and a repeated point.
+    terms[4].0 = G1Affine::identity();
+    expected -= G1Affine::from(g * Scalar::from(31)) * terms[4].1;
+    terms[17].0 = terms[16].0;
+    expected += (terms[16].0 * terms[17].1) - (G1Affine::from(g * Scalar::from(122)) * terms[17].1);
+
+    assert_eq!(G1Projective::msm(&terms), expected);
+    assert_eq!(G1Projective::msm(&terms[..3]), terms[0].0 * terms[0].1 + terms[1].0 * terms[1].1 + terms[2].0 * terms[2].1);
+    assert_eq!(G1Projective::msm(&[]), G1Projective::identity());
+}
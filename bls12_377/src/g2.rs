@@ -2,10 +2,16 @@
 
 use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 
+#[cfg(feature = "rand_core")]
+use rand_core::{CryptoRng, RngCore};
 use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
 
 use crate::fp::Fp;
 use crate::fp2::Fp2;
+use crate::blinding::Blinding;
+use crate::util::wipe;
 use crate::Scalar;
 
 /// This is an element of $\mathbb{G}_2$ represented in the affine coordinate space.
@@ -140,7 +146,7 @@ impl<'a, 'b> Sub<&'b G2Affine> for &'a G2Projective {
 impl_binops_additive!(G2Projective, G2Affine);
 impl_binops_additive_specify_output!(G2Affine, G2Projective, G2Projective);
 
-const fn b() -> Fp2 {
+pub(crate) const fn b() -> Fp2 {
     Fp2 {
         c0: Fp::from_raw_unchecked([
             0x0,
@@ -275,8 +281,7 @@ impl G2Affine {
     }
 
     /// Serializes this element into uncompressed form in
-    /// little-endian representation. Assumes the received point
-    /// is not infinity.
+    /// little-endian representation.
     #[inline(always)]     
     pub fn to_uncompressed_littleendian(&self) -> [u8; 192] {
         let mut res = [0; 192];
@@ -289,6 +294,10 @@ impl G2Affine {
         res[96..144].copy_from_slice(&y.c0.to_bytes_littleendian()[..]);
         res[144..192].copy_from_slice(&y.c1.to_bytes_littleendian()[..]);
 
+        // Is this point at infinity? If so, set the second-most significant bit
+        // of the last byte.
+        res[191] |= u8::conditional_select(&0u8, &(1u8 << 6), self.infinity);
+
         res
     }
 
@@ -468,6 +477,176 @@ impl G2Affine {
         }
     }
 
+    /// Serializes this element into compressed form in little-endian byte
+    /// order, as used by arkworks and Celo: `x.c0` followed by `x.c1`. The flags
+    /// live in the two most significant bits of the last byte: bit 7 is set if
+    /// the y-coordinate is the lexicographically largest, and bit 6 is set for
+    /// the point at infinity.
+    pub fn to_compressed_le(&self) -> [u8; 96] {
+        // Strictly speaking, self.x is zero already when self.infinity is true, but
+        // to guard against implementation mistakes we do not assume this.
+        let x = Fp2::conditional_select(&self.x, &Fp2::zero(), self.infinity);
+
+        let mut res = [0; 96];
+
+        res[0..48].copy_from_slice(&x.c0.to_bytes_littleendian()[..]);
+        res[48..96].copy_from_slice(&x.c1.to_bytes_littleendian()[..]);
+
+        // Is this point at infinity? If so, set the second-most significant bit.
+        res[95] |= u8::conditional_select(&0u8, &(1u8 << 6), self.infinity);
+
+        // Is the y-coordinate the lexicographically largest of the two associated with the
+        // x-coordinate? If so, set the most significant bit so long as this is not
+        // the point at infinity.
+        res[95] |= u8::conditional_select(
+            &0u8,
+            &(1u8 << 7),
+            (!self.infinity) & self.y.lexicographically_largest(),
+        );
+
+        res
+    }
+
+    /// Attempts to deserialize a compressed element in the little-endian
+    /// format produced by `to_compressed_le()`. This is not constant time.
+    pub fn from_compressed_le(bytes: &[u8; 96]) -> Option<Self> {
+        // We already know the point is on the curve because this is established
+        // by the y-coordinate recovery procedure in from_compressed_le_unchecked().
+
+        Self::from_compressed_le_unchecked(bytes).and_then(|p| {
+            match bool::from(p.is_torsion_free()) {
+                true => Some(p),
+                _ => None,
+            }
+        })
+    }
+
+    /// Attempts to deserialize a compressed element in the little-endian
+    /// format produced by `to_compressed_le()`, not checking if the element is
+    /// in the correct subgroup. This is not constant time.
+    /// **This is dangerous to call unless you trust the bytes you are reading; otherwise,
+    /// API invariants may be broken.** Please consider using `from_compressed_le()` instead.
+    pub fn from_compressed_le_unchecked(bytes: &[u8; 96]) -> Option<Self> {
+        // Obtain the two flags from the end of the byte sequence
+        let sort_flag_set = Choice::from((bytes[95] >> 7) & 1);
+        let infinity_flag_set = Choice::from((bytes[95] >> 6) & 1);
+
+        // Attempt to obtain the x-coordinate
+        let xc0 = {
+            let mut tmp = [0; 48];
+            tmp.copy_from_slice(&bytes[0..48]);
+
+            Fp::from_bytes_little_endian_vartime(&tmp)
+        }?;
+        let xc1 = {
+            let mut tmp = [0; 48];
+            tmp.copy_from_slice(&bytes[48..96]);
+
+            // Mask away the flag bits
+            tmp[47] &= 0b0011_1111;
+
+            Fp::from_bytes_little_endian_vartime(&tmp)
+        }?;
+        let x = Fp2 { c0: xc0, c1: xc1 };
+
+        // If the infinity flag is set, the x-coordinate should be zero and
+        // the sort flag should not be set.
+        if bool::from(infinity_flag_set) {
+            return match bool::from((!sort_flag_set) & x.is_zero()) {
+                true => Some(G2Affine::identity()),
+                _ => None,
+            };
+        }
+
+        // Recover a y-coordinate given x by y = sqrt_vartime(x^3 + B)
+        ((x.square() * x) + b()).sqrt_vartime().map(|y| {
+            // Switch to the correct y-coordinate if necessary.
+            let y = Fp2::conditional_select(&y, &-y, y.lexicographically_largest() ^ sort_flag_set);
+
+            G2Affine {
+                x,
+                y,
+                infinity: Choice::from(0u8),
+            }
+        })
+    }
+
+    /// Attempts to deserialize an uncompressed element in little-endian byte
+    /// order, as used by arkworks and Celo.
+    pub fn from_uncompressed_le(bytes: &[u8; 192]) -> CtOption<Self> {
+        Self::from_uncompressed_le_unchecked(bytes)
+            .and_then(|p| CtOption::new(p, p.is_on_curve() & p.is_torsion_free()))
+    }
+
+    /// Attempts to deserialize an uncompressed element in little-endian byte
+    /// order, not checking if the element is on the curve and not checking if
+    /// it is in the correct subgroup. The sort flag is ignored.
+    /// **This is dangerous to call unless you trust the bytes you are reading; otherwise,
+    /// API invariants may be broken.** Please consider using `from_uncompressed_le()` instead.
+    pub fn from_uncompressed_le_unchecked(bytes: &[u8; 192]) -> CtOption<Self> {
+        // Obtain the infinity flag from the end of the byte sequence
+        let infinity_flag_set = Choice::from((bytes[191] >> 6) & 1);
+
+        // Attempt to obtain the x-coordinate
+        let xc0 = {
+            let mut tmp = [0; 48];
+            tmp.copy_from_slice(&bytes[0..48]);
+
+            Fp::from_bytes_little_endian(&tmp)
+        };
+        let xc1 = {
+            let mut tmp = [0; 48];
+            tmp.copy_from_slice(&bytes[48..96]);
+
+            Fp::from_bytes_little_endian(&tmp)
+        };
+
+        // Attempt to obtain the y-coordinate
+        let yc0 = {
+            let mut tmp = [0; 48];
+            tmp.copy_from_slice(&bytes[96..144]);
+
+            Fp::from_bytes_little_endian(&tmp)
+        };
+        let yc1 = {
+            let mut tmp = [0; 48];
+            tmp.copy_from_slice(&bytes[144..192]);
+
+            // Mask away the flag bits
+            tmp[47] &= 0b0011_1111;
+
+            Fp::from_bytes_little_endian(&tmp)
+        };
+
+        xc0.and_then(|xc0| {
+            xc1.and_then(|xc1| {
+                yc0.and_then(|yc0| {
+                    yc1.and_then(|yc1| {
+                        let x = Fp2 { c0: xc0, c1: xc1 };
+                        let y = Fp2 { c0: yc0, c1: yc1 };
+
+                        // Create a point representing this value
+                        let p = G2Affine::conditional_select(
+                            &G2Affine {
+                                x,
+                                y,
+                                infinity: infinity_flag_set,
+                            },
+                            &G2Affine::identity(),
+                            infinity_flag_set,
+                        );
+
+                        CtOption::new(
+                            p,
+                            // If the infinity flag is set, the x and y coordinates should have been zero.
+                            (!infinity_flag_set) | (infinity_flag_set & x.is_zero() & y.is_zero()),
+                        )
+                    })
+                })
+            })
+        })
+    }
+
     /// Returns true if this element is the identity (the point at infinity).
     pub fn is_identity(&self) -> Choice {
         self.infinity
@@ -545,6 +724,15 @@ impl ConstantTimeEq for G2Projective {
     }
 }
 
+#[cfg(feature = "zeroize")]
+impl Zeroize for G2Projective {
+    fn zeroize(&mut self) {
+        self.x.zeroize();
+        self.y.zeroize();
+        self.z.zeroize();
+    }
+}
+
 impl ConditionallySelectable for G2Projective {
     #[inline] 
     fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
@@ -605,7 +793,10 @@ impl<'a, 'b> Mul<&'b Scalar> for &'a G2Projective {
 
     #[inline(always)]
     fn mul(self, other: &'b Scalar) -> Self::Output {
-        self.multiply(&other.to_bytes())
+        let mut by = other.to_bytes();
+        let acc = self.multiply(&by);
+        wipe(&mut by);
+        acc
     }
 }
 
@@ -613,7 +804,10 @@ impl<'a, 'b> Mul<&'b Scalar> for &'a G2Affine {
     type Output = G2Projective;
 
     fn mul(self, other: &'b Scalar) -> Self::Output {
-        G2Projective::from(self).multiply(&other.to_bytes())
+        let mut by = other.to_bytes();
+        let acc = G2Projective::from(self).multiply(&by);
+        wipe(&mut by);
+        acc
     }
 }
 
@@ -642,6 +836,13 @@ impl G2Projective {
         }
     }
 
+    /// Returns a uniformly random element of the $q$-order subgroup,
+    /// computed as a random multiple of the generator.
+    #[cfg(feature = "rand_core")]
+    pub fn random(rng: impl RngCore + CryptoRng) -> G2Projective {
+        G2Projective::generator() * Scalar::random(rng)
+    }
+
     /// Computes the doubling of this point.
     #[inline]
     pub fn double(&self) -> G2Projective {
@@ -815,7 +1016,7 @@ impl G2Projective {
     }
 
     #[inline(always)]
-    fn multiply(&self, by: &[u8; 32]) -> G2Projective {
+    fn multiply(&self, by: &[u8]) -> G2Projective {
         let mut acc = G2Projective::identity();
 
         // This is a simple double-and-add implementation of point
@@ -823,7 +1024,7 @@ impl G2Projective {
         // significant bit of the scalar.
         //
         // We skip the leading bit because it's always unset for Fq
-        // elements.
+        // elements and for blinded scalars.
         for bit in by
             .iter()
             .rev()
@@ -836,6 +1037,99 @@ impl G2Projective {
         acc
     }
 
+    /// Multiplies by `by` with scalar blinding, randomized projective
+    /// coordinates and, if enabled, point blinding, all drawn from
+    /// `blinding`. For points of the q-order subgroup the result is the same
+    /// as `self * by`.
+    pub fn mul_blinded(&self, by: &Scalar, blinding: &Blinding) -> G2Projective {
+        let mut e = blinding.blind_scalar(by);
+        let p = self.randomize_z(&Fp2::from(blinding.z));
+
+        let acc = match blinding.point {
+            None => p.multiply(&e),
+            Some(t) => {
+                let r = G2Projective::generator() * t;
+                (p + r).multiply(&e) - r.multiply(&e)
+            }
+        };
+
+        wipe(&mut e);
+        acc
+    }
+
+    /// Returns the same point with its coordinates (X : Y : Z) replaced by
+    /// (λ²X : λ³Y : λZ).
+    fn randomize_z(&self, lambda: &Fp2) -> G2Projective {
+        let lambda2 = lambda.square();
+        G2Projective {
+            x: self.x * lambda2,
+            y: self.y * lambda2 * lambda,
+            z: self.z * lambda,
+        }
+    }
+
+    /// Computes $\sum_i [s_i] P_i$ over the `terms` $(P_i, s_i)$. The terms
+    /// are processed in groups that share their doublings, which is several
+    /// times faster than summing the products.
+    pub fn msm(terms: &[(G2Affine, Scalar)]) -> G2Projective {
+        const CHUNK: usize = 16;
+
+        let mut acc = G2Projective::identity();
+        for terms in terms.chunks(CHUNK) {
+            let mut bytes = [[0u8; 32]; CHUNK];
+            for (b, (_, s)) in bytes.iter_mut().zip(terms.iter()) {
+                *b = s.to_bytes();
+            }
+
+            // Interleaved double-and-add over the bits of all scalars, skipping
+            // the leading bit, which is always unset for Fq elements.
+            let mut sum = G2Projective::identity();
+            for i in (0..255).rev() {
+                sum = sum.double();
+                for ((p, _), b) in terms.iter().zip(bytes.iter()) {
+                    let bit = Choice::from((b[i / 8] >> (i % 8)) & 1u8);
+                    sum = G2Projective::conditional_select(&sum, &sum.add_mixed(p), bit);
+                }
+            }
+            acc += sum;
+
+            for b in bytes.iter_mut() {
+                wipe(b);
+            }
+        }
+
+        acc
+    }
+
+    /// Multiplies by the cofactor $h$ of the curve, mapping any point on the
+    /// curve into the $q$-order subgroup $\mathbb{G}_2$.
+    pub fn clear_cofactor(&self) -> G2Projective {
+        // h, as a little-endian array of 64-bit limbs. These bits are public,
+        // so branching on them does not leak anything about the point.
+        const H: [u64; 8] = [
+            0x0000000000000001,
+            0x452217cc90000000,
+            0xa0f3622fba094800,
+            0xd693e8c36676bd09,
+            0x8c505634fae2e189,
+            0xfbb36b00e1dcc40c,
+            0xddd88d99a6f6a829,
+            0x0026ba558ae9562a,
+        ];
+
+        let mut acc = G2Projective::identity();
+        for limb in H.iter().rev() {
+            for i in (0..64).rev() {
+                acc = acc.double();
+                if (limb >> i) & 1 == 1 {
+                    acc += self;
+                }
+            }
+        }
+
+        acc
+    }
+
     /// Converts a batch of `G2Projective` elements into `G2Affine` elements. This
     /// function will panic if `p.len() != q.len()`.
     pub fn batch_normalize(p: &[Self], q: &mut [G2Affine]) {
@@ -1243,3 +1537,211 @@ fn test_batch_normalize() {
         }
     }
 }
+
+#[test]
+fn test_clear_cofactor() {
+    // (2, y) is on the curve, but not in the q-order subgroup.
+    let a = G2Affine {
+        x: Fp2 {
+            c0: Fp::one() + Fp::one(),
+            c1: Fp::zero(),
+        },
+        y: Fp2 {
+            c0: Fp::from_raw_unchecked([
+                0xb1f41b128b1c1a79,
+                0x9c3ec28ea931b6ae,
+                0xb2f6538f8d45362d,
+                0x75cbbcaf7dc89327,
+                0x74465bebc46a0e73,
+                0x004a42ce54c2f7fd,
+            ]),
+            c1: Fp::from_raw_unchecked([
+                0x1b6580495ace3678,
+                0xea9e57c3ad907c6a,
+                0x15a491f0abb81376,
+                0x68b66f7ebe0fb778,
+                0xaa67f161242a7287,
+                0x00c89401bfb5336b,
+            ]),
+        },
+        infinity: Choice::from(0u8),
+    };
+    assert!(bool::from(a.is_on_curve()));
+    assert!(!bool::from(a.is_torsion_free()));
+
+    let b = G2Affine::from(G2Projective::from(a).clear_cofactor());
+    assert!(bool::from(b.is_on_curve()));
+    assert!(bool::from(b.is_torsion_free()));
+    assert_eq!(
+        &b.to_uncompressed()[..],
+        &hex::decode("00a272c24ca7d3429d9f07b86366d6ea24148cd0d42beba1a208c4859cf3630f4de60b0c0f56b5e10ddf884b60d27f8800415abb1137c88935bb08884aee7b99049f123bf27dce03401724764cd34f4d20a7c4c78130d2d07faab58c9b87bda90121cb2668f07eaa4752e212f85ed167509504b67ef09df578536c115ac38999e66e6d2df31cbe0ceb2f80ac9c3a80510199275defaacc0db97bc0e7237bbb97f523908ed09da02f4ac4ef1a54a524d933cc6492b66b5ee576521ebbbb23ee23").unwrap()[..]
+    );
+
+    assert!(bool::from(
+        G2Projective::identity().clear_cofactor().is_identity()
+    ));
+}
+
+#[test]
+fn test_compressed_le() {
+    let mut p = G2Projective::generator();
+    for _ in 0..10 {
+        for q in [G2Affine::from(p), G2Affine::from(-p)].iter() {
+            let bytes = q.to_compressed_le();
+            assert_eq!(
+                (bytes[95] >> 7) == 1,
+                bool::from(q.y.lexicographically_largest())
+            );
+            assert_eq!(G2Affine::from_compressed_le(&bytes).unwrap(), *q);
+            assert_eq!(G2Affine::from_compressed_le_unchecked(&bytes).unwrap(), *q);
+        }
+        p = p.double() + G2Projective::generator();
+    }
+
+    let bytes = G2Affine::identity().to_compressed_le();
+    assert_eq!(bytes[95], 1u8 << 6);
+    assert!(bool::from(
+        G2Affine::from_compressed_le(&bytes).unwrap().is_identity()
+    ));
+
+    // The infinity flag requires a zero x-coordinate and a clear sort flag.
+    let mut bad = bytes;
+    bad[0] = 1;
+    assert!(G2Affine::from_compressed_le(&bad).is_none());
+    let mut bad = bytes;
+    bad[95] |= 1u8 << 7;
+    assert!(G2Affine::from_compressed_le(&bad).is_none());
+
+    // Non-canonical x-coordinates are rejected.
+    let mut bad = G2Affine::generator().to_compressed_le();
+    for b in bad[0..48].iter_mut() {
+        *b = 0xff;
+    }
+    assert!(G2Affine::from_compressed_le_unchecked(&bad).is_none());
+}
+
+#[test]
+fn test_uncompressed_le() {
+    let mut p = G2Projective::generator();
+    for _ in 0..10 {
+        let q = G2Affine::from(p);
+        let mut bytes = q.to_uncompressed_littleendian();
+        assert_eq!(G2Affine::from_uncompressed_le(&bytes).unwrap(), q);
+
+        // arkworks also sets the sort flag on uncompressed points; it is ignored.
+        bytes[191] |= 1u8 << 7;
+        assert_eq!(G2Affine::from_uncompressed_le(&bytes).unwrap(), q);
+        p = p.double() + G2Projective::generator();
+    }
+
+    let bytes = G2Affine::identity().to_uncompressed_littleendian();
+    assert!(bool::from(
+        G2Affine::from_uncompressed_le(&bytes).unwrap().is_identity()
+    ));
+    let mut bad = bytes;
+    bad[0] = 1;
+    assert!(bool::from(G2Affine::from_uncompressed_le(&bad).is_none()));
+
+    // Points off the curve are rejected.
+    let mut bad = G2Affine::generator().to_uncompressed_littleendian();
+    bad[96] ^= 1;
+    assert!(bool::from(G2Affine::from_uncompressed_le(&bad).is_none()));
+    assert!(bool::from(
+        G2Affine::from_uncompressed_le_unchecked(&bad).is_some()
+    ));
+}
+
+#[test]
+fn test_le_encodings_subgroup_check() {
+    // (2, y) is on the curve, but not in the q-order subgroup.
+    let mut x = [0u8; 96];
+    x[0] = 2;
+    let a = G2Affine::from_compressed_le_unchecked(&x).unwrap();
+    assert!(bool::from(a.is_on_curve()));
+    assert!(!bool::from(a.is_torsion_free()));
+
+    let bytes = a.to_compressed_le();
+    assert!(G2Affine::from_compressed_le(&bytes).is_none());
+    assert_eq!(G2Affine::from_compressed_le_unchecked(&bytes).unwrap(), a);
+
+    let bytes = a.to_uncompressed_littleendian();
+    assert!(bool::from(G2Affine::from_uncompressed_le(&bytes).is_none()));
+    assert_eq!(G2Affine::from_uncompressed_le_unchecked(&bytes).unwrap(), a);
+}
+
+#[cfg(feature = "rand_core")]
+#[test]
+fn test_random() {
+    use rand_chacha::rand_core::SeedableRng;
+
+    let mut rng = rand_chacha::ChaChaRng::from_seed([0u8; 32]);
+    let a = G2Projective::random(&mut rng);
+    let b = G2Projective::random(&mut rng);
+    assert!(a != b);
+    for p in [a, b].iter() {
+        let p = G2Affine::from(p);
+        assert!(!bool::from(p.is_identity()));
+        assert!(bool::from(p.is_on_curve()));
+        assert!(bool::from(p.is_torsion_free()));
+    }
+}
+
+#[cfg(feature = "zeroize")]
+#[test]
+fn test_zeroize() {
+    use zeroize::Zeroize;
+
+    let mut a = G2Projective::generator();
+    a.zeroize();
+    assert!(bool::from(a.is_identity()));
+}
+
+#[test]
+fn test_mul_blinded() {
+    let p = G2Projective::generator() * Scalar::from(7);
+    let s = Scalar::from_raw([
+        0x1234_5678_9abc_def0,
+        0x0fed_cba9_8765_4321,
+        0x1111_2222_3333_4444,
+        0x0555_6666_7777_8888,
+    ]);
+
+    let mut bytes = [0u8; 80];
+    for (i, b) in bytes.iter_mut().enumerate() {
+        *b = (i as u8).wrapping_mul(37).wrapping_add(11);
+    }
+    let blinding = Blinding::from_bytes(&bytes);
+    assert_eq!(p.mul_blinded(&s, &blinding), p * s);
+    assert_eq!(p.mul_blinded(&-Scalar::one(), &blinding), -p);
+    assert_eq!(p.mul_blinded(&Scalar::zero(), &blinding), G2Projective::identity());
+
+    let blinding = blinding.with_point_blinding(&[0x5a; 64]);
+    assert_eq!(p.mul_blinded(&s, &blinding), p * s);
+    assert_eq!(G2Projective::identity().mul_blinded(&s, &blinding), G2Projective::identity());
+
+    // With an all-zero mask and λ = 1, only the point blinding is active.
+    let blinding = Blinding::from_bytes(&[0; 80]).with_point_blinding(&[1; 64]);
+    assert_eq!(p.mul_blinded(&s, &blinding), p * s);
+}
+
+#[test]
+fn test_msm() {
+    let g = G2Projective::generator();
+    let mut terms = [(G2Affine::identity(), Scalar::zero()); 20];
+    let mut expected = G2Projective::identity();
+    for (i, (p, s)) in terms.iter_mut().enumerate() {
+        let i = i as u64;
+        *p = G2Affine::from(g * Scalar::from(i * 7 + 3));
+        *s = Scalar::from_raw([i.wrapping_mul(0x9e37_79b9_7f4a_7c15), i, !i, i << 32]);
+        expected += *p * *s;
+    }
+    // An identity point and a repeated point.
+    terms[4].0 = G2Affine::identity();
+    expected -= G2Affine::from(g * Scalar::from(31)) * terms[4].1;
+    terms[17].0 = terms[16].0;
+    expected += (terms[16].0 * terms[17].1) - (G2Affine::from(g * Scalar::from(122)) * terms[17].1);
+
+    assert_eq!(G2Projective::msm(&terms), expected);
+    assert_eq!(G2Projective::msm(&terms[..3]), terms[0].0 * terms[0].1 + terms[1].0 * terms[1].1 + terms[2].0 * terms[2].1);
+    assert_eq!(G2Projective::msm(&[]), G2Projective::identity());
+}
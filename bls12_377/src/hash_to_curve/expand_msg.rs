@@ -0,0 +1,373 @@
+//! This module implements message expansion consistent with the
+//! hash-to-curve specification, RFC 9380, section 5.3.
+
+use core::fmt;
+use core::marker::PhantomData;
+
+use digest::generic_array::{typenum::Unsigned, GenericArray};
+use digest::{BlockInput, Digest, ExtendableOutput, Input, XofReader};
+
+/// The domain separation tag prefix used when the supplied tag is too long.
+const OVERSIZE_DST_SALT: &[u8] = b"H2C-OVERSIZE-DST-";
+
+/// The maximum length of a domain separation tag once it has been reduced.
+const MAX_DST_LENGTH: usize = 255;
+
+/// A domain separation tag of at most 255 bytes, stored inline so that
+/// expanding a message does not require an allocation.
+#[derive(Clone, Copy)]
+struct Dst {
+    bytes: [u8; MAX_DST_LENGTH],
+    len: usize,
+}
+
+impl Dst {
+    /// Stores `dst` as is, or its digest if `dst` exceeds 255 bytes.
+    fn new<F: FnOnce(&mut [u8]) -> usize>(dst: &[u8], reduce: F) -> Dst {
+        let mut bytes = [0u8; MAX_DST_LENGTH];
+        let len = if dst.len() > MAX_DST_LENGTH {
+            reduce(&mut bytes[..])
+        } else {
+            bytes[..dst.len()].copy_from_slice(dst);
+            dst.len()
+        };
+        Dst { bytes, len }
+    }
+
+    fn data(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+
+    /// Feeds DST_prime = DST || I2OSP(len(DST), 1) into a hash function.
+    fn input_prime<F: FnMut(&[u8])>(&self, mut input: F) {
+        input(self.data());
+        input(&[self.len as u8]);
+    }
+}
+
+impl fmt::Debug for Dst {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Dst(")?;
+        for b in self.data() {
+            write!(f, "{:02x}", b)?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// A trait for message expansion methods supported by hash-to-curve.
+pub trait ExpandMessage {
+    /// The state used to produce the expanded message.
+    type Expander: ExpandMessageState;
+
+    /// Initializes a message expander for the given message and domain
+    /// separation tag, producing `len_in_bytes` bytes of output.
+    fn init_expand(message: &[u8], dst: &[u8], len_in_bytes: usize) -> Self::Expander;
+}
+
+/// The state of an in-progress message expansion.
+pub trait ExpandMessageState {
+    /// Reads output bytes into `output`, returning the number of bytes read.
+    fn read_into(&mut self, output: &mut [u8]) -> usize;
+
+    /// Returns the number of output bytes that have not yet been read.
+    fn remain(&self) -> usize;
+}
+
+/// A generator for `expand_message_xof` (RFC 9380, section 5.3.2), based
+/// on an extendable-output hash function such as SHAKE128.
+///
+/// Domain separation tags longer than 255 bytes are reduced to 32 bytes,
+/// corresponding to a target security level of k = 128 bits.
+pub struct ExpandMsgXof<H> {
+    phantom: PhantomData<H>,
+}
+
+impl<H> fmt::Debug for ExpandMsgXof<H> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ExpandMsgXof")
+    }
+}
+
+/// The state of an `expand_message_xof` expansion.
+pub struct ExpandMsgXofState<H: ExtendableOutput> {
+    reader: H::Reader,
+    remain: usize,
+}
+
+impl<H: ExtendableOutput> fmt::Debug for ExpandMsgXofState<H> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ExpandMsgXofState")
+            .field("remain", &self.remain)
+            .finish()
+    }
+}
+
+impl<H> ExpandMessage for ExpandMsgXof<H>
+where
+    H: Default + Input + ExtendableOutput,
+{
+    type Expander = ExpandMsgXofState<H>;
+
+    fn init_expand(message: &[u8], dst: &[u8], len_in_bytes: usize) -> Self::Expander {
+        assert!(
+            len_in_bytes <= 0xffff,
+            "expand_message_xof output length must fit in two bytes"
+        );
+
+        let dst = Dst::new(dst, |out| {
+            // DST = H("H2C-OVERSIZE-DST-" || a_very_long_DST, ceil(2 * k / 8))
+            let len = 32;
+            let mut h = H::default();
+            h.input(OVERSIZE_DST_SALT);
+            h.input(dst);
+            h.xof_result().read(&mut out[..len]);
+            len
+        });
+
+        // msg_prime = msg || I2OSP(len_in_bytes, 2) || DST_prime
+        let mut h = H::default();
+        h.input(message);
+        h.input([(len_in_bytes >> 8) as u8, len_in_bytes as u8]);
+        dst.input_prime(|data| h.input(data));
+
+        ExpandMsgXofState {
+            reader: h.xof_result(),
+            remain: len_in_bytes,
+        }
+    }
+}
+
+impl<H: ExtendableOutput> ExpandMessageState for ExpandMsgXofState<H> {
+    fn read_into(&mut self, output: &mut [u8]) -> usize {
+        let len = core::cmp::min(self.remain, output.len());
+        self.reader.read(&mut output[..len]);
+        self.remain -= len;
+        len
+    }
+
+    fn remain(&self) -> usize {
+        self.remain
+    }
+}
+
+/// A generator for `expand_message_xmd` (RFC 9380, section 5.3.1), based
+/// on a Merkle-Damgård hash function such as SHA-256.
+pub struct ExpandMsgXmd<H> {
+    phantom: PhantomData<H>,
+}
+
+impl<H> fmt::Debug for ExpandMsgXmd<H> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ExpandMsgXmd")
+    }
+}
+
+/// The state of an `expand_message_xmd` expansion.
+pub struct ExpandMsgXmdState<H: Digest> {
+    dst: Dst,
+    b_0: GenericArray<u8, H::OutputSize>,
+    b_i: GenericArray<u8, H::OutputSize>,
+    i: usize,
+    b_offs: usize,
+    remain: usize,
+}
+
+impl<H: Digest> fmt::Debug for ExpandMsgXmdState<H> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ExpandMsgXmdState")
+            .field("dst", &self.dst)
+            .field("i", &self.i)
+            .field("remain", &self.remain)
+            .finish()
+    }
+}
+
+impl<H> ExpandMessage for ExpandMsgXmd<H>
+where
+    H: Digest + BlockInput,
+{
+    type Expander = ExpandMsgXmdState<H>;
+
+    fn init_expand(message: &[u8], dst: &[u8], len_in_bytes: usize) -> Self::Expander {
+        let b_in_bytes = H::OutputSize::to_usize();
+        #[allow(clippy::manual_div_ceil)]
+        let ell = (len_in_bytes + b_in_bytes - 1) / b_in_bytes;
+        assert!(
+            ell <= 255,
+            "expand_message_xmd output length must not exceed 255 hash outputs"
+        );
+
+        let dst = Dst::new(dst, |out| {
+            // DST = H("H2C-OVERSIZE-DST-" || a_very_long_DST)
+            let hashed = H::new().chain(OVERSIZE_DST_SALT).chain(dst).result();
+            out[..b_in_bytes].copy_from_slice(&hashed);
+            b_in_bytes
+        });
+
+        // b_0 = H(Z_pad || msg || l_i_b_str || I2OSP(0, 1) || DST_prime)
+        let mut h = H::new();
+        let mut z_pad_len = H::BlockSize::to_usize();
+        while z_pad_len > 0 {
+            let len = core::cmp::min(z_pad_len, 64);
+            h.input(&[0u8; 64][..len]);
+            z_pad_len -= len;
+        }
+        h.input(message);
+        h.input([(len_in_bytes >> 8) as u8, len_in_bytes as u8, 0u8]);
+        dst.input_prime(|data| h.input(data));
+        let b_0 = h.result();
+
+        // b_1 = H(b_0 || I2OSP(1, 1) || DST_prime)
+        let mut h = H::new();
+        h.input(&b_0);
+        h.input([1u8]);
+        dst.input_prime(|data| h.input(data));
+        let b_i = h.result();
+
+        ExpandMsgXmdState {
+            dst,
+            b_0,
+            b_i,
+            i: 2,
+            b_offs: 0,
+            remain: len_in_bytes,
+        }
+    }
+}
+
+impl<H> ExpandMessageState for ExpandMsgXmdState<H>
+where
+    H: Digest,
+{
+    fn read_into(&mut self, output: &mut [u8]) -> usize {
+        let read_len = core::cmp::min(self.remain, output.len());
+        let mut offs = 0;
+        while offs < read_len {
+            let b_offs = self.b_offs;
+            let mut copy_len = self.b_i.len() - b_offs;
+            if copy_len > 0 {
+                copy_len = core::cmp::min(copy_len, read_len - offs);
+                output[offs..(offs + copy_len)]
+                    .copy_from_slice(&self.b_i[b_offs..(b_offs + copy_len)]);
+                offs += copy_len;
+                self.b_offs = b_offs + copy_len;
+            } else {
+                // b_i = H(strxor(b_0, b_(i - 1)) || I2OSP(i, 1) || DST_prime)
+                let mut b_prev_xor = self.b_0.clone();
+                for (a, b) in b_prev_xor.iter_mut().zip(self.b_i.iter()) {
+                    *a ^= b;
+                }
+                let mut h = H::new();
+                h.input(&b_prev_xor);
+                h.input([self.i as u8]);
+                self.dst.input_prime(|data| h.input(data));
+                self.b_i = h.result();
+                self.b_offs = 0;
+                self.i += 1;
+            }
+        }
+        self.remain -= read_len;
+        read_len
+    }
+
+    fn remain(&self) -> usize {
+        self.remain
+    }
+}
+
+#[cfg(test)]
+fn expand_to_vec<X: ExpandMessage>(msg: &[u8], dst: &[u8], len_in_bytes: usize) -> std::vec::Vec<u8> {
+    let mut expander = X::init_expand(msg, dst, len_in_bytes);
+    let mut out = vec![0u8; len_in_bytes];
+
+    // Read in uneven pieces to exercise the buffering of each block
+    let mut offs = 0;
+    let mut step = 1;
+    while offs < len_in_bytes {
+        let end = core::cmp::min(offs + step, len_in_bytes);
+        assert_eq!(expander.read_into(&mut out[offs..end]), end - offs);
+        offs = end;
+        step += 7;
+    }
+    assert_eq!(expander.remain(), 0);
+    assert_eq!(expander.read_into(&mut [0u8; 4]), 0);
+
+    out
+}
+
+#[test]
+fn test_expand_message_xmd_sha256() {
+    // Test vectors from RFC 9380, appendix K.1
+    const DST: &[u8] = b"QUUX-V01-CS02-with-expander-SHA256-128";
+    let vectors: [(&[u8], usize, &str); 4] = [
+        (
+            b"",
+            0x20,
+            "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235",
+        ),
+        (
+            b"abc",
+            0x20,
+            "d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615",
+        ),
+        (
+            b"abcdef0123456789",
+            0x20,
+            "eff31487c770a893cfb36f912fbfcbff40d5661771ca4b2cb4eafe524333f5c1",
+        ),
+        (
+            b"",
+            0x80,
+            "af84c27ccfd45d41914fdff5df25293e221afc53d8ad2ac06d5e3e29485dadbee0d121587713a3e0dd4d5e69e93eb7cd4f5df4cd103e188cf60cb02edc3edf18eda8576c412b18ffb658e3dd6ec849469b979d444cf7b26911a08e63cf31f9dcc541708d3491184472c2c29bb749d4286b004ceb5ee6b9a7fa5b646c993f0ced",
+        ),
+    ];
+
+    for &(msg, len, expected) in vectors.iter() {
+        let out = expand_to_vec::<ExpandMsgXmd<sha2::Sha256>>(msg, DST, len);
+        assert_eq!(hex::encode(out), expected);
+    }
+}
+
+#[test]
+fn test_expand_message_xmd_long_dst() {
+    let dst = [0x51u8; 300];
+    let out = expand_to_vec::<ExpandMsgXmd<sha2::Sha256>>(b"abc", &dst[..], 0x40);
+    assert_eq!(
+        hex::encode(out),
+        "f5102336ac6678bc19393262236cd5f461e788b5392b472348ff43cf41bd8278fe766d41765a0278c67a9197ab98a7676770215b81b1ff02d5bef3902f44d1ad"
+    );
+}
+
+#[test]
+fn test_expand_message_xof_shake128() {
+    // Test vectors from RFC 9380, appendix K.3
+    const DST: &[u8] = b"QUUX-V01-CS02-with-expander-SHAKE128";
+    let vectors: [(&[u8], usize, &str); 2] = [
+        (
+            b"",
+            0x20,
+            "86518c9cd86581486e9485aa74ab35ba150d1c75c88e26b7043e44e2acd735a2",
+        ),
+        (
+            b"abc",
+            0x20,
+            "8696af52a4d862417c0763556073f47bc9b9ba43c99b505305cb1ec04a9ab468",
+        ),
+    ];
+
+    for &(msg, len, expected) in vectors.iter() {
+        let out = expand_to_vec::<ExpandMsgXof<sha3::Shake128>>(msg, DST, len);
+        assert_eq!(hex::encode(out), expected);
+    }
+}
+
+#[test]
+fn test_expand_message_xof_long_dst() {
+    let dst = [0x51u8; 300];
+    let out = expand_to_vec::<ExpandMsgXof<sha3::Shake128>>(b"abc", &dst[..], 0x40);
+    assert_eq!(
+        hex::encode(out),
+        "4d608dd6194a3cccd5e6c0eaba0527fdb1a32d39479d037351906699f34cb7cac2899c1972ae803dea8b31ae5ea63a88b8fd1edcb14de7627756644cfab96f8a"
+    );
+}
@@ -0,0 +1,337 @@
+//! This module implements hashing to $\mathbb{G}_1$ of BLS12-377, using the
+//! simplified SWU map onto a 2-isogenous curve as described in RFC 9380,
+//! section 6.6.3.
+
+use digest::generic_array::{typenum::U64, GenericArray};
+use subtle::{Choice, ConditionallyNegatable, ConditionallySelectable, ConstantTimeEq};
+
+use super::{HashToField, MapToCurve, Sgn0};
+use crate::fp::{self, Fp};
+use crate::g1::G1Projective;
+
+/// Coefficient A of the isogenous curve E': y^2 = x^3 + A * x + B, which is -15.
+const SSWU_ELLP_A: Fp = Fp::from_raw_unchecked([
+    0x6f19c000000008ed,
+    0xb06f79a0700004a3,
+    0x426363ff84d7a8cd,
+    0x49135dff8b7f9477,
+    0x5cd256899b9aa488,
+    0x001e23a229f7ff8b,
+]);
+
+/// Coefficient B of the isogenous curve E', which is 22.
+const SSWU_ELLP_B: Fp = Fp::from_raw_unchecked([
+    0x9a76bffffffff2e9,
+    0x5a3e286faffff932,
+    0xdc25c143d08286d2,
+    0xe1cd141e77fcf991,
+    0x3167b6320cca6b5c,
+    0x0063347edb6f8ed7,
+]);
+
+/// Z = -11, the non-square chosen by the `find_z_sswu` procedure of RFC 9380,
+/// appendix H.2, for the curve E'.
+const SSWU_Z: Fp = Fp::from_raw_unchecked([
+    0xf54900000000068c,
+    0xde669a6a40000366,
+    0xa166d075f4c36096,
+    0x1c2ae2ea447c0cfe,
+    0xca69a7c72feb6eef,
+    0x00a582e39e2ac109,
+]);
+
+/// Z^t, where p - 1 = 2^s * t and t is odd.
+const SQRT_RATIO_C6: Fp = Fp::from_raw_unchecked([
+    0xe5c85f8bf4c4b7df,
+    0xc714dafc33fe8b3f,
+    0xd1eac56f1c0bd13f,
+    0x8e838cbfaa9cb0d2,
+    0x29212c79fd19edc0,
+    0x00f1f0d31144e581,
+]);
+
+/// Z^((t + 1) / 2).
+const SQRT_RATIO_C7: Fp = Fp::from_raw_unchecked([
+    0x9b8ecd665efa5275,
+    0x67089c7dc9070a39,
+    0xd998301b52d9dd8a,
+    0xfbfa3741b26bed42,
+    0x7f6e8330c90ce48b,
+    0x01143f3a3b88ee78,
+]);
+
+const THREE: Fp = Fp::from_raw_unchecked([
+    0x0869fffffffffe38,
+    0xf3c1de8a7fffff13,
+    0xde791afc9f77bfd6,
+    0x71ebc7264b752910,
+    0xe6ddc13e80b58eb9,
+    0x01a83325a8f9ddce,
+]);
+
+impl HashToField for Fp {
+    // ceil(log2(p)) = 377, m = 1, k = 128, so L = ceil((377 + 128) / 8) = 64.
+    type InputLength = U64;
+
+    fn from_okm(okm: &GenericArray<u8, U64>) -> Fp {
+        let mut bytes = [0u8; 64];
+        bytes.copy_from_slice(&okm[..]);
+        Fp::from_bytes_wide(&bytes)
+    }
+}
+
+impl Sgn0 for Fp {
+    fn sgn0(&self) -> Choice {
+        // The parity of the canonical representative; `to_bytes` is big-endian.
+        Choice::from(self.to_bytes()[47] & 1)
+    }
+}
+
+/// Computes `v^(2^k)`.
+fn square_n(v: &Fp, k: u32) -> Fp {
+    let mut v = *v;
+    for _ in 0..k {
+        v = v.square();
+    }
+    v
+}
+
+/// Returns `(true, sqrt(u / v))` if `u / v` is square, and otherwise
+/// `(false, sqrt(Z * u / v))`, in constant time. This is the `sqrt_ratio`
+/// function of RFC 9380, appendix F.2.1.1, with c1 = 46.
+///
+/// `v` must not be zero.
+fn sqrt_ratio(u: &Fp, v: &Fp) -> (Choice, Fp) {
+    let c1 = fp::two_adicity();
+
+    // The exponents below are public, so `pow_vartime` runs in constant time
+    // with respect to the field elements.
+    let mut tv1 = SQRT_RATIO_C6;
+    // v^c4, where c4 = 2^c1 - 1
+    let mut tv2 = *v;
+    for _ in 1..c1 {
+        tv2 = tv2.square() * v;
+    }
+    let mut tv3 = tv2.square() * v;
+    let mut tv5 = (u * tv3).pow_vartime(&fp::t_minus_one_div_two()) * tv2;
+    tv2 = tv5 * v;
+    tv3 = tv5 * u;
+    let mut tv4 = tv3 * tv2;
+    // tv4^c5, where c5 = 2^(c1 - 1)
+    tv5 = square_n(&tv4, c1 - 1);
+    let is_qr = tv5.ct_eq(&Fp::one());
+    tv2 = tv3 * SQRT_RATIO_C7;
+    tv5 = tv4 * tv1;
+    tv3 = Fp::conditional_select(&tv2, &tv3, is_qr);
+    tv4 = Fp::conditional_select(&tv5, &tv4, is_qr);
+
+    for i in (2..=c1).rev() {
+        tv5 = square_n(&tv4, i - 2);
+        let e1 = tv5.ct_eq(&Fp::one());
+        tv2 = tv3 * tv1;
+        tv1 = tv1.square();
+        tv5 = tv4 * tv1;
+        tv3 = Fp::conditional_select(&tv2, &tv3, e1);
+        tv4 = Fp::conditional_select(&tv5, &tv4, e1);
+    }
+
+    (is_qr, tv3)
+}
+
+/// Maps a field element to a point on E', returning `(xn, xd, y)` such that
+/// the point is `(xn / xd, y)`. This is the straight-line simplified SWU map of
+/// RFC 9380, appendix F.2, without the final inversion.
+fn map_to_curve_simple_swu(u: &Fp) -> (Fp, Fp, Fp) {
+    let tv1 = SSWU_Z * u.square();
+    let mut tv2 = tv1.square() + tv1;
+    let tv3 = SSWU_ELLP_B * (tv2 + Fp::one());
+    let tv4 = SSWU_ELLP_A * Fp::conditional_select(&-tv2, &SSWU_Z, tv2.is_zero());
+
+    tv2 = tv3.square();
+    let mut tv6 = tv4.square();
+    tv2 = (tv2 + SSWU_ELLP_A * tv6) * tv3;
+    tv6 *= tv4;
+    tv2 += SSWU_ELLP_B * tv6;
+
+    let (is_gx1_square, y1) = sqrt_ratio(&tv2, &tv6);
+
+    let x = Fp::conditional_select(&(tv1 * tv3), &tv3, is_gx1_square);
+    let mut y = Fp::conditional_select(&(tv1 * u * y1), &y1, is_gx1_square);
+    y.conditional_negate(!u.sgn0().ct_eq(&y.sgn0()));
+
+    (x, tv4, y)
+}
+
+/// Maps a point `(xn / xd, y)` of E' to the curve E: y^2 = x^3 + 1.
+///
+/// E' has the rational 2-torsion point (2, 0), and Velu's formulae for the
+/// isogeny with that kernel give
+///
+/// (x, y) -> (x - 3 / (x - 2), y * (1 + 3 / (x - 2)^2))
+///
+/// onto y^2 = x^3 + 64, which is isomorphic to E by (x, y) -> (x / 4, y / 8).
+/// The result is written in Jacobian coordinates to avoid inversions, and the
+/// kernel of the isogeny maps to the identity.
+fn iso_map(xn: &Fp, xd: &Fp, y: &Fp) -> G1Projective {
+    // With d = x - 2 = dn / xd and z = xd * dn, the image of the point on
+    // y^2 = x^3 + 64 is (x_num / z^2, y_num / z^3), where
+    //   x_num = (xn * dn - 3 * xd^2) * z
+    //   y_num = y * (dn^2 + 3 * xd^2) * xd^2 * z
+    // Doubling z then applies the isomorphism to E.
+    let dn = xn - (xd + xd);
+    let xd2 = xd.square();
+    let three_xd2 = THREE * xd2;
+    let z = xd * dn;
+
+    G1Projective {
+        x: (xn * dn - three_xd2) * z,
+        y: y * (dn.square() + three_xd2) * xd2 * z,
+        z: z + z,
+    }
+}
+
+impl MapToCurve for G1Projective {
+    type Field = Fp;
+
+    fn map_to_curve(u: &Fp) -> G1Projective {
+        let (xn, xd, y) = map_to_curve_simple_swu(u);
+        iso_map(&xn, &xd, &y)
+    }
+
+    fn clear_h(&self) -> G1Projective {
+        self.clear_cofactor()
+    }
+}
+
+#[cfg(test)]
+fn fp_from_hex(s: &str) -> Fp {
+    let mut bytes = [0u8; 48];
+    bytes.copy_from_slice(&hex::decode(s).unwrap());
+    Fp::from_bytes(&bytes).unwrap()
+}
+
+#[test]
+fn test_hash_to_field_fp() {
+    use super::{ExpandMsgXmd, ExpandMsgXof};
+
+    const DST: &[u8] = b"QUUX-V01-CS02-with-BLS12377_XMD:SHA-256_HASH_TO_FIELD_";
+    let vectors: [(&[u8], [&str; 2]); 2] = [
+        (
+            b"",
+            [
+                "016239941514b34a41cdfc8bcb2419e059d5c9753ec568a3eb9e1249c451845ab27f12a7e31f7c6720e5249f69cad972",
+                "00286c162e532b95463f4b0000fad607c45ea24b3a07d8d81c064b9b1714bd4844d421d0d2fc553d139e889cb2f26638",
+            ],
+        ),
+        (
+            b"abc",
+            [
+                "0149572d0510de52c2b4c756068aebec85e8130c299ab0eaac71b19f404cf492562a2a0ed8728f9509f897572c51b4e4",
+                "0157427dc2ecb2f9edc1e4e765bc60af3ef677dae5f04d19fb558d6e66b73ce99a72f33f2195e9caa0b2d31f73a1997c",
+            ],
+        ),
+    ];
+    for &(msg, expected) in vectors.iter() {
+        let mut u = [Fp::zero(); 2];
+        Fp::hash_to_field::<ExpandMsgXmd<sha2::Sha256>>(msg, DST, &mut u);
+        assert_eq!(u[0], fp_from_hex(expected[0]));
+        assert_eq!(u[1], fp_from_hex(expected[1]));
+    }
+
+    const XOF_DST: &[u8] = b"QUUX-V01-CS02-with-BLS12377_XOF:SHAKE128_HASH_TO_FIELD_";
+    let mut u = [Fp::zero(); 2];
+    Fp::hash_to_field::<ExpandMsgXof<sha3::Shake128>>(b"abc", XOF_DST, &mut u);
+    assert_eq!(
+        u[0],
+        fp_from_hex("0126b9c6789722f59d251d20228559601ae2272a3bf0100d1ed939371f4f0bf5448ea69e71b0e0d3119b001138b604e5")
+    );
+    assert_eq!(
+        u[1],
+        fp_from_hex("001c95135c52eefa059773e775ce84419c3ab247c227beed382ce5dce0b65d6718ea70c10011e2084d262ead7c900c9e")
+    );
+}
+
+#[test]
+fn test_sqrt_ratio() {
+    let mut u = Fp::one();
+    let v = SSWU_ELLP_B;
+    for _ in 0..10 {
+        let (is_qr, root) = sqrt_ratio(&u, &v);
+        let ratio = u * v.invert().unwrap();
+        match ratio.sqrt_vartime() {
+            Some(_) => {
+                assert!(bool::from(is_qr));
+                assert_eq!(root.square(), ratio);
+            }
+            None => {
+                assert!(!bool::from(is_qr));
+                assert_eq!(root.square(), SSWU_Z * ratio);
+            }
+        }
+        u += THREE;
+    }
+
+    // The straight-line algorithm reports 0 / v as a non-square, but the root
+    // is still correct. This never arises in the SSWU map, as g(x) has no roots.
+    let (_, root) = sqrt_ratio(&Fp::zero(), &v);
+    assert!(bool::from(root.is_zero()));
+}
+
+#[test]
+fn test_map_to_curve() {
+    use crate::G1Affine;
+
+    // Exercise both branches of the map, the exceptional case u = 0, and an
+    // input whose image lies in the kernel of the isogeny.
+    let mut u = Fp::zero();
+    for _ in 0..10 {
+        let p = G1Projective::map_to_curve(&u);
+        assert!(bool::from(p.is_on_curve()));
+        let p = G1Affine::from(p.clear_h());
+        assert!(bool::from(p.is_on_curve()));
+        assert!(bool::from(p.is_torsion_free()));
+        u += Fp::one();
+    }
+}
+
+#[test]
+fn test_hash_to_curve_g1() {
+    use super::{ExpandMsgXmd, HashToCurve};
+    use crate::G1Affine;
+
+    fn check<F: Fn(&[u8]) -> G1Projective>(f: F, vectors: &[(&[u8], &str)]) {
+        for &(msg, expected) in vectors {
+            let p = G1Affine::from(f(msg));
+            assert!(bool::from(p.is_on_curve()));
+            assert!(bool::from(p.is_torsion_free()));
+            assert_eq!(hex::encode(&p.to_uncompressed()[..]), expected);
+        }
+    }
+
+    let msg_q128 = [b"q128_".as_ref(), &[b'q'; 128][..]].concat();
+    let msg_a512 = [b"a512_".as_ref(), &[b'a'; 512][..]].concat();
+
+    const RO_DST: &[u8] = b"QUUX-V01-CS02-with-BLS12377G1_XMD:SHA-256_SSWU_RO_";
+    check(
+        |msg| <G1Projective as HashToCurve<ExpandMsgXmd<sha2::Sha256>>>::hash_to_curve(msg, RO_DST),
+        &[
+            (b"", "00e19d01961b3f61ca264dfa4332d9ffaf684d6da28d12066465c03c642dac9e5abf209a3e10c9b09193696afee625bc01397b5b5a9b5b34bdfdf00ab843814caad98c4ce4d9b4b70726f79224f33bcaf4ece464baf44b714ae85b49a560c11f"),
+            (b"abc", "019251cae20a1a9a01839ca7fe5f0ea713a46bd8322d5f69dc6fdc13b2bbca89ca2c369f77265a8bcd038a78f96544e1004a35ffa1fb61de9df56535f783307766d5923255a3ca541e8687147e15160e64cfa3b1769ba4a2ea27a89ecdf8ebd7"),
+            (b"abcdef0123456789", "0161e9f9382578c0e9a7338c1d53cac3a51d0d7709702ca57cc382e700710b106237ea3917f0cfc9380b641a269f7c0400d4dbcf8121f4e606097c16ed251a296715b7a8a1071c0abc97f9eecb2b1fa3fc6b598b9b7cf5a8f39606165b96b8d9"),
+            (&msg_q128, "01241a3e84855fb4b13c049d0216e091d2b877d50b44c3a9c7a21a7f0ac43bd0c8387a60671b1c3d23f0f5bd42ab9668004983416545079c672c7b9520a56d56f0b9d3dcc82871694e2b31a2e245d14d0498e20a6f523257c9ffbfaaa0354746"),
+            (&msg_a512, "007c5721178578705be9191c3e26e8f7b2e6750c9bea0fef00ad1a736a1cb26da0cdbcf5a860a03e533c299801a10953001dca60e6dc838df91b51074096793c38b3d1148d67245a383a26c735743037e55ecec81de42b761aece007afc361b9"),
+        ],
+    );
+
+    const NU_DST: &[u8] = b"QUUX-V01-CS02-with-BLS12377G1_XMD:SHA-256_SSWU_NU_";
+    check(
+        |msg| <G1Projective as HashToCurve<ExpandMsgXmd<sha2::Sha256>>>::encode_to_curve(msg, NU_DST),
+        &[
+            (b"", "01907484865ea423c73415f458614d075424b975bbfc0c5652b466f73a21094a5923409500256fba7f8f65d7b43299eb0071b1add87be177667e8ba1e7ce035d8cdc08e8865093866c5c84d875fdb111108c684c334eb159deb958076230cf7a"),
+            (b"abc", "009d1ed26f000a08d384bf0657207f8800d9c34625f9defbff8ed54361975763a9d7c64403e6ab90ad16b5aa0bd1bbff018006e30484312764ca2ec8ca5675c644f0c460083c4c8cf40d57d0556482ba4430d9fbf1ab29da602b2acacf407bc5"),
+            (b"abcdef0123456789", "00ffca3d1b700118558c4137a68baad0af0a20f7d5359d512da9f29fa941395f1fdc11aa136ebc7da89af2d8e19ef103015c385b16c2f9452d188b1e056ff0d98722fe193338f41b2fc48d32bed7dbc54e6247023a603cf28b7052957631cd6d"),
+            (&msg_q128, "01a2e885cd4d308d956a25e6d812f35a47aa41ddaf567694b00d149453c23ccbc1c6f411437b9a95c922df830337618a00f40677c35e09e50681d9599267366c849e2a4beea09b66afb73b77af0f717f519a48af99a84b2f7065b35e7fd33b7f"),
+            (&msg_a512, "00dafabbff2c1d5da88acaa8c74d31b703283dc7bce8e2dfa285e5002e561bcc0b2d2054b8212ff6b1322f05efce55c5015a3de14b917d17ad44bf494e04afba13e3f7be9e18ec58d46516eee14f2380669b21b15699e4912b585e2dfdf07fcb"),
+        ],
+    );
+}
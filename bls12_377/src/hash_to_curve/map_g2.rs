@@ -0,0 +1,1688 @@
+//! This module implements hashing to $\mathbb{G}_2$ of BLS12-377, using the
+//! simplified SWU map onto an isogenous curve as described in RFC 9380,
+//! section 6.6.3.
+//!
+//! The twist E: y^2 = x^3 + 1/u has no rational 2- or 3-torsion subgroup other
+//! than those fixed by its automorphisms, so the smallest isogeny onto it from
+//! a curve with a nonzero j-invariant has degree 23. The isogenous curve E' and
+//! the constants below were derived with Velu's formulae from a 23-torsion
+//! subgroup of E defined over an extension of degree 11.
+
+use digest::generic_array::{typenum::U128, GenericArray};
+use subtle::{Choice, ConditionallyNegatable, ConditionallySelectable, ConstantTimeEq};
+
+use super::{HashToField, MapToCurve, Sgn0};
+use crate::fp::Fp;
+use crate::fp2::Fp2;
+use crate::g2::G2Projective;
+
+/// Coefficient A of the isogenous curve E': y^2 = x^3 + A * x + B.
+const SSWU_ELLP_A: Fp2 = Fp2 {
+    c0: Fp::from_raw_unchecked([
+        0x3b523c8cdcff9889,
+        0xc4780e8aecb49c4e,
+        0xb6213978038aee6f,
+        0x3dfa913b0bb775fa,
+        0x7dc49dcc25fe2b32,
+        0x013fd9f9381384b3,
+    ]),
+    c1: Fp::from_raw_unchecked([
+        0x44d116606c7d0832,
+        0x47b1b75c2c43ef0d,
+        0x58f4f6cec624a506,
+        0x8baf58e6bba2cba7,
+        0x3ab84ef126f23e5b,
+        0x0020e07cfc7bb701,
+    ]),
+};
+
+/// Coefficient B of the isogenous curve E'.
+const SSWU_ELLP_B: Fp2 = Fp2 {
+    c0: Fp::from_raw_unchecked([
+        0xf6f6133cad0e2bf0,
+        0xa1684a4a6adf377c,
+        0xc2c78bcb17019fb8,
+        0x023ec01738585200,
+        0x4e18cc284dba98ec,
+        0x0123ac74f4cfb105,
+    ]),
+    c1: Fp::from_raw_unchecked([
+        0x20c316aeaae12d45,
+        0x9033a807ecda62f5,
+        0xad04281c8e9f799f,
+        0x721250e7ea917628,
+        0x2b735c35b0e9c073,
+        0x0128b6ff2b34c941,
+    ]),
+};
+
+/// Z = 5 + u, the non-square chosen by the `find_z_sswu` procedure of RFC 9380,
+/// appendix H.2, for the curve E'.
+const SSWU_Z: Fp2 = Fp2 {
+    c0: Fp::from_raw_unchecked([
+        0x88fd3ffffffffd07,
+        0x7f37c04d4ffffe74,
+        0xfe81201ffa68f7bb,
+        0x4e661ca22778db8c,
+        0xba8be6fd148d4f4f,
+        0x0114c5a35730b618,
+    ]),
+    c1: Fp::from_raw_unchecked([
+        0x02cdffffffffff68,
+        0x51409f837fffffb1,
+        0x9f7db3a98a7d3ff2,
+        0x7b4e97b76e7c6305,
+        0x4cf495bf803c84e8,
+        0x008d6661e2fdf49a,
+    ]),
+};
+
+/// The 2-adicity s of p^2 - 1.
+const SQRT_RATIO_C1: u32 = 47;
+
+/// (t - 1) / 2, as a little-endian array of 64-bit limbs.
+const SQRT_RATIO_C3: [u64; 12] = [
+    0xd254f00000010a11,
+    0x268f2e1bd8007338,
+    0xfbd235f1c09a4ada,
+    0x24b958638c9d6bcb,
+    0xaaaa8bb70ae03183,
+    0xe712502a4d6c460a,
+    0x18afb90660cdc014,
+    0x71413dda4d5c09d0,
+    0xb87da4aee93f1f5e,
+    0x913bb361263c4bb8,
+    0xd307d0bbaffb2256,
+    0x0000000000000002,
+];
+
+/// Z^t, where p^2 - 1 = 2^s * t and t is odd.
+const SQRT_RATIO_C6: Fp2 = Fp2 {
+    c0: Fp::from_raw_unchecked([
+        0x0000000000000000,
+        0x0000000000000000,
+        0x0000000000000000,
+        0x0000000000000000,
+        0x0000000000000000,
+        0x0000000000000000,
+    ]),
+    c1: Fp::from_raw_unchecked([
+        0xe7ab978919807187,
+        0xe17c762e511dded0,
+        0x1d24fec67a997830,
+        0x29267fb15bdb7c33,
+        0xa9c04a9e67b73e00,
+        0x005649b587e6c97a,
+    ]),
+};
+
+/// Z^((t + 1) / 2).
+const SQRT_RATIO_C7: Fp2 = Fp2 {
+    c0: Fp::from_raw_unchecked([
+        0x54a3dabfaa863fcc,
+        0x98152f1cf7d1a97f,
+        0x9ffe738ac885f6fb,
+        0xed16ccba5acc021b,
+        0xdd605011d8e2f2b6,
+        0x00ab1f9d6ae0c047,
+    ]),
+    c1: Fp::from_raw_unchecked([
+        0x02d95402e60fe7db,
+        0x3e27666e58776daa,
+        0x15592958414a71bd,
+        0xbbe39a64a7c357da,
+        0xbdb1222d941da9bc,
+        0x008f0b285ba238d9,
+    ]),
+};
+
+/// Coefficients of the numerator of the x-coordinate map, lowest degree first.
+const ISO_XNUM: [Fp2; 24] = [
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x53be47eb64170be6,
+            0x3d8ac1279b79cd11,
+            0xbb01c0fbb0817b78,
+            0x93ba56dc014c49f1,
+            0x2731aa2b666f49a4,
+            0x013471831f9f006c,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x5d7334fadd62c272,
+            0x6f66ee7b250ac89e,
+            0x885e5e6d79820d7a,
+            0xf690ed725bfce276,
+            0xe8051340fe726399,
+            0x001f5c644ccaea1c,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x8b52d9d9a86ce715,
+            0x6f609bb895337b50,
+            0xd9c7e02700e3b1c0,
+            0xc36ab690181d8cd6,
+            0xad3df2d3c9dd735a,
+            0x009371d86da5410d,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x5771e851b7ee2a86,
+            0x331a209b9b7f1ca5,
+            0x633d8478621c36e9,
+            0xa7eaa150e913c0da,
+            0xc19e6934e052cd3e,
+            0x00ddb4c96d6051ec,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x9133eda61a52cb4d,
+            0x914be99e2ae394fa,
+            0x7e876c04b5a1c417,
+            0x1310d68c3509f7df,
+            0xdc7b105d5d6f9b70,
+            0x01843bab9b19bcef,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0xfaf0adf22bb2f1cd,
+            0x6603ea733fa8e73f,
+            0x78fe635e1bad0c26,
+            0x9e8c7af50da309f9,
+            0xaf0253caa24e5621,
+            0x01868de0742f078c,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0xc7107733d06de3e8,
+            0xccfa4e577d8f71b0,
+            0xc07e6d33e649e108,
+            0xc6d4cae8d4fc9d6e,
+            0x1d2579427bc97d4d,
+            0x018dda50cfc7d05e,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x88eae8ec85bae22b,
+            0xa95d0d577dcec094,
+            0x830676f359dae572,
+            0xc631f7f4b65bf68d,
+            0x3d50196bd72506d7,
+            0x00ad84ddcbfa08ed,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x041d2109437ab6a3,
+            0x0e8faf16c816f45a,
+            0xf3152ee4b7ffa5a5,
+            0xfa664bee3837c731,
+            0xfb2efd093d601458,
+            0x0044bbeeea3ddb7d,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x0307d003baefd680,
+            0x2cf538352413f0a3,
+            0x6e69d49ad79fb253,
+            0x3d85dc98df87d2cf,
+            0x69808c8372418443,
+            0x00808caf7a66e662,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x686ab68dec533b1b,
+            0xdca6665f36ec7c09,
+            0x80a4c9347e445b72,
+            0xadd79adc49e062d8,
+            0x342000a141255c15,
+            0x016768cd5176a597,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x2eb3b97023649b66,
+            0x082fe80ace2884e1,
+            0xa1a72d3890edebb7,
+            0x16c9b43a0b9adabe,
+            0xd0497c1a342cd27a,
+            0x00db1a5a50fcef2e,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x751c96745f99b683,
+            0x7092703e1951f1c9,
+            0xc70c2b0665b1b433,
+            0xdbd0f627529810b0,
+            0xe1765c53afe1ed3e,
+            0x013c57457d478ead,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x1692817f1566b315,
+            0xdbe1148c29049baf,
+            0x2cba15e63510ffd3,
+            0x0f28fa6d32749943,
+            0xd8acefb7cca7cfff,
+            0x007f0b058126a7cf,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x833d5e8344a7ff48,
+            0x7945a88d2c0d159b,
+            0xf29bc720c9a8bf36,
+            0x8e48c9fec628996c,
+            0xb48243d1b67e76c3,
+            0x00c01143ab69ee00,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x17fcbe52e89756e3,
+            0x1b08b4bb12f3f6dd,
+            0xbe67003c22631ed7,
+            0x18b8dfe86718a459,
+            0x5f60315c1c9f8a15,
+            0x01703034158889b4,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x32a9d76c395ccd83,
+            0xd3783bfa5b26ae95,
+            0x504627ffc5c3bb3a,
+            0xc310fb9e26ddd318,
+            0xc39667dc3a80da9a,
+            0x0049cb7363b3b730,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x389f92b96d35a774,
+            0xc7b232e069671f65,
+            0xd81b215b05eca96f,
+            0x4d58347a795a7e22,
+            0x4f4cfa07472b804a,
+            0x0026c3726df22bdb,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0xa73b8fe037a987c4,
+            0x6a914a26120ecff9,
+            0x3a0913c657e5deba,
+            0x3e4ba64cf1ca6cb9,
+            0x185c11b36a8b2c39,
+            0x007802fd326ce99b,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0xe6ebe5536bbd3e4d,
+            0x80aa7270e2db4c4d,
+            0xdd152ef9cd2dd666,
+            0xc483eba2bbeb304c,
+            0x29f90b7ece3d1329,
+            0x00f2bea93f9ec577,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x4581c8e4c71eec58,
+            0x0f27a1e3e1024503,
+            0xdd2d69b4e0f78c98,
+            0x95c65a3954b2d445,
+            0x2449de1337c3b2fd,
+            0x00071b844efbdab2,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x4efb545ca46126e3,
+            0xbcc175400bc021f0,
+            0x6c83b521beeb6f4f,
+            0xa1b535b993fe2ebe,
+            0x1ef898c01c90b86a,
+            0x018a3b2a4f48f26e,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x7c4e38075d337cb1,
+            0x4eace315c83a0303,
+            0xf08e5f5b521a588c,
+            0x43e4bb1ebb4d50e8,
+            0x75dc9768d12f865e,
+            0x00fb6a36c63fc3fa,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x1114be365bbd6658,
+            0x2eeb3916854d22ab,
+            0xc04e58071e5047df,
+            0x990a17f476349e48,
+            0x9771edd7fe86b469,
+            0x01395506b31e7db6,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x53976740118911f7,
+            0x3df78b88307dca3c,
+            0xe9d38fca72229f81,
+            0xcd9ed437c0fd120a,
+            0xb58bc0e018ff9930,
+            0x0169fae8d7c2d610,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x21c4f8437c111db7,
+            0xc843f4af9d110b7b,
+            0x6d60b97b8bedf730,
+            0x8bfbe98288dffdc9,
+            0xc6bef7287614ad25,
+            0x012b39660bdefb86,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x68b11f7a1c5e7f1a,
+            0xc3b0e545836f3c1c,
+            0x5915e47b1c2219ab,
+            0x9d30c3eb44d50ecc,
+            0xffcbb6d861b1e79c,
+            0x012f4254174e6251,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x972392448db7f595,
+            0xcf6f71bcd230b38e,
+            0xc638a286e2be1bfe,
+            0xa1c7e8a52c72d418,
+            0x5928b6798af3d670,
+            0x00d1610cf00a30bd,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x5c06e6894a266a8b,
+            0x7fcaa22fdd8b4142,
+            0x87768d9973589304,
+            0x105f8c1ff93f66a6,
+            0xfbc10e66356137ac,
+            0x006e4cbf7dc93392,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0xff99e03f89feb29a,
+            0x99329958179238a2,
+            0x8a66d3c2a33d17d6,
+            0xb8119ad72f8e308a,
+            0x3ec3bf51f2165f1c,
+            0x00e2f7f99192884d,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x88e471ae57ce2073,
+            0xadd3c1148b142580,
+            0x483d1a8a24f0267c,
+            0xaf8ab757f10bbd0f,
+            0xcb1d88020afaa6fb,
+            0x01552665324d9cda,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0xf5dd861272cb7ace,
+            0x81d92a6a0636bbf7,
+            0xe04f9626e4fbebcb,
+            0xf58f0f24100c30b1,
+            0xde2e45045824069d,
+            0x013eb4f6c353f18d,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x5b58915ac8cdb00d,
+            0xe9ee0e040bf5c69c,
+            0xbcf2b4e93ae85c8d,
+            0x387dcdb84a591b6a,
+            0xafa7050a338a87d8,
+            0x009bff355c82a09a,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0xc888e55b992fac90,
+            0xa414da9b2244c854,
+            0x6029d62ea45a86cc,
+            0xd4f4023d51e22ea1,
+            0xdb196bd11997e4da,
+            0x00f8069b025d78d3,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x3aad44001036b818,
+            0xf9f27070198d95cf,
+            0xbe1dda8332020d29,
+            0xf5b3e32bd09524f2,
+            0x4c28fbeb5158f9be,
+            0x018ea1f2da65eba7,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0xb74d3e0b841d1bac,
+            0xad0ed93be35bf6c8,
+            0x1080a508e9a93272,
+            0xdfbd3596e150b15e,
+            0x4074da8f4ea5b49c,
+            0x0032ae72c4f080f3,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0xefede03dad6c5b22,
+            0x9db99d7bd90a42e0,
+            0xdffa703d7e29f455,
+            0x94480fcadce59e84,
+            0xdf405c54e068fc1c,
+            0x00e1d8186fd224c5,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x504bc59c65928962,
+            0x0bc4a67fa82ba2a1,
+            0x9f4a8d5c208580b0,
+            0xb0d1544672ad9d7d,
+            0x8cd70a406738937b,
+            0x017b6be9be258af5,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x59942c4571f4b89c,
+            0x8a77dc1e9bb882cc,
+            0x05bbab1e83cf34a7,
+            0xbcaaf9ad8f555dee,
+            0xf720e7cba0d67846,
+            0x018330cb01f98b44,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x9870cf06c3486991,
+            0xef5d4243b61f9f6e,
+            0x88cfe93fbdf2436e,
+            0xb159db672ef62765,
+            0xbfdb249b70afd470,
+            0x01286b8a27f676a6,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x0cde92286e0b0baa,
+            0x5b3fb5191c9b1450,
+            0x6c4471eb7298dcd5,
+            0xbba161710bc83994,
+            0x9e9ff7817cb09d40,
+            0x0079048c2170ec7d,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0xe239e0fdd93b4f9b,
+            0x7e5a3af338b62807,
+            0xad22e4b109842724,
+            0x37d9b8ca5d9c4f63,
+            0x4e16cbd70b111230,
+            0x00c964cd75bcb538,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x8705cd1de5248651,
+            0xc78d19ed68d52cf2,
+            0xd13c748236fb9b08,
+            0x120905a28107cb98,
+            0xc22853c6d2cd27f4,
+            0x0138e365142e6d7a,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x3502404f67e79919,
+            0x07df334a0fd82cb8,
+            0x0f6e53ff6892b712,
+            0x38c7a2de5edd7712,
+            0x7a681fdd1bcb136c,
+            0x00104ed73021cbc4,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x9ee1a8c41e6d5302,
+            0xa37e7b224fbd14ef,
+            0xf37c93eb7a8515e1,
+            0xd81fe514c1cffd2e,
+            0x5c2aecc2bc5dc491,
+            0x0196f7a31412348b,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x95ec20976846bdd0,
+            0x551fb5dded503658,
+            0x6bbaabcedc689a15,
+            0xd0a9e3d2ef614cdf,
+            0x8d9283a358061d5c,
+            0x0110781b5441fc9b,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x3242df55a7eca48a,
+            0x105e7a39c4e65a03,
+            0x868c02141c568769,
+            0x4378c64523ddc744,
+            0xd92a719398a84931,
+            0x016e3ed4bb9b0cea,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x0000000000000000,
+            0x0000000000000000,
+            0x0000000000000000,
+            0x0000000000000000,
+            0x0000000000000000,
+            0x0000000000000000,
+        ]),
+    },
+];
+
+/// Coefficients of the kernel polynomial of the isogeny, lowest degree first.
+/// Its square is the denominator of the x-coordinate map.
+const ISO_XDEN: [Fp2; 12] = [
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x25b139e405b98352,
+            0x94b0b3f24ded9940,
+            0x377cb3899671082e,
+            0x1f862e14700e64be,
+            0xb3d82015e19e0ae6,
+            0x00009e869a1922c9,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0xf89e803973f2fb23,
+            0x845806ede884cc4c,
+            0xbedd6d88196dcacc,
+            0xa1a3fb547e7c4ae3,
+            0xe7886b570c3b8575,
+            0x0045de7803eed33a,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x7f6a7954cc748858,
+            0x094ff4807855e9ac,
+            0x69afd81f4c68f51a,
+            0xb3de5460ad055729,
+            0x8adf1e703d32ba2d,
+            0x0172b7b2978afee8,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0xe62b440729438af3,
+            0x00aefac51084d6b4,
+            0xef7ec4d78b6cad18,
+            0x0b6b7ee96b204fa3,
+            0xef96b2ea4e3f5168,
+            0x0170ac70e08c7e10,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x132d12f97bbfc031,
+            0x5ea4a418165d8742,
+            0x00285c0cb36e1e60,
+            0x5cf082cbbf078da0,
+            0x45d627efae1e515a,
+            0x0191aace695e5233,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x0abd8596c8474f57,
+            0x2d7b5a855e16f30d,
+            0xe6bf284903294f12,
+            0x4be413d58a85fb9d,
+            0xb2159317daeb928d,
+            0x001d016b4849e3a2,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x3c01fe64d87ea8f3,
+            0xc37957c77acd452c,
+            0x27ae437241cbc46c,
+            0xc782d778eaf17a57,
+            0x3ffaedf846b562e1,
+            0x00341bcea98cfab8,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x9e341a79d8215ba6,
+            0xc25c1f7c15154779,
+            0xd77843b45a561f60,
+            0x7d417e2495afd484,
+            0x9abb5006c3e6ee36,
+            0x0184e7f7a261a8de,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x4e4e778fc6f8caea,
+            0x5277a48f9c8b4c60,
+            0xb47d17cd383e278a,
+            0x92610a35e8d5a6da,
+            0xd6e2ee70f8cf50a4,
+            0x00b310a2e9803b17,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x1654eb36b1cc28f0,
+            0xfa0c13a7daa7cb19,
+            0x95cef6bb99117da9,
+            0x18881c306c02680d,
+            0x378a995257934511,
+            0x003975d6674f13f2,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x9554c8751f6a0ae4,
+            0xb18bbfbcb2c32e7d,
+            0xe1f43665a575529b,
+            0xf111e10b68e949ae,
+            0x3c897f456ec963ac,
+            0x014120128777ee5e,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0xf7e9e87d50e222fa,
+            0x01e733aaef6a5124,
+            0x9614a19d4da05b74,
+            0xc9f83024305ff2a2,
+            0xffbbce502efa02c3,
+            0x0110a1e0a0444a7e,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x1976da5375793915,
+            0xad44d27a60b5e959,
+            0x4723a8899d064a04,
+            0x9141842390c1b3fc,
+            0xf5582a6c3f0f954c,
+            0x0132e5e38db00dbe,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x2154e0dd961fa965,
+            0x905123d2e416612a,
+            0xb55b734704c4f125,
+            0xda93e151e9da25a7,
+            0x4f324c5e3dc695a4,
+            0x002138523eeff129,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x0c4e0ee57fbaaa5a,
+            0x214dd04832a8d907,
+            0x2fd9e36f6014d23e,
+            0x232c4ba72b68f1fc,
+            0xdf9f4ef58fccc931,
+            0x0136b97a2745c86d,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x3eeb4a8d47d56314,
+            0xf3fd1fe467fc9be1,
+            0xc927b342beaedb82,
+            0x49a60595b374d49c,
+            0x1ba0f2afee916fd2,
+            0x006d443b2bf9212e,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0xdb64a1295e2b31b6,
+            0xcd8276f6964ae770,
+            0xbc70e74a5d0883fe,
+            0xecf6c1466a83a00c,
+            0x2e7fa34eaf0bdaae,
+            0x00bf9aefda31de02,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0xa3eacbfb3bb1eec0,
+            0x5fea62b415e88c51,
+            0x32a203a273105c7f,
+            0x30f10578c811afda,
+            0x58d228d876bd6e43,
+            0x00c2084adc78ee11,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x8dc3a2aa8879c5e5,
+            0x493ed0626d084b0e,
+            0x8cdd2d2880e1074f,
+            0xd13b38986b77e631,
+            0x8a4bd1c5d3c8dc2b,
+            0x00db8168a9c12bd8,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0xd5c29d4b641cf5a6,
+            0x5a17e9c339276978,
+            0xde16ba6e9de72982,
+            0xbff307b65ea7c8cc,
+            0xac4130eaf440c9f9,
+            0x00f8ec9635e05fde,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0xbd9b5ea16ff44297,
+            0xeb15245c82dc2111,
+            0x5888f2306970cb8a,
+            0x46eb58a3500ffd3e,
+            0x24b60149896014f8,
+            0x0055f38b065cc4b7,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x1dc26c6f3d171cc1,
+            0x6ad9905061602630,
+            0x9d9976185e053afb,
+            0x8acbb5eab4282a79,
+            0x756041bd951a90d7,
+            0x00dc14830c9f7590,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x02cdffffffffff68,
+            0x51409f837fffffb1,
+            0x9f7db3a98a7d3ff2,
+            0x7b4e97b76e7c6305,
+            0x4cf495bf803c84e8,
+            0x008d6661e2fdf49a,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x0000000000000000,
+            0x0000000000000000,
+            0x0000000000000000,
+            0x0000000000000000,
+            0x0000000000000000,
+            0x0000000000000000,
+        ]),
+    },
+];
+
+/// Coefficients of the numerator of the y-coordinate map, lowest degree first.
+/// The denominator is the cube of the kernel polynomial.
+const ISO_YNUM: [Fp2; 34] = [
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0xf8c6bf8c7787c327,
+            0xc29e43cdf5ec883b,
+            0x58393c730756583f,
+            0x13d61e9d45fa04c1,
+            0x904da9443960cd84,
+            0x0077c4b3db6f2cbd,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x630203ac13144525,
+            0x9a5f89985907b760,
+            0xf68efb1cf8cdc2b3,
+            0x56cc317c04e7e153,
+            0x46679437fe3fb58a,
+            0x01a24d6368e6d8b8,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x3a9a1e9f4e852729,
+            0xffc975aa63f174f8,
+            0x4a55ec7930472493,
+            0x3819ba8da1dcbb14,
+            0x38327d09b07fc897,
+            0x011f9430b06787a5,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x1e9bb7389d9e6249,
+            0x3869cf252be31020,
+            0x38330a6562045858,
+            0xde1a3abbcdb72b7d,
+            0xb8716b8ba7b5b90c,
+            0x00f901e21f5a8ee8,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x1a30fc113f141e1f,
+            0x631fef9260296414,
+            0x9b4d780fd0582e2a,
+            0x5f80c5685c75638c,
+            0xa824feb90d499353,
+            0x016e2f2b409460ca,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0xa76cb5f357e7cdc8,
+            0x0e669a0b8e2fc934,
+            0x4777be336c50055c,
+            0xfb4f993539875d6a,
+            0xdf343d19d200bfed,
+            0x0025d4b4cae0b1f1,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x0bd968538b7b7ecf,
+            0xa9125c7c317e2e80,
+            0x7041c02f4a29d609,
+            0x8e6a06e6d4889bda,
+            0xbb55f40927d8eb1b,
+            0x0002ab40bd81bec7,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0xc96e4c81a364b23d,
+            0x4d1128c683ef3fe5,
+            0xb059f58a001249f1,
+            0x542fd7256d3ba2fd,
+            0x72e5013c97b923a7,
+            0x000ca9cf0d750739,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x8b4462d55baa6c3b,
+            0x876e75c009d650a7,
+            0x25ce5dc5a585c52d,
+            0x2ad80e0c02ed7925,
+            0xcfe4a62b06e87a85,
+            0x007ae371458c26a3,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0xab1e5ac275121dc6,
+            0xb255a1938482dea5,
+            0x09369bd1a2e9b287,
+            0xe2210f3cc74281d1,
+            0xe360242bb985788a,
+            0x00837ce830153016,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x111af5bc6e096409,
+            0xc38851509323b083,
+            0x697b6159190febe3,
+            0xff95ec96d66df14d,
+            0x0db423b8c37c08d7,
+            0x0113bc7d654ec049,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0xae76373fc028229c,
+            0xc2201072e03484d2,
+            0x4eb807057348b321,
+            0x619c65131159df41,
+            0xe9cc24ee161ba71c,
+            0x009dfef3e472079e,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x124f0f2c8e18f966,
+            0x1614ae6f02564e98,
+            0x6c535653b348c01c,
+            0x4d4c4011d5f28d72,
+            0x4e35a4d80a17fdac,
+            0x010a1a15a95fe223,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0xf6c5d1955ac55d2f,
+            0x86c885f0736685af,
+            0xf451aa78c4f9e8a2,
+            0x3a51de636caa4962,
+            0x7f4abd22cb97689a,
+            0x01a685f444327308,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x1a4652c434936865,
+            0xba087091ed110e1e,
+            0x820372ee8419fd68,
+            0x7311fc72ca70c260,
+            0x624ae716a4653244,
+            0x015645b34f2ab69b,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0xf917290b4789edfa,
+            0x8cc7b8cf8c54fcd1,
+            0x47b6ce7203afd895,
+            0x38d2a3adced43317,
+            0xf8947da53f9cba4d,
+            0x00a254260a07d09c,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x36483abc1db8e1f0,
+            0xb72bb1765d880378,
+            0xb898ec69869f405d,
+            0xa0f693bac6708227,
+            0x56e1d8966c489ed1,
+            0x01161f17e0160df9,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x17ba46c8feda3c40,
+            0x5b5e5a0d508d1d4d,
+            0x1150cc28ae5cfbaa,
+            0xfcade0bd1566b1c6,
+            0xf8c968e23e2c3d38,
+            0x00b0822dc367f0dc,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0xb5ec4f83a9769478,
+            0xeb032c15c6469f21,
+            0xabdb2f788fd38a54,
+            0x323170206a8ec093,
+            0x0325eff5822c1e2c,
+            0x00e17f4cb58ee790,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x12e84714e3125216,
+            0x2ddba643a53f3f87,
+            0xf49d9392c0aef673,
+            0xa30a0ef32dd83d8d,
+            0xc463f2eb77540c40,
+            0x0124f56ba19ffedc,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x328ade1cb5814637,
+            0x7c563df3b760e67c,
+            0x476fa0292db18d6b,
+            0xe94debeac8c26bf6,
+            0x14001b8ee866664c,
+            0x00b8c8dd006f21c1,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x8f96efbeb1f9490c,
+            0x78093099a1392710,
+            0x7d90bc4ee6ed3ce7,
+            0x3be74c5f4dcf0756,
+            0x95ce388982cf76b2,
+            0x002e231226414fb8,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x50273b403c84e9ab,
+            0xedaca73768a23f33,
+            0xf7dd925485633f0c,
+            0x180035595411bb3f,
+            0x21cfd3586f976b0a,
+            0x010c65e023782877,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x30212658fb049ed6,
+            0x406c66443aa11834,
+            0x3ac1eb3ee2b34b23,
+            0xf2cb57dac2ec477e,
+            0x53d9cbd337de73c6,
+            0x00f08116403a483c,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x12632dca9515c952,
+            0xdcff22745cbe6ffd,
+            0x9e2a60882da56eba,
+            0x24c9fdabd0dc5b81,
+            0xdafb5e31826aa46a,
+            0x017d22e9d665add9,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x186de01dfccaa0e5,
+            0x99b5b2b9a57cd03f,
+            0x1cfa39e7171a8cab,
+            0xbec8b8c4070d6076,
+            0xd8e31b32d55b5b7a,
+            0x015fc4e80ac96b65,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x3cbae22b64f85132,
+            0xff600e37f199d897,
+            0xfa787c80b5e12979,
+            0x5e489126520f4430,
+            0xc6972c08e83f1ba1,
+            0x0012bcb9f8fb7562,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0xd15140afe11efb52,
+            0x5b28b3eb01f2c4e2,
+            0xe1700684452b53e7,
+            0xf6e45e82092d8e5a,
+            0x6b517b772af474d9,
+            0x003388113855c225,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x17b5e80a4331243f,
+            0xc1b5c51e89842e43,
+            0xd007ed8c588f366f,
+            0x6d6a74ce12620df5,
+            0xe2a85516fd36f02b,
+            0x011e1d1bc51021a1,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0xb5253fdaaff27138,
+            0xc2b50985914ee075,
+            0xab2745981a2e4b84,
+            0x8d1c04c21dedab77,
+            0x74744d0c289cf9e8,
+            0x00774e2534f099c3,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0xc532395ae25f6e0a,
+            0x117e0e7c076a4941,
+            0x4f86ea2789c65b9e,
+            0xefa009995c08a393,
+            0x1b6eb5cc85e3a4f3,
+            0x0088dc6f41a7d15c,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x44c512c2e9fa2874,
+            0xa0b55c0a209ce235,
+            0xbfa7b39dba559394,
+            0xd1ce4c0f46108d9a,
+            0x4f3d5c1661285560,
+            0x015b6dc224fc2c71,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x6f6d1dcddac9e2c7,
+            0x819dc2a39047d592,
+            0x1f5521aa64ea1263,
+            0xc017d565b0db4124,
+            0xa465d1c468ab7301,
+            0x002706e15ddafb39,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0xd20ef333d5110bbc,
+            0x7831fae5b2f3852b,
+            0xb00f26cb9cb675f3,
+            0xec047723b70b109e,
+            0x54019417fc138b64,
+            0x012c3b6600d65c42,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x048b8545b963e9d0,
+            0x49a80586b1d1061c,
+            0xf0a1c31586483dee,
+            0xad3bae51aa0f53ae,
+            0x888ed5b26e839ef8,
+            0x00d8ffb3c71ddfcc,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x2520623ce06f33e4,
+            0xd240806563c60560,
+            0x6fa4256475c80827,
+            0xf749de0174c8fcda,
+            0x715a93d0ec7ae605,
+            0x00b47c561b043d1f,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x590c9c6ad571f770,
+            0x9a8ef739bd34e9cb,
+            0xa941bac3bd17f06c,
+            0x8e6736417dcc5d12,
+            0xe43164e07df03acc,
+            0x01498f26432d9645,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x60b49b77deb45b68,
+            0x5bf23d9c5d888e38,
+            0x571e9ca0ad27856b,
+            0x4c1c830abb028fea,
+            0xe18913e397dceff2,
+            0x00ddb3eeda5e7ba8,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x625d08390de86dc1,
+            0x231c1fae3e84163d,
+            0x282bd706430c6f64,
+            0xf2ddeb18f50a2f25,
+            0xb16f0779ffc1cb82,
+            0x00edee1a4dd5143d,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0xbda15d56028deb36,
+            0xd75fe3f1840006bd,
+            0x602da22a7a73a128,
+            0x45e0d57406b677ee,
+            0xd1d022aaf9e9373b,
+            0x010ec84959d92dc4,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x20be8046516d37f7,
+            0xd1ea5dfa25c0a7e0,
+            0x795e279582af9cea,
+            0xe180a5f8f6130fe9,
+            0x51b59820acc7877a,
+            0x0022a948b97cb433,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x45b0ef01ab785bf2,
+            0xb4beb1bf8544f39b,
+            0x6e9456b7f4f1b9e2,
+            0xad301a0c474e8486,
+            0x017806cf5c04ef6c,
+            0x009334c02be9b146,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x643c5dea862356e7,
+            0xa539a0ec911dd6ca,
+            0xece5bb2f51b35fa4,
+            0x7cd1a75bd649b1c1,
+            0x61c679d724ab26c6,
+            0x01496f36fe8d70d9,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0xf6a6cf3d6b6fb79e,
+            0x60b63663aa109535,
+            0x082cd352704b7d81,
+            0xa52a9c6ed65ed5e5,
+            0xf2fa4b94bf631083,
+            0x01779de21c9e8340,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x653f93733cd9eb26,
+            0x13b3f632db6b13ca,
+            0xdcf35167c7e76c70,
+            0x835a64bc31ea6e0b,
+            0x3b0960853b0dfbb3,
+            0x00f5a65d300f2711,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x64b112b299aa5b1d,
+            0xb6b1d370d2f39775,
+            0x36740de97fbcfa4b,
+            0xd301a30b22985a6c,
+            0xbbe8d7e01d016184,
+            0x007b34e4fdec12cb,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0xf93be04300af81cb,
+            0xae8aa75802d7719d,
+            0x7652896c80d25d23,
+            0x4e2527322c33c571,
+            0x93791b18bacae8be,
+            0x00fa2f90b4b5fbc6,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0xe10f8481656dfd6d,
+            0xa91278bb5dfd3308,
+            0x842a78cfe90c6b47,
+            0xbc2ba0688a13756d,
+            0xc5f54a2dd66e4822,
+            0x013cbe2dadbc8a06,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x75f7ee686892aa52,
+            0xbaf37b17aca89de5,
+            0x058abf72d6fa7080,
+            0xd4a1e64bfebaab65,
+            0x1816d3d3240aacf9,
+            0x0121d87c012b204a,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x5b02a1f0414d63ce,
+            0x8fe5161188d06901,
+            0x1ec4ae617e671410,
+            0x43e046efe20dd451,
+            0xf174bcaef9b8a543,
+            0x0082b4908b9a6838,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x0af82579a4a482e7,
+            0x0e1f03a3528153a3,
+            0x70a65f2ea57e8043,
+            0x43de6269e2b70c91,
+            0x5488375bda7e35c9,
+            0x0079a53512faee41,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0xbda5e243efeb0a21,
+            0x6018dbb945becb27,
+            0x79c17f67ed559915,
+            0x3cdd68361d6d5c75,
+            0xccb684fb4dca0d5a,
+            0x012b593332bf94e4,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x578d29076c1b56bc,
+            0xc9a2b69d87a4c01e,
+            0x37698120e1d166a0,
+            0xde4be745cf2bcd0e,
+            0xcf5dfc3d61454c6d,
+            0x006d25631b3aab26,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x0d20778afc29d6a9,
+            0x8e8e1512e032eadf,
+            0x13fd75283962bfa8,
+            0x2685247c3306e84f,
+            0xa7eac4b2ad87b905,
+            0x00372af306f3890f,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0xedd56e86b5b365b3,
+            0xc856f818136b9553,
+            0x9808013240eeea9c,
+            0xb99196e7c232024a,
+            0xcb363362d3a902d6,
+            0x000207f33d7de53a,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x44ed90aa3a73bc3e,
+            0xfc55e19bd7eb6908,
+            0x8c0fe30feb9d687a,
+            0x9dc100b7994d5576,
+            0x66f162839dc699b7,
+            0x014f44ef5d0e1b03,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x4f3db8b0c60624cd,
+            0x90174d1e2db946de,
+            0x2fee834c7b37666b,
+            0xdfe53d96c1bbf35e,
+            0x72ca54f1ff127e36,
+            0x0021e4531a24befd,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x6d5fb9c2ecd50d91,
+            0x9a338041b10d5c51,
+            0x943e841199d6e91f,
+            0xb353a14218c2a968,
+            0xcba7edc4b18472ec,
+            0x002a733239e04d71,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x110d15ce7eabcf3b,
+            0xc708ea874a70c0c6,
+            0xc5d503a3787f7100,
+            0x03390a501d421bce,
+            0xb45cd4ac392a665b,
+            0x004e9898b227f8e8,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x701e2d89669adba3,
+            0x380c66f7b15ced91,
+            0x7b057467c162d049,
+            0xa64e3ddf99dc04db,
+            0x234398916e9ac744,
+            0x006479b9e0d92c33,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0xbaf84b52e0bb5506,
+            0x073bc8f9bd933708,
+            0x4085ae2ce7268116,
+            0x5070d978e7db5f83,
+            0x788750c02945294f,
+            0x00b54e61c4abb28b,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x55b790c61693a378,
+            0x8a3f8233e6ea20c6,
+            0xe77f2f65e48e13c6,
+            0x49aa0a58622ee816,
+            0xf2b40c8a6d752cdc,
+            0x00a88bb3f44cace9,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0xfd7118772d05ad41,
+            0x23863315ab05c7d9,
+            0x5c856c13fd19a9a9,
+            0xe0539655cc858ebe,
+            0xda8c748200ee9469,
+            0x0147b5bd60425819,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0xd4317274bd608128,
+            0xf23b6ced48acec3a,
+            0x21e626bc78235063,
+            0xfb98201b07ffe07e,
+            0x3a1e67835364485c,
+            0x004f28a7859d2de5,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0xb463401fbb3ba71c,
+            0x4fd440e06d950f54,
+            0x5db6d17409ac3b71,
+            0x2bf244168458d99d,
+            0x89cc619d2b099499,
+            0x006865c259674fa2,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x0b83b84f2b494cab,
+            0x150ddf8e64b26bc3,
+            0xf9684e71905ab220,
+            0xd29a4e6660911268,
+            0x6e8af189367c99fd,
+            0x01770bfb6ed10489,
+        ]),
+    },
+    Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x87c1eb28cc2d5d06,
+            0xaf272a654db333bd,
+            0xa5c26b8ccd0f705f,
+            0x13cb243479b534dd,
+            0x1d5e0010366eabcd,
+            0x01662ff0eb1f0e26,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x0000000000000000,
+            0x0000000000000000,
+            0x0000000000000000,
+            0x0000000000000000,
+            0x0000000000000000,
+            0x0000000000000000,
+        ]),
+    },
+];
+
+impl HashToField for Fp2 {
+    // ceil(log2(p)) = 377, m = 2, k = 128, so m * L = 2 * 64 = 128.
+    type InputLength = U128;
+
+    fn from_okm(okm: &GenericArray<u8, U128>) -> Fp2 {
+        let mut bytes = [0u8; 64];
+        bytes.copy_from_slice(&okm[..64]);
+        let c0 = Fp::from_bytes_wide(&bytes);
+        bytes.copy_from_slice(&okm[64..]);
+        let c1 = Fp::from_bytes_wide(&bytes);
+        Fp2 { c0, c1 }
+    }
+}
+
+impl Sgn0 for Fp2 {
+    fn sgn0(&self) -> Choice {
+        let sign_0 = self.c0.sgn0();
+        let zero_0 = self.c0.is_zero();
+        let sign_1 = self.c1.sgn0();
+        sign_0 | (zero_0 & sign_1)
+    }
+}
+
+/// Computes `v^(2^k)`.
+fn square_n(v: &Fp2, k: u32) -> Fp2 {
+    let mut v = *v;
+    for _ in 0..k {
+        v = v.square();
+    }
+    v
+}
+
+/// Returns `(true, sqrt(u / v))` if `u / v` is square, and otherwise
+/// `(false, sqrt(Z * u / v))`, in constant time. This is the `sqrt_ratio`
+/// function of RFC 9380, appendix F.2.1.1, with c1 = 47.
+///
+/// `v` must not be zero.
+fn sqrt_ratio(u: &Fp2, v: &Fp2) -> (Choice, Fp2) {
+    let c1 = SQRT_RATIO_C1;
+
+    // The exponents below are public, so `pow_vartime_extended` runs in
+    // constant time with respect to the field elements.
+    let mut tv1 = SQRT_RATIO_C6;
+    // v^c4, where c4 = 2^c1 - 1
+    let mut tv2 = *v;
+    for _ in 1..c1 {
+        tv2 = tv2.square() * v;
+    }
+    let mut tv3 = tv2.square() * v;
+    let mut tv5 = (u * tv3).pow_vartime_extended(&SQRT_RATIO_C3) * tv2;
+    tv2 = tv5 * v;
+    tv3 = tv5 * u;
+    let mut tv4 = tv3 * tv2;
+    // tv4^c5, where c5 = 2^(c1 - 1)
+    tv5 = square_n(&tv4, c1 - 1);
+    let is_qr = tv5.ct_eq(&Fp2::one());
+    tv2 = tv3 * SQRT_RATIO_C7;
+    tv5 = tv4 * tv1;
+    tv3 = Fp2::conditional_select(&tv2, &tv3, is_qr);
+    tv4 = Fp2::conditional_select(&tv5, &tv4, is_qr);
+
+    for i in (2..=c1).rev() {
+        tv5 = square_n(&tv4, i - 2);
+        let e1 = tv5.ct_eq(&Fp2::one());
+        tv2 = tv3 * tv1;
+        tv1 = tv1.square();
+        tv5 = tv4 * tv1;
+        tv3 = Fp2::conditional_select(&tv2, &tv3, e1);
+        tv4 = Fp2::conditional_select(&tv5, &tv4, e1);
+    }
+
+    (is_qr, tv3)
+}
+
+/// Maps a field element to a point on E', returning `(xn, xd, y)` such that
+/// the point is `(xn / xd, y)`. This is the straight-line simplified SWU map of
+/// RFC 9380, appendix F.2, without the final inversion.
+fn map_to_curve_simple_swu(u: &Fp2) -> (Fp2, Fp2, Fp2) {
+    let tv1 = SSWU_Z * u.square();
+    let mut tv2 = tv1.square() + tv1;
+    let tv3 = SSWU_ELLP_B * (tv2 + Fp2::one());
+    let tv4 = SSWU_ELLP_A * Fp2::conditional_select(&-tv2, &SSWU_Z, tv2.is_zero());
+
+    tv2 = tv3.square();
+    let mut tv6 = tv4.square();
+    tv2 = (tv2 + SSWU_ELLP_A * tv6) * tv3;
+    tv6 *= tv4;
+    tv2 += SSWU_ELLP_B * tv6;
+
+    let (is_gx1_square, y1) = sqrt_ratio(&tv2, &tv6);
+
+    let x = Fp2::conditional_select(&(tv1 * tv3), &tv3, is_gx1_square);
+    let mut y = Fp2::conditional_select(&(tv1 * u * y1), &y1, is_gx1_square);
+    y.conditional_negate(!u.sgn0().ct_eq(&y.sgn0()));
+
+    (x, tv4, y)
+}
+
+/// Evaluates the polynomial with coefficients `coeffs` at `xn / xd`, scaled by
+/// `xd^n` where `n` is its degree. `xd_pow[i]` must hold `xd^i`.
+fn eval_homogeneous(coeffs: &[Fp2], xn: &Fp2, xd_pow: &[Fp2]) -> Fp2 {
+    let n = coeffs.len() - 1;
+    let mut acc = coeffs[n];
+    for i in (0..n).rev() {
+        acc = acc * xn + coeffs[i] * xd_pow[n - i];
+    }
+    acc
+}
+
+/// Maps a point `(xn / xd, y)` of E' to the curve E.
+///
+/// With D the kernel polynomial, the isogeny is
+/// (x, y) -> (XNUM(x) / D(x)^2, y * YNUM(x) / D(x)^3).
+/// The result is written in Jacobian coordinates to avoid inversions, and the
+/// kernel of the isogeny maps to the identity.
+fn iso_map(xn: &Fp2, xd: &Fp2, y: &Fp2) -> G2Projective {
+    let mut xd_pow = [Fp2::one(); ISO_YNUM.len()];
+    for i in 1..xd_pow.len() {
+        xd_pow[i] = xd_pow[i - 1] * xd;
+    }
+
+    // Homogenized, the image is (x_num / (xd * d^2), y * y_num / d^3), which
+    // has Jacobian coordinates (x_num * xd, y * y_num * xd^3, xd * d).
+    let x_num = eval_homogeneous(&ISO_XNUM, xn, &xd_pow);
+    let d = eval_homogeneous(&ISO_XDEN, xn, &xd_pow);
+    let y_num = eval_homogeneous(&ISO_YNUM, xn, &xd_pow);
+
+    G2Projective {
+        x: x_num * xd,
+        y: y * y_num * xd_pow[3],
+        z: xd * d,
+    }
+}
+
+impl MapToCurve for G2Projective {
+    type Field = Fp2;
+
+    fn map_to_curve(u: &Fp2) -> G2Projective {
+        let (xn, xd, y) = map_to_curve_simple_swu(u);
+        iso_map(&xn, &xd, &y)
+    }
+
+    fn clear_h(&self) -> G2Projective {
+        self.clear_cofactor()
+    }
+}
+
+#[cfg(test)]
+fn fp2_from_hex(c0: &str, c1: &str) -> Fp2 {
+    let mut bytes = [0u8; 48];
+    bytes.copy_from_slice(&hex::decode(c0).unwrap());
+    let c0 = Fp::from_bytes(&bytes).unwrap();
+    bytes.copy_from_slice(&hex::decode(c1).unwrap());
+    let c1 = Fp::from_bytes(&bytes).unwrap();
+    Fp2 { c0, c1 }
+}
+
+#[test]
+fn test_hash_to_field_fp2() {
+    use super::ExpandMsgXmd;
+
+    const DST: &[u8] = b"QUUX-V01-CS02-with-BLS12377_XMD:SHA-256_HASH_TO_FIELD_";
+    let vectors: [(&[u8], [[&str; 2]; 2]); 2] = [
+        (
+            b"",
+            [
+                [
+                    "00cc36c28692dc0c50808d65256970114c288813c170906160d0cb6bbd02f396399b04d6b9ad1380adf2b0c653610b25",
+                    "002f86fa1c33d520820f37e5301273f683ba507bc8046e384e0582d0b6968815e3d6b5d9f607c4e8aa237a90dee56e10",
+                ],
+                [
+                    "00d385ab11295f87d055bb890024293ea57d8b320f5061d9478392edf8c0b63023fb1c7704b4639cb543896cd4fff2b2",
+                    "019ab5de9e924561b647ec1f1efebdf45837a584f4f118573f00ade64ca1f32c5eb6d630a77baeee2b1d5ec1c2e28190",
+                ],
+            ],
+        ),
+        (
+            b"abc",
+            [
+                [
+                    "01a626195ccc43a6c94274931ab04730978d247840e88b7946b95586c237eaa616b0430834271fc9301c20562bd0288d",
+                    "0100a2fd9f78cc51148a1c6755ecea6cc78d6c089f8a6cd62623af84efc8428d9283a5c91552e66214015da89be8b943",
+                ],
+                [
+                    "01004c80d6b3582bb0ec112c6a73f9d19bbbf7826915acbe39f4a8a2d3cc7d828127d0fd273cb339d3587474bf96a59a",
+                    "017923b7d3636a781cfef181c8bfb559764eb251dfb0899a775187c5674357447d7c495080f71d393adecebb0f8a7c7d",
+                ],
+            ],
+        ),
+    ];
+    for &(msg, expected) in vectors.iter() {
+        let mut u = [Fp2::zero(); 2];
+        Fp2::hash_to_field::<ExpandMsgXmd<sha2::Sha256>>(msg, DST, &mut u);
+        assert_eq!(u[0], fp2_from_hex(expected[0][0], expected[0][1]));
+        assert_eq!(u[1], fp2_from_hex(expected[1][0], expected[1][1]));
+    }
+}
+
+#[test]
+fn test_sqrt_ratio() {
+    let mut u = Fp2::one();
+    let v = SSWU_ELLP_B;
+    for _ in 0..10 {
+        let (is_qr, root) = sqrt_ratio(&u, &v);
+        let ratio = u * v.invert().unwrap();
+        match ratio.sqrt_vartime() {
+            Some(_) => {
+                assert!(bool::from(is_qr));
+                assert_eq!(root.square(), ratio);
+            }
+            None => {
+                assert!(!bool::from(is_qr));
+                assert_eq!(root.square(), SSWU_Z * ratio);
+            }
+        }
+        u += SSWU_Z;
+    }
+}
+
+#[test]
+fn test_map_to_curve() {
+    use crate::G2Affine;
+
+    // Exercise both branches of the map and the exceptional case u = 0.
+    let mut u = Fp2::zero();
+    for _ in 0..4 {
+        let p = G2Projective::map_to_curve(&u);
+        assert!(bool::from(p.is_on_curve()));
+        let p = G2Affine::from(p.clear_h());
+        assert!(bool::from(p.is_on_curve()));
+        assert!(bool::from(p.is_torsion_free()));
+        u += SSWU_Z;
+    }
+}
+
+#[test]
+fn test_hash_to_curve_g2() {
+    use super::{ExpandMsgXmd, HashToCurve};
+    use crate::G2Affine;
+
+    fn check<F: Fn(&[u8]) -> G2Projective>(f: F, vectors: &[(&[u8], &str)]) {
+        for &(msg, expected) in vectors {
+            let p = G2Affine::from(f(msg));
+            assert!(bool::from(p.is_on_curve()));
+            assert!(bool::from(p.is_torsion_free()));
+            assert_eq!(hex::encode(&p.to_uncompressed()[..]), expected);
+        }
+    }
+
+    let msg_q128 = [b"q128_".as_ref(), &[b'q'; 128][..]].concat();
+    let msg_a512 = [b"a512_".as_ref(), &[b'a'; 512][..]].concat();
+
+    const RO_DST: &[u8] = b"QUUX-V01-CS02-with-BLS12377G2_XMD:SHA-256_SSWU_RO_";
+    check(
+        |msg| <G2Projective as HashToCurve<ExpandMsgXmd<sha2::Sha256>>>::hash_to_curve(msg, RO_DST),
+        &[
+            (b"", "0075ebc54568ba9d695d04596fa2a720c9b0b5fe4f0e9948a7836f3d5762fadb4eb5e76ceef1ce8e41697f125b89951b013aa0de7e154e4f26aaad8b0a4652270f5c4246d8b11fc056fc9eb38c2c49e633fe22bae319d97863966fa19d5765770106e2407a8cb71218d67be153ded5931f627695d5cf495ab55f38acd2d18c70fb9e4084b8356dba6f2faf8d25fbe40e01214de35c082739b86f26857f1cc305dfc0c0fe58c3d8430dee9f5aa3984a5504d8de827af268c472b52cc5f23fea06"),
+            (b"abc", "00497ee380fa2a574b49b97a7e9a520fd38b8e3167703270413c6d7abbacb07cc4355deb7a54a6f2922e149600ee8f48018e6d6b4fd85803fd02ab7a498104d8b355c834feacd07e40355368a2135bfe308c37a2a6e571e2fb944408e24bc26400b063db94c3d92469691d9942134de5d77513d76e4ada11163f6218f07622bdc077fc7ca429e9e440a1d7c0ba73a51d00961314a3fe9f10eb0bd0db4b35f5054735b5d6b001fefdaf2613da27c912ab84ce451bc78c6e0f24bd3d1ff1c5e15f"),
+            (b"abcdef0123456789", "009c11732f7af99634182f54bc69c23250f5713d1f2995d3fdfd87b154e69e53ffe910ccdb0f3e2ad7b4c743b41f86d100ba2e58d5f18cb84f9046c1da3699be21a45e10d4243919fb330d82748914b60174743933c30f3351e57c937b92b59a01417165001529e50b708ed4617c73d67c56a4b85696ed71be476f9d543ce9971b0c4a4b29ba050e7a9655e7f0f9432900a6a8caebc2205afa79e46c8bbe20252ce47c5865590187058d0ca88179ed461618e7cbcd46474c2c51fdd30f2afd93"),
+            (&msg_q128, "012c2641887e707c28ed86e7d0d253893438c4e1cced777a5b569f6362bd940696a40da1e51c794970e06fc3f840fa37008f4af53223f727ce931ced667636d915deb981f614cd86c66769a7fe901abbc81d2eae3cb40cce5ef951464888cee500d719b8b454a8330c1d2299726f085b537b23857218fd68e9337c7cfefba5228fb6df23b7778088023f8f427ac93a5201889ab7059b7f13c20320fb453c4fe36e5b612ea9f7ca127f8b3c1ab6a37ff8276fa62f645f7c214bb575a2647f316f"),
+            (&msg_a512, "00bf761a4553e2f758564ec5d2df45c1238a2028d91561b6b1dab696cd4571024fc6adfc2fdf4073de7476882c388c190176aa0420e20473b01c569b368120a74df4d7d6aba3a851742991e0d88160b262bd51595d00ae244dfa7d92c6573f21003daeb7729ff6c58cdacf52a305627d229603f22ef4989870de2c6ceec109b076d528ff2c0d6b9889d7fecdb8eea1d3001c23c460ddd204c76a6908fe7de903034208bac292a22ef47bac4d2809690a487a4dd4773871e8430813e1ad995163"),
+        ],
+    );
+
+    const NU_DST: &[u8] = b"QUUX-V01-CS02-with-BLS12377G2_XMD:SHA-256_SSWU_NU_";
+    check(
+        |msg| <G2Projective as HashToCurve<ExpandMsgXmd<sha2::Sha256>>>::encode_to_curve(msg, NU_DST),
+        &[
+            (b"", "0104d969e14e52be47d194699a69351dec3510ee343c5951d9f9d1ab9273a1bf5adcad37e1eb3fdb656080dd76e45f2700c63fa3752fd976f0b868ad8bac8a5b4603ce00775922e0af6bc509dfc38f8bdf2b8500d1f25ff96567c77ed7b866a10117dbe3ed01cfcceae15c6d401c075319240c1d9b0509560a1e1e91d0c1494f63f0da39dc5d40c6502a7e6f173f15be00d84fe32b71e46cef4d15611da9fdb92d9bb472e68a58973f9463b8ccc517eb60f56144055e8ea062c5ceb1777944eb"),
+            (b"abc", "005b1069ebcaf06ad44a8aef80ec873c05ebc6019af0157d6f18de65e3aa80404010c246fb9c777164fb5eba29a3e5f20076ba7fe95800ff0080de1c793af7d07f5ebeacf1ec0201e88668393026784e88c680565585fc5ffd6e3cbb66b1680b01807fc38a5c14ccc5137d81bdcd57192132f8ac9ee696a87a660edbd94a444576b7e79738e07f604342682d9359001900019f550316fa7c5afa06c6b07e835bb4ff258c827613405a7a9ffe75a04e91185c214a90c9013fa524b11fb3f4a0e3"),
+            (b"abcdef0123456789", "0009157a7705ecb57c25d26f7e73ea9a0d2c3af829538f5e2265b7c239f8a427b6a1cd62b5441bbf03825c572c28ea1d005747e0750abc3b05b66fe12afd24d13b99c02caadf92dfe582a0103e9344f2cbb95453fb3b3fd50bbef5186861dfc10048a14945bbf8ba165cf834bc864c40cafc33df823b3992ea484d630b40967e62b4d475c764ea148f93d68d76f9db3f01077d533cf92dca05e0c5546761dcdb368bb13b6e79b1f56dfa4600f2010a789c373071c35ceca1f7ca4b3de75a5e6f"),
+            (&msg_q128, "0157fcd97cae9871034bee4572b00607c08f55bf5795c7bf23686c374dca4873b6b1e30a22be2ae74d40d59532fd1946012a7cd16b8cd029f0703aca3807d0f09657edfd72d4bab8fc752d0497c785768b486b91c2203951001426c14dcdea56007de068430b42cb211e46d8dbc6515d1a246f2b651d765715a004642a1dc8277486b47ce51a13da3b3eef1dda7c899f018acb9a3a3bfe086eaa215442c055ba9c9276ea45226a8b92bc9f6a1303b319fedddd69cb47ebf2c3c0829cd5627867"),
+            (&msg_a512, "014edbe3dc318c36adf5b8e92153b93ad520a332109bd751ca1b0ed0e94387cb376f21482de2404e05408445f2c5b090016166dbebd336818ec25436fa3c4fabb8d6c2510aaa73f4d84293e5c04e2b2865007b81191e63e75f73fdbd943775e3019a883e425717cd06bc2f109baff1b9f4206f2666c947e9d2f952dbcee95479a76692980e218bfdd3e7d34951597c3100b10e9c5d6051f265368a22a63d1f9a4f3bca9e0e2a096adc9d3ef3b6c9b69273aabbad0133339efb096c895862a091"),
+        ],
+    );
+}
@@ -0,0 +1,58 @@
+//! This module implements hashing to the scalar field of BLS12-377.
+
+use digest::generic_array::{typenum::U48, GenericArray};
+
+use super::HashToField;
+use crate::scalar::Scalar;
+
+impl HashToField for Scalar {
+    // ceil(log2(q)) = 253, m = 1, k = 128, so L = ceil((253 + 128) / 8) = 48.
+    type InputLength = U48;
+
+    fn from_okm(okm: &GenericArray<u8, U48>) -> Scalar {
+        // The expanded message is big-endian, while `from_bytes_wide` expects
+        // a little-endian integer.
+        let mut bytes = [0u8; 64];
+        for (dst, src) in bytes.iter_mut().zip(okm.iter().rev()) {
+            *dst = *src;
+        }
+        Scalar::from_bytes_wide(&bytes)
+    }
+}
+
+#[cfg(test)]
+fn scalar_from_hex(s: &str) -> Scalar {
+    let mut bytes = [0u8; 32];
+    bytes.copy_from_slice(&hex::decode(s).unwrap());
+    bytes.reverse();
+    Scalar::from_bytes(&bytes).unwrap()
+}
+
+#[test]
+fn test_hash_to_field_scalar() {
+    use super::ExpandMsgXmd;
+
+    const DST: &[u8] = b"QUUX-V01-CS02-with-BLS12377_XMD:SHA-256_HASH_TO_FIELD_";
+    let vectors: [(&[u8], [&str; 2]); 2] = [
+        (
+            b"",
+            [
+                "09bac921bbeb3a8ed0ed41faf2e32b7df3336c5085397b12e55cecbc46088f29",
+                "03a35201c1ca8e43091061bc3357db13deff255c00d8571c4641118ecbb7eaae",
+            ],
+        ),
+        (
+            b"abc",
+            [
+                "08abd56bfe21106e190d507d0923f7958a0eb29aa2e8652a56749eef1082ec65",
+                "0b4976c59c3cfbe08e61274c1a233327104f7c971ba6ec9d9c60f98ba1f19e90",
+            ],
+        ),
+    ];
+    for &(msg, expected) in vectors.iter() {
+        let mut u = [Scalar::zero(); 2];
+        Scalar::hash_to_field::<ExpandMsgXmd<sha2::Sha256>>(msg, DST, &mut u);
+        assert_eq!(u[0], scalar_from_hex(expected[0]));
+        assert_eq!(u[1], scalar_from_hex(expected[1]));
+    }
+}
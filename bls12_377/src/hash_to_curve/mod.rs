@@ -0,0 +1,116 @@
+//! This module implements hashing to the fields and groups of BLS12-377 as
+//! described in the hash-to-curve specification,
+//! [RFC 9380](https://www.rfc-editor.org/rfc/rfc9380.html).
+//!
+//! Nothing in this module allocates, so it is usable on embedded targets.
+
+use core::ops::Add;
+
+use digest::generic_array::{typenum::Unsigned, ArrayLength, GenericArray};
+use subtle::Choice;
+
+mod expand_msg;
+pub use self::expand_msg::{
+    ExpandMessage, ExpandMessageState, ExpandMsgXmd, ExpandMsgXmdState, ExpandMsgXof,
+    ExpandMsgXofState,
+};
+
+mod map_g1;
+mod map_g2;
+mod map_scalar;
+
+/// The suite identifier for hashing to $\mathbb{G}_1$ with `expand_message_xmd`
+/// using SHA-256 and the simplified SWU map. Applications append this to their
+/// own tag to form the domain separation tag passed to `hash_to_curve`.
+pub const SUITE_G1_XMD_SHA256_SSWU_RO: &[u8] = b"BLS12377G1_XMD:SHA-256_SSWU_RO_";
+
+/// The suite identifier for encoding to $\mathbb{G}_1$ with `expand_message_xmd`
+/// using SHA-256 and the simplified SWU map, for use with `encode_to_curve`.
+pub const SUITE_G1_XMD_SHA256_SSWU_NU: &[u8] = b"BLS12377G1_XMD:SHA-256_SSWU_NU_";
+
+/// The suite identifier for hashing to $\mathbb{G}_2$ with `expand_message_xmd`
+/// using SHA-256 and the simplified SWU map, for use with `hash_to_curve`.
+pub const SUITE_G2_XMD_SHA256_SSWU_RO: &[u8] = b"BLS12377G2_XMD:SHA-256_SSWU_RO_";
+
+/// The suite identifier for encoding to $\mathbb{G}_2$ with `expand_message_xmd`
+/// using SHA-256 and the simplified SWU map, for use with `encode_to_curve`.
+pub const SUITE_G2_XMD_SHA256_SSWU_NU: &[u8] = b"BLS12377G2_XMD:SHA-256_SSWU_NU_";
+
+/// Enables a field element to be hashed from a byte string, as described by
+/// the `hash_to_field` procedure of RFC 9380, section 5.2.
+pub trait HashToField: Sized {
+    /// The number of bytes of expanded message, `m * L`, used to produce
+    /// each field element.
+    type InputLength: ArrayLength<u8>;
+
+    /// Interprets `m * L` bytes of uniform output as a field element,
+    /// reducing each of the `m` big-endian integers modulo the characteristic.
+    fn from_okm(okm: &GenericArray<u8, Self::InputLength>) -> Self;
+
+    /// Hashes a byte string of arbitrary length into one or more elements of
+    /// `Self`, using `X` to expand the message and `dst` to separate domains.
+    ///
+    /// This function panics if the requested output length exceeds what the
+    /// message expansion method `X` is able to produce.
+    fn hash_to_field<X: ExpandMessage>(message: &[u8], dst: &[u8], output: &mut [Self]) {
+        let len_per_elm = Self::InputLength::to_usize();
+        let len_in_bytes = output.len() * len_per_elm;
+        let mut expander = X::init_expand(message, dst, len_in_bytes);
+
+        let mut buf = GenericArray::<u8, Self::InputLength>::default();
+        for item in output.iter_mut() {
+            expander.read_into(&mut buf[..]);
+            *item = Self::from_okm(&buf);
+        }
+    }
+}
+
+/// Returns the "sign" of a field element, as defined by the `sgn0` function of
+/// RFC 9380, section 4.1.
+pub(crate) trait Sgn0 {
+    fn sgn0(&self) -> Choice;
+}
+
+/// Enables a curve to be used as the target of a mapping from its base field,
+/// as described in RFC 9380, section 6.
+pub trait MapToCurve: Sized {
+    /// The field element type the curve is mapped from.
+    type Field: Copy + Default + HashToField;
+
+    /// Maps an element of the field to a point on the curve. The resulting
+    /// point is not necessarily in the prime order subgroup.
+    fn map_to_curve(elt: &Self::Field) -> Self;
+
+    /// Clears the cofactor, sending a point on the curve to the prime order
+    /// subgroup.
+    fn clear_h(&self) -> Self;
+}
+
+/// Implements the hash-to-curve and encode-to-curve procedures of RFC 9380,
+/// section 3, for any curve supporting `MapToCurve`.
+pub trait HashToCurve<X: ExpandMessage>: MapToCurve + for<'a> Add<&'a Self, Output = Self> {
+    /// Hashes a byte string to a point in the prime order subgroup. The
+    /// output is indistinguishable from a random oracle.
+    fn hash_to_curve(message: &[u8], dst: &[u8]) -> Self {
+        let mut u = [Self::Field::default(); 2];
+        Self::Field::hash_to_field::<X>(message, dst, &mut u);
+        let p1 = Self::map_to_curve(&u[0]);
+        let p2 = Self::map_to_curve(&u[1]);
+        (p1 + &p2).clear_h()
+    }
+
+    /// Encodes a byte string to a point in the prime order subgroup. This is
+    /// cheaper than `hash_to_curve`, but the output distribution is not uniform.
+    fn encode_to_curve(message: &[u8], dst: &[u8]) -> Self {
+        let mut u = [Self::Field::default(); 1];
+        Self::Field::hash_to_field::<X>(message, dst, &mut u);
+        Self::map_to_curve(&u[0]).clear_h()
+    }
+}
+
+impl<G, X> HashToCurve<X> for G
+where
+    G: MapToCurve + for<'a> Add<&'a Self, Output = Self>,
+    X: ExpandMessage,
+{
+}
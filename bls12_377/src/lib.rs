@@ -19,7 +19,7 @@
 // involve various binary operators, and so this lint is triggered unnecessarily.
 #![allow(clippy::suspicious_arithmetic_impl)]
 
-#[cfg(test)]
+#[cfg(any(test, feature = "fault_injection"))]
 #[macro_use]
 extern crate std;
 
@@ -33,17 +33,41 @@ pub use scalar::Scalar;
 #[cfg(feature = "groups")]
 pub mod fp;
 #[cfg(feature = "groups")]
-mod fp2;
+pub mod fp2;
+#[cfg(feature = "groups")]
+mod blinding;
 #[cfg(feature = "groups")]
 mod g1;
 #[cfg(feature = "groups")]
 mod g2;
 
+#[cfg(feature = "groups")]
+pub use blinding::Blinding;
 #[cfg(feature = "groups")]
 pub use g1::{G1Affine, G1Projective};
 #[cfg(feature = "groups")]
 pub use g2::{G2Affine, G2Projective};
 
+#[cfg(feature = "pairings")]
+pub mod fp12;
+#[cfg(feature = "pairings")]
+pub mod fp6;
+#[cfg(feature = "pairings")]
+mod pairings;
+
+#[cfg(feature = "pairings")]
+pub use pairings::{multi_miller_loop, pairing, G2Prepared, Gt, MillerLoopResult};
+
+/// The BLS parameter x for BLS12-377 is 0x8508c00000000001.
+#[cfg(feature = "pairings")]
+const BLS_X: u64 = 0x8508_c000_0000_0001;
+
+#[cfg(feature = "hash_to_curve")]
+pub mod hash_to_curve;
+
+#[cfg(feature = "serde")]
+mod serde_impl;
+
 // TODO: This should be upstreamed to subtle.
 // See https://github.com/dalek-cryptography/subtle/pull/48
 trait CtOptionExt<T> {
@@ -0,0 +1,745 @@
+//! The optimal ate pairing on BLS12-377.
+//!
+//! BLS12-377 uses a D-type sextic twist: $\mathbb{G}_2$ lives on
+//! $E': y^2 = x^3 + 1/u$ over Fp2, and the line functions of the Miller loop
+//! are sparse elements $c_0 + (c_3 + c_4 v) w$ of Fp12. The Miller loop and
+//! final exponentiation follow arkworks' `Bls12` implementation, so pairing
+//! values match it.
+
+use core::fmt;
+use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+
+use crate::fp::Fp;
+use crate::fp12::Fp12;
+use crate::fp2::Fp2;
+use crate::fp6::Fp6;
+use crate::g2::b as g2_b;
+use crate::{G1Affine, G2Affine, Scalar, BLS_X};
+
+/// The number of line functions in the Miller loop: one per doubling, for
+/// the 63 bits of x below the leading one, and one per addition, for the 6
+/// set bits among them.
+const NUM_COEFFS: usize = 69;
+
+/// 1/2 in Fp.
+const TWO_INV: Fp = Fp::from_raw_unchecked([
+    0x8166ffffffffffb4,
+    0x28a04fc1bfffffd8,
+    0xcfbed9d4c53e9ff9,
+    0x3da74bdbb73e3182,
+    0x267a4adfc01e4274,
+    0x46b330f17efa4d,
+]);
+
+/// Represents results of a Miller loop, one of the most expensive portions
+/// of the pairing function. `MillerLoopResult`s cannot be compared with each
+/// other until `.final_exponentiation()` is called, which is also expensive.
+#[derive(Copy, Clone, Debug)]
+pub struct MillerLoopResult(pub(crate) Fp12);
+
+impl Default for MillerLoopResult {
+    fn default() -> Self {
+        MillerLoopResult(Fp12::one())
+    }
+}
+
+impl ConditionallySelectable for MillerLoopResult {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        MillerLoopResult(Fp12::conditional_select(&a.0, &b.0, choice))
+    }
+}
+
+impl MillerLoopResult {
+    /// This performs a "final exponentiation" routine to convert the result
+    /// of a Miller loop into an element of `Gt` with help of efficient
+    /// squaring operations in the so-called `cyclotomic subgroup` of `Fp6`
+    /// so that it can be compared with other elements of `Gt`.
+    pub fn final_exponentiation(&self) -> Gt {
+        #[must_use]
+        fn fp4_square(a: Fp2, b: Fp2) -> (Fp2, Fp2) {
+            let t0 = a.square();
+            let t1 = b.square();
+            let mut t2 = t1.mul_by_u();
+            let c0 = t2 + t0;
+            t2 = a + b;
+            t2 = t2.square();
+            t2 -= t0;
+            let c1 = t2 - t1;
+
+            (c0, c1)
+        }
+
+        // Adaptation of Algorithm 5.5.4, Guide to Pairing-Based Cryptography
+        // Faster Squaring in the Cyclotomic Subgroup of Sixth Degree Extensions
+        // https://eprint.iacr.org/2009/565.pdf
+        #[must_use]
+        fn cyclotomic_square(f: Fp12) -> Fp12 {
+            let mut z0 = f.c0.c0;
+            let mut z4 = f.c0.c1;
+            let mut z3 = f.c0.c2;
+            let mut z2 = f.c1.c0;
+            let mut z1 = f.c1.c1;
+            let mut z5 = f.c1.c2;
+
+            let (t0, t1) = fp4_square(z0, z1);
+
+            // For A
+            z0 = t0 - z0;
+            z0 = z0 + z0 + t0;
+
+            z1 = t1 + z1;
+            z1 = z1 + z1 + t1;
+
+            let (mut t0, t1) = fp4_square(z2, z3);
+            let (t2, t3) = fp4_square(z4, z5);
+
+            // For C
+            z4 = t0 - z4;
+            z4 = z4 + z4 + t0;
+
+            z5 = t1 + z5;
+            z5 = z5 + z5 + t1;
+
+            // For B
+            t0 = t3.mul_by_u();
+            z2 = t0 + z2;
+            z2 = z2 + z2 + t0;
+
+            z3 = t2 - z3;
+            z3 = z3 + z3 + t2;
+
+            Fp12 {
+                c0: Fp6 {
+                    c0: z0,
+                    c1: z4,
+                    c2: z3,
+                },
+                c1: Fp6 {
+                    c0: z2,
+                    c1: z1,
+                    c2: z5,
+                },
+            }
+        }
+
+        /// Raises an element of the cyclotomic subgroup to x, which is
+        /// public.
+        #[must_use]
+        fn cyclotomic_exp(f: Fp12) -> Fp12 {
+            let mut tmp = Fp12::one();
+            for i in (0..64).rev() {
+                tmp = cyclotomic_square(tmp);
+                if ((BLS_X >> i) & 1) == 1 {
+                    tmp *= f;
+                }
+            }
+            tmp
+        }
+
+        let f = self.0;
+
+        // The easy part, f^((p^6 - 1)(p^2 + 1)), moves f into the cyclotomic
+        // subgroup, where the inverse is the conjugate. A Miller loop over
+        // valid points is never zero; if it were, the zero propagates into a
+        // result that matches no pairing value.
+        let mut r = f.conjugate() * f.invert().unwrap_or(Fp12::zero());
+        r = r.frobenius_map().frobenius_map() * r;
+
+        // The hard part raises r to
+        //   λ0 + λ1 p + λ2 p^2 + λ3 p^3 = 3 (p^4 - p^2 + 1) / q,
+        // with
+        //   λ3 = x^2 - 2x + 1,
+        //   λ2 = x^3 - 2x^2 + x = x λ3,
+        //   λ1 = x^4 - 2x^3 + 2x - 1 = x λ2 - λ3 + x,
+        //   λ0 = x^5 - 2x^4 + 2x^2 - x + 3 = x λ1 + λ3 + 2.
+        let mut y0 = cyclotomic_square(r).conjugate(); // r^-2
+        let mut y5 = cyclotomic_exp(r); // r^x
+        let mut y1 = cyclotomic_square(y5); // r^2x
+        let mut y3 = y0 * y5; // r^(x - 2)
+        y0 = cyclotomic_exp(y3); // r^(x^2 - 2x)
+        let y2 = cyclotomic_exp(y0); // r^(x^3 - 2x^2)
+        let mut y4 = cyclotomic_exp(y2); // r^(x^4 - 2x^3)
+        y4 *= y1; // r^(x^4 - 2x^3 + 2x)
+        y1 = cyclotomic_exp(y4); // r^(x^5 - 2x^4 + 2x^2)
+        y3 = y3.conjugate(); // r^(-x + 2)
+        y1 *= y3; // r^(x^5 - 2x^4 + 2x^2 - x + 2)
+        y1 *= r; // r^λ0
+        y3 = r.conjugate(); // r^-1
+        y0 *= r; // r^λ3
+        y0 = y0.frobenius_map().frobenius_map().frobenius_map(); // r^(λ3 p^3)
+        y4 *= y3; // r^λ1
+        y4 = y4.frobenius_map(); // r^(λ1 p)
+        y5 *= y2; // r^λ2
+        y5 = y5.frobenius_map().frobenius_map(); // r^(λ2 p^2)
+
+        Gt(y5 * y0 * y4 * y1)
+    }
+}
+
+impl<'b> Add<&'b MillerLoopResult> for &MillerLoopResult {
+    type Output = MillerLoopResult;
+
+    #[inline]
+    fn add(self, rhs: &'b MillerLoopResult) -> MillerLoopResult {
+        MillerLoopResult(self.0 * rhs.0)
+    }
+}
+
+impl_add_binop_specify_output!(MillerLoopResult, MillerLoopResult, MillerLoopResult);
+
+impl AddAssign<MillerLoopResult> for MillerLoopResult {
+    #[inline]
+    fn add_assign(&mut self, rhs: MillerLoopResult) {
+        *self = *self + rhs;
+    }
+}
+
+impl<'b> AddAssign<&'b MillerLoopResult> for MillerLoopResult {
+    #[inline]
+    fn add_assign(&mut self, rhs: &'b MillerLoopResult) {
+        *self = *self + rhs;
+    }
+}
+
+/// This is an element of $\mathbb{G}_T$, the target group of the pairing
+/// function. As with $\mathbb{G}_1$ and $\mathbb{G}_2$ this group has order
+/// $q$.
+///
+/// Typically, $\mathbb{G}_T$ is written multiplicatively but we will write
+/// it additively to keep code and abstractions consistent.
+#[derive(Copy, Clone, Debug)]
+pub struct Gt(pub(crate) Fp12);
+
+impl Default for Gt {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl fmt::Display for Gt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl ConstantTimeEq for Gt {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl ConditionallySelectable for Gt {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Gt(Fp12::conditional_select(&a.0, &b.0, choice))
+    }
+}
+
+impl Eq for Gt {}
+impl PartialEq for Gt {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        bool::from(self.ct_eq(other))
+    }
+}
+
+impl Gt {
+    /// Returns the group identity, which is $1$.
+    pub fn identity() -> Gt {
+        Gt(Fp12::one())
+    }
+
+    /// Returns `true` if this element is the identity.
+    pub fn is_identity(&self) -> Choice {
+        self.ct_eq(&Self::identity())
+    }
+
+    /// Doubles this group element.
+    pub fn double(&self) -> Gt {
+        Gt(self.0.square())
+    }
+}
+
+impl Neg for &Gt {
+    type Output = Gt;
+
+    #[inline]
+    fn neg(self) -> Gt {
+        // The element is unitary, so we just conjugate.
+        Gt(self.0.conjugate())
+    }
+}
+
+impl Neg for Gt {
+    type Output = Gt;
+
+    #[inline]
+    fn neg(self) -> Gt {
+        -&self
+    }
+}
+
+impl<'b> Add<&'b Gt> for &Gt {
+    type Output = Gt;
+
+    #[inline]
+    fn add(self, rhs: &'b Gt) -> Gt {
+        Gt(self.0 * rhs.0)
+    }
+}
+
+impl<'b> Sub<&'b Gt> for &Gt {
+    type Output = Gt;
+
+    #[inline]
+    fn sub(self, rhs: &'b Gt) -> Gt {
+        self + (-rhs)
+    }
+}
+
+impl<'b> Mul<&'b Scalar> for &Gt {
+    type Output = Gt;
+
+    fn mul(self, other: &'b Scalar) -> Self::Output {
+        let mut acc = Gt::identity();
+
+        // This is a simple double-and-add implementation of group element
+        // multiplication, moving from most significant to least
+        // significant bit of the scalar.
+        //
+        // We skip the leading bit because it's always unset for Fq
+        // elements.
+        for bit in other
+            .to_bytes()
+            .iter()
+            .rev()
+            .flat_map(|byte| (0..8).rev().map(move |i| Choice::from((byte >> i) & 1u8)))
+            .skip(1)
+        {
+            acc = acc.double();
+            acc = Gt::conditional_select(&acc, &(acc + self), bit);
+        }
+
+        acc
+    }
+}
+
+impl_binops_additive!(Gt, Gt);
+impl_binops_multiplicative!(Gt, Scalar);
+
+/// This structure contains cached computations pertaining to a
+/// $\mathbb{G}_2$ element as part of the pairing function (specifically,
+/// the Miller loop) and so should be computed whenever a $\mathbb{G}_2$
+/// element is being used in multiple pairings or is otherwise known in
+/// advance. This should be used in conjunction with the
+/// [`multi_miller_loop`](crate::multi_miller_loop) function provided by
+/// this crate.
+#[derive(Clone, Debug)]
+pub struct G2Prepared {
+    infinity: Choice,
+    coeffs: [(Fp2, Fp2, Fp2); NUM_COEFFS],
+}
+
+impl From<G2Affine> for G2Prepared {
+    fn from(q: G2Affine) -> G2Prepared {
+        struct Adder {
+            cur: G2Homogeneous,
+            base: G2Affine,
+            coeffs: [(Fp2, Fp2, Fp2); NUM_COEFFS],
+            index: usize,
+        }
+
+        impl Adder {
+            // The loop takes exactly `NUM_COEFFS` steps; `get_mut` keeps
+            // the bounds check from leaving a panic path behind.
+            fn push(&mut self, coeffs: (Fp2, Fp2, Fp2)) {
+                if let Some(c) = self.coeffs.get_mut(self.index) {
+                    *c = coeffs;
+                }
+                self.index += 1;
+            }
+        }
+
+        impl MillerLoopDriver for Adder {
+            type Output = ();
+
+            fn doubling_step(&mut self, _: Self::Output) -> Self::Output {
+                let coeffs = doubling_step(&mut self.cur);
+                self.push(coeffs);
+            }
+            fn addition_step(&mut self, _: Self::Output) -> Self::Output {
+                let coeffs = addition_step(&mut self.cur, &self.base);
+                self.push(coeffs);
+            }
+            fn square_output(_: Self::Output) -> Self::Output {}
+            fn one() -> Self::Output {}
+        }
+
+        let is_identity = q.is_identity();
+        let q = G2Affine::conditional_select(&q, &G2Affine::generator(), is_identity);
+
+        let mut adder = Adder {
+            cur: G2Homogeneous::from(&q),
+            base: q,
+            coeffs: [(Fp2::zero(), Fp2::zero(), Fp2::zero()); NUM_COEFFS],
+            index: 0,
+        };
+
+        miller_loop(&mut adder);
+
+        debug_assert_eq!(adder.index, NUM_COEFFS);
+
+        G2Prepared {
+            infinity: is_identity,
+            coeffs: adder.coeffs,
+        }
+    }
+}
+
+/// Computes $$\sum_{i=1}^n \textbf{ML}(a_i, b_i)$$ given a series of terms
+/// $$(a_1, b_1), (a_2, b_2), ..., (a_n, b_n).$$
+///
+/// Requires the `pairings` feature to be enabled.
+pub fn multi_miller_loop(terms: &[(&G1Affine, &G2Prepared)]) -> MillerLoopResult {
+    struct Adder<'a, 'b, 'c> {
+        terms: &'c [(&'a G1Affine, &'b G2Prepared)],
+        index: usize,
+    }
+
+    impl<'a, 'b, 'c> Adder<'a, 'b, 'c> {
+        fn step(&mut self, mut f: Fp12) -> Fp12 {
+            let index = self.index;
+            for term in self.terms {
+                // As in `G2Prepared::from`, `get` leaves no panic path.
+                if let Some(coeffs) = term.1.coeffs.get(index) {
+                    let either_identity = term.0.is_identity() | term.1.infinity;
+
+                    let new_f = ell(f, coeffs, term.0);
+                    f = Fp12::conditional_select(&new_f, &f, either_identity);
+                }
+            }
+            self.index += 1;
+
+            f
+        }
+    }
+
+    impl<'a, 'b, 'c> MillerLoopDriver for Adder<'a, 'b, 'c> {
+        type Output = Fp12;
+
+        fn doubling_step(&mut self, f: Self::Output) -> Self::Output {
+            self.step(f)
+        }
+        fn addition_step(&mut self, f: Self::Output) -> Self::Output {
+            self.step(f)
+        }
+        fn square_output(f: Self::Output) -> Self::Output {
+            f.square()
+        }
+        fn one() -> Self::Output {
+            Fp12::one()
+        }
+    }
+
+    let mut adder = Adder { terms, index: 0 };
+
+    MillerLoopResult(miller_loop(&mut adder))
+}
+
+/// Invoke the pairing function without the use of precomputation and other
+/// optimizations.
+pub fn pairing(p: &G1Affine, q: &G2Affine) -> Gt {
+    struct Adder {
+        cur: G2Homogeneous,
+        base: G2Affine,
+        p: G1Affine,
+    }
+
+    impl MillerLoopDriver for Adder {
+        type Output = Fp12;
+
+        fn doubling_step(&mut self, f: Self::Output) -> Self::Output {
+            let coeffs = doubling_step(&mut self.cur);
+            ell(f, &coeffs, &self.p)
+        }
+        fn addition_step(&mut self, f: Self::Output) -> Self::Output {
+            let coeffs = addition_step(&mut self.cur, &self.base);
+            ell(f, &coeffs, &self.p)
+        }
+        fn square_output(f: Self::Output) -> Self::Output {
+            f.square()
+        }
+        fn one() -> Self::Output {
+            Fp12::one()
+        }
+    }
+
+    let either_identity = p.is_identity() | q.is_identity();
+    let p = G1Affine::conditional_select(p, &G1Affine::generator(), either_identity);
+    let q = G2Affine::conditional_select(q, &G2Affine::generator(), either_identity);
+
+    let mut adder = Adder {
+        cur: G2Homogeneous::from(&q),
+        base: q,
+        p,
+    };
+
+    let tmp = miller_loop(&mut adder);
+    let tmp = MillerLoopResult(Fp12::conditional_select(
+        &tmp,
+        &Fp12::one(),
+        either_identity,
+    ));
+    tmp.final_exponentiation()
+}
+
+trait MillerLoopDriver {
+    type Output;
+
+    fn doubling_step(&mut self, f: Self::Output) -> Self::Output;
+    fn addition_step(&mut self, f: Self::Output) -> Self::Output;
+    fn square_output(f: Self::Output) -> Self::Output;
+    fn one() -> Self::Output;
+}
+
+/// This is a "generic" implementation of the Miller loop to avoid
+/// duplicating code structure elsewhere; instead, we'll write concrete
+/// instantiations of `MillerLoopDriver` for whatever purposes we need
+/// (such as caching modes). x is positive, so the result needs no final
+/// conjugation.
+fn miller_loop<D: MillerLoopDriver>(driver: &mut D) -> D::Output {
+    let mut f = D::one();
+
+    for i in (0..63).rev() {
+        f = D::square_output(f);
+        f = driver.doubling_step(f);
+
+        if ((BLS_X >> i) & 1) == 1 {
+            f = driver.addition_step(f);
+        }
+    }
+
+    f
+}
+
+/// A point of $E'$ in homogeneous projective coordinates (x = X/Z,
+/// y = Y/Z), which the line formulas below are written for.
+#[derive(Copy, Clone)]
+struct G2Homogeneous {
+    x: Fp2,
+    y: Fp2,
+    z: Fp2,
+}
+
+impl<'a> From<&'a G2Affine> for G2Homogeneous {
+    fn from(q: &'a G2Affine) -> G2Homogeneous {
+        G2Homogeneous {
+            x: q.x,
+            y: q.y,
+            z: Fp2::one(),
+        }
+    }
+}
+
+/// Evaluates a line at `p` and multiplies it into `f`.
+fn ell(f: Fp12, coeffs: &(Fp2, Fp2, Fp2), p: &G1Affine) -> Fp12 {
+    let c0 = coeffs.0.mul_by_fp(&p.y);
+    let c3 = coeffs.1.mul_by_fp(&p.x);
+
+    f.mul_by_034(&c0, &c3, &coeffs.2)
+}
+
+/// Doubles `r` and returns the coefficients of the tangent line, using the
+/// formulas of "Faster Explicit Formulas for Computing Pairings over
+/// Ordinary Curves" (Aranha et al.), section 4.
+fn doubling_step(r: &mut G2Homogeneous) -> (Fp2, Fp2, Fp2) {
+    let a = (r.x * r.y).mul_by_fp(&TWO_INV);
+    let b = r.y.square();
+    let c = r.z.square();
+    let e = g2_b() * (c + c + c);
+    let f = e + e + e;
+    let g = (b + f).mul_by_fp(&TWO_INV);
+    let h = (r.y + r.z).square() - (b + c);
+    let i = e - b;
+    let j = r.x.square();
+    let e_square = e.square();
+
+    r.x = a * (b - f);
+    r.y = g.square() - (e_square + e_square + e_square);
+    r.z = b * h;
+
+    (-h, j + j + j, i)
+}
+
+/// Adds `q` to `r` and returns the coefficients of the line through them.
+fn addition_step(r: &mut G2Homogeneous, q: &G2Affine) -> (Fp2, Fp2, Fp2) {
+    let theta = r.y - (q.y * r.z);
+    let lambda = r.x - (q.x * r.z);
+    let c = theta.square();
+    let d = lambda.square();
+    let e = lambda * d;
+    let f = r.z * c;
+    let g = r.x * d;
+    let h = e + f - (g + g);
+
+    r.x = lambda * h;
+    r.y = theta * (g - h) - (e * r.y);
+    r.z *= e;
+
+    let j = theta * q.x - (lambda * q.y);
+
+    (lambda, -theta, j)
+}
+
+#[cfg(test)]
+use crate::{G1Projective, G2Projective};
+
+#[test]
+fn test_gt_order() {
+    let g = pairing(&G1Affine::generator(), &G2Affine::generator());
+    assert!(!bool::from(g.is_identity()));
+    assert_eq!(g * -Scalar::one(), -g);
+    assert!(bool::from((g * -Scalar::one() + g).is_identity()));
+}
+
+#[test]
+fn test_bilinearity() {
+    let a = Scalar::from_raw([1, 2, 3, 4]).square();
+    let b = Scalar::from_raw([5, 6, 7, 8]).square();
+    let c = a * b;
+
+    let g = G1Affine::from(G1Affine::generator() * a);
+    let h = G2Affine::from(G2Affine::generator() * b);
+    let p = pairing(&g, &h);
+
+    assert!(p != Gt::identity());
+
+    let expected = G1Affine::from(G1Affine::generator() * c);
+
+    assert_eq!(p, pairing(&expected, &G2Affine::generator()));
+    assert_eq!(
+        p,
+        pairing(&G1Affine::generator(), &G2Affine::generator()) * c
+    );
+}
+
+#[test]
+fn test_unitary() {
+    let g = G1Affine::generator();
+    let h = G2Affine::generator();
+    let p = -pairing(&g, &h);
+    let q = pairing(&g, &-h);
+    let r = pairing(&-g, &h);
+
+    assert_eq!(p, q);
+    assert_eq!(q, r);
+}
+
+#[test]
+fn test_identity_pairing() {
+    assert_eq!(
+        pairing(&G1Affine::identity(), &G2Affine::generator()),
+        Gt::identity()
+    );
+    assert_eq!(
+        pairing(&G1Affine::generator(), &G2Affine::identity()),
+        Gt::identity()
+    );
+}
+
+#[test]
+fn test_known_answer() {
+    // e(g1, g2) as computed by an independent reference implementation,
+    // with affine lines and a plain final exponentiation by (p^12 - 1) / q,
+    // then cubed: the hard part above raises to 3 (p^4 - p^2 + 1) / q.
+    const EXPECTED: [&str; 12] = [
+        "00b718ff624a95f189bfb44bcd6d6556226837c1f74d1afbf4bea573b71c17d3a243cae41d966e2164aad0991fd790cc",
+        "0197261459eb50c526a28ebbdbd4b5b33d4c55b759d8c926289c96e4ea032783da4f1994ed09ee68fd791367c8b54d87",
+        "00756970de5e545d91121e151ce96c26ad820ebe4ffbc9dee234351401925eaa4193e377135ced4d3845057c0c39ecd6",
+        "00373f07857759dbec3d57af8bfdc79d28f44db5103e523e28ea69c688af7c831e726417cb5123530fadb5540ac05763",
+        "00ec2d5430932820eb74bd698a2d919cf7086335f235019815501b97fd833d90f07eb111885af785beb343ea1db8d4e7",
+        "0051ae2dce91bcd2251abbaf8dfb67c7e5cf6d864c61f81a09aaeac3dfdcf6ae0b3168929ccc7d91abb8b4e13974b7db",
+        "0095fcebb2a29b10d2f5283a40b147a82ea62114c9bae68e0d745c1afc70c6eeaf1b1c5bf6352d82931b6bdcbff8da47",
+        "001fdad7541653e8ac2d735c24f472716122bb24a3e675c20ab2c23d7380c7a349d49dd0db11f95c08861744e3b19a8e",
+        "00b3530a66bf5754b3e0b7b2c070a35c072bb613698c32db836cef1fcb77086125efd02528d4235f7d7b87e554174d82",
+        "004064943ac5c2fc0ef854d8168c67f56adb2a5a16d900dba15be3ecb0172a9ecd96ebf6375d0262f5d43d0709dc8c5f",
+        "0066910d06a91685179f1b448b9b198d5ed2eabc44d21580005e5f708a3c7858eb9b921691e40ba25804aced41190d34",
+        "0008f3e3e451ff584f864ca1d53fc34562f2ebf3baa7c610d8a3b51a7fa9e8dfaac34399e40540e3bc57a73d11924c03",
+    ];
+
+    let g = pairing(&G1Affine::generator(), &G2Affine::generator()).0;
+    let coeffs = [
+        g.c0.c0.c0, g.c0.c0.c1, g.c0.c1.c0, g.c0.c1.c1, g.c0.c2.c0, g.c0.c2.c1, g.c1.c0.c0,
+        g.c1.c0.c1, g.c1.c1.c0, g.c1.c1.c1, g.c1.c2.c0, g.c1.c2.c1,
+    ];
+    for (c, expected) in coeffs.iter().zip(EXPECTED.iter()) {
+        let mut bytes = [0u8; 48];
+        bytes.copy_from_slice(&hex::decode(expected).unwrap());
+        assert_eq!(*c, Fp::from_bytes(&bytes).unwrap());
+    }
+}
+
+#[test]
+fn test_multi_miller_loop() {
+    let a1 = G1Affine::generator();
+    let b1 = G2Affine::generator();
+
+    let a2 = G1Affine::from(G1Affine::generator() * Scalar::from_raw([1, 2, 3, 4]).square());
+    let b2 = G2Affine::from(G2Affine::generator() * Scalar::from_raw([4, 2, 2, 4]).square());
+
+    let a3 = G1Affine::identity();
+    let b3 = G2Affine::from(G2Affine::generator() * Scalar::from_raw([9, 2, 2, 4]).square());
+
+    let a4 = G1Affine::from(G1Affine::generator() * Scalar::from_raw([5, 5, 5, 5]).square());
+    let b4 = G2Affine::identity();
+
+    let a5 = G1Affine::from(G1Affine::generator() * Scalar::from_raw([323, 32, 3, 1]).square());
+    let b5 = G2Affine::from(G2Affine::generator() * Scalar::from_raw([4, 2, 2, 9099]).square());
+
+    let b1_prepared = G2Prepared::from(b1);
+    let b2_prepared = G2Prepared::from(b2);
+    let b3_prepared = G2Prepared::from(b3);
+    let b4_prepared = G2Prepared::from(b4);
+    let b5_prepared = G2Prepared::from(b5);
+
+    let expected = pairing(&a1, &b1)
+        + pairing(&a2, &b2)
+        + pairing(&a3, &b3)
+        + pairing(&a4, &b4)
+        + pairing(&a5, &b5);
+
+    let test = multi_miller_loop(&[
+        (&a1, &b1_prepared),
+        (&a2, &b2_prepared),
+        (&a3, &b3_prepared),
+        (&a4, &b4_prepared),
+        (&a5, &b5_prepared),
+    ])
+    .final_exponentiation();
+
+    assert_eq!(expected, test);
+}
+
+#[test]
+fn test_miller_loop_result_default() {
+    assert_eq!(
+        MillerLoopResult::default().final_exponentiation(),
+        Gt::identity(),
+    );
+}
+
+#[test]
+fn test_pairing_check() {
+    // e(a g1, g2) e(-g1, a g2) = 1, the shape of a signature check.
+    let a = Scalar::from(0x1234_5678);
+    let p = G1Affine::from(G1Projective::generator() * a);
+    let q = G2Prepared::from(G2Affine::from(G2Projective::generator() * a));
+    let g2 = G2Prepared::from(G2Affine::generator());
+
+    let check = multi_miller_loop(&[(&p, &g2), (&-G1Affine::generator(), &q)]).final_exponentiation();
+    assert!(bool::from(check.is_identity()));
+
+    let check = multi_miller_loop(&[(&p, &g2), (&G1Affine::generator(), &q)]).final_exponentiation();
+    assert!(!bool::from(check.is_identity()));
+}
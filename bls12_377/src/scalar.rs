@@ -5,7 +5,11 @@ use core::fmt;
 use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 
 use byteorder::{ByteOrder, LittleEndian};
+#[cfg(feature = "rand_core")]
+use rand_core::{CryptoRng, RngCore};
 use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
 
 use crate::util::{adc, mac, sbb};
 
@@ -49,6 +53,13 @@ impl PartialEq for Scalar {
     }
 }
 
+#[cfg(feature = "zeroize")]
+impl Zeroize for Scalar {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
 impl ConditionallySelectable for Scalar {
     fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
         Scalar([
@@ -238,6 +249,15 @@ impl Scalar {
         res
     }
 
+    /// Returns a uniformly random element of the field, obtained by
+    /// reducing 512 bits drawn from `rng` by the modulus.
+    #[cfg(feature = "rand_core")]
+    pub fn random(mut rng: impl RngCore + CryptoRng) -> Scalar {
+        let mut buf = [0; 64];
+        rng.fill_bytes(&mut buf);
+        Scalar::from_bytes_wide(&buf)
+    }
+
     /// Converts a 512-bit little endian integer into
     /// a `Scalar` by reducing by the modulus.
     pub fn from_bytes_wide(bytes: &[u8; 64]) -> Scalar {
@@ -362,6 +382,20 @@ impl Scalar {
         )
     }
 
+    /// Computes the multiplicative inverse of this element,
+    /// failing if the element is zero.
+    pub fn invert(&self) -> CtOption<Self> {
+        // self^(q - 2)
+        let t = self.pow_vartime(&[
+            0x0a117fffffffffff,
+            0x59aa76fed0000001,
+            0x60b44d1e5c37b001,
+            0x12ab655e9a2ca556,
+        ]);
+
+        CtOption::new(t, !self.ct_eq(&Self::zero()))
+    }
+
     /// Exponentiates `self` by `by`, where `by` is a
     /// little-endian order integer exponent.
     pub fn pow(&self, by: &[u64; 4]) -> Self {
@@ -872,6 +906,24 @@ fn test_squaring() {
     }
 }
 
+#[test]
+fn test_invert() {
+    assert!(bool::from(Scalar::zero().invert().is_none()));
+    assert_eq!(Scalar::one().invert().unwrap(), Scalar::one());
+    assert_eq!((-Scalar::one()).invert().unwrap(), -Scalar::one());
+
+    let mut tmp = LARGEST;
+
+    for _ in 0..100 {
+        let mut tmp2 = tmp.invert().unwrap();
+        tmp2.mul_assign(&tmp);
+
+        assert_eq!(tmp2, Scalar::one());
+
+        tmp.add_assign(&LARGEST);
+    }
+}
+
 #[test]
 fn test_from_raw() {
     assert_eq!(Scalar::from_raw(modulus().0), Scalar::zero());
@@ -890,3 +942,26 @@ fn test_double() {
 
     assert_eq!(a.double(), a + a);
 }
+
+#[cfg(feature = "rand_core")]
+#[test]
+fn test_random() {
+    use rand_chacha::rand_core::SeedableRng;
+
+    let mut rng = rand_chacha::ChaChaRng::from_seed([0u8; 32]);
+    let a = Scalar::random(&mut rng);
+    let b = Scalar::random(&mut rng);
+    assert!(a != b);
+    assert!(a != Scalar::zero());
+    assert!(b != Scalar::zero());
+}
+
+#[cfg(feature = "zeroize")]
+#[test]
+fn test_zeroize() {
+    use zeroize::Zeroize;
+
+    let mut a = Scalar::from(0x0102_0304u64);
+    a.zeroize();
+    assert_eq!(a, Scalar::zero());
+}
@@ -0,0 +1,274 @@
+//! This module implements `serde` support for the field and group elements
+//! of this crate, behind the `serde` feature.
+//!
+//! Elements are encoded with the same little-endian byte representations
+//! used by arkworks and Celo: scalars and base field elements as canonical
+//! little-endian integers, and affine points in compressed form. Human-readable
+//! formats (such as JSON) receive a lowercase hex string; binary formats (such
+//! as CBOR) receive the raw bytes. Points are validated to be on the curve and
+//! in the correct subgroup when deserialized. No allocation is performed.
+
+use core::fmt;
+
+use serde::de::{self, Deserializer, SeqAccess, Visitor};
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
+
+use crate::Scalar;
+
+#[cfg(feature = "groups")]
+use crate::fp::Fp;
+#[cfg(feature = "groups")]
+use crate::{G1Affine, G2Affine};
+
+/// The largest encoding handled by this module, that of a compressed
+/// `G2Affine` element.
+const MAX_LEN: usize = 96;
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+fn serialize_bytes<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        let mut buf = [0u8; 2 * MAX_LEN];
+        let buf = &mut buf[..2 * bytes.len()];
+        for (byte, out) in bytes.iter().zip(buf.chunks_mut(2)) {
+            out[0] = HEX_DIGITS[(byte >> 4) as usize];
+            out[1] = HEX_DIGITS[(byte & 0x0f) as usize];
+        }
+
+        // The buffer only contains ASCII hex digits.
+        serializer.serialize_str(core::str::from_utf8(buf).unwrap())
+    } else {
+        serializer.serialize_bytes(bytes)
+    }
+}
+
+fn hex_value(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'a'..=b'f' => Some(c - b'a' + 10),
+        b'A'..=b'F' => Some(c - b'A' + 10),
+        _ => None,
+    }
+}
+
+/// Fills a fixed-length output buffer from either a hex string or a byte
+/// string, depending on what the deserializer hands us.
+struct BytesVisitor<'a> {
+    out: &'a mut [u8],
+}
+
+impl<'a, 'de> Visitor<'de> for BytesVisitor<'a> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} bytes or a hex string of {} digits",
+            self.out.len(),
+            2 * self.out.len()
+        )
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<(), E> {
+        let v = v.as_bytes();
+        if v.len() != 2 * self.out.len() {
+            return Err(E::invalid_length(v.len(), &self));
+        }
+
+        for (out, digits) in self.out.iter_mut().zip(v.chunks(2)) {
+            match (hex_value(digits[0]), hex_value(digits[1])) {
+                (Some(hi), Some(lo)) => *out = (hi << 4) | lo,
+                _ => return Err(E::custom("invalid hex digit")),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<(), E> {
+        if v.len() != self.out.len() {
+            return Err(E::invalid_length(v.len(), &self));
+        }
+
+        self.out.copy_from_slice(v);
+
+        Ok(())
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        // Some binary formats hand byte strings over as sequences of `u8`.
+        let len = self.out.len();
+        for i in 0..len {
+            self.out[i] = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(i, &self))?;
+        }
+
+        if seq.next_element::<u8>()?.is_some() {
+            return Err(de::Error::invalid_length(len + 1, &self));
+        }
+
+        Ok(())
+    }
+}
+
+fn deserialize_bytes<'de, D: Deserializer<'de>>(
+    deserializer: D,
+    out: &mut [u8],
+) -> Result<(), D::Error> {
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_str(BytesVisitor { out })
+    } else {
+        deserializer.deserialize_bytes(BytesVisitor { out })
+    }
+}
+
+macro_rules! impl_serde {
+    ($t:ident, $len:expr, $to_bytes:ident, $from_bytes:ident, $what:expr) => {
+        impl Serialize for $t {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serialize_bytes(&self.$to_bytes()[..], serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $t {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let mut bytes = [0u8; $len];
+                deserialize_bytes(deserializer, &mut bytes[..])?;
+
+                Option::from($t::$from_bytes(&bytes))
+                    .ok_or_else(|| de::Error::custom(concat!("invalid ", $what, " encoding")))
+            }
+        }
+    };
+}
+
+impl_serde!(Scalar, 32, to_bytes, from_bytes, "scalar");
+
+#[cfg(feature = "groups")]
+impl_serde!(
+    Fp,
+    48,
+    to_bytes_littleendian,
+    from_bytes_little_endian,
+    "field element"
+);
+
+#[cfg(feature = "groups")]
+impl_serde!(G1Affine, 48, to_compressed_le, from_compressed_le, "G1 point");
+
+#[cfg(feature = "groups")]
+impl_serde!(G2Affine, 96, to_compressed_le, from_compressed_le, "G2 point");
+
+#[test]
+fn test_scalar_serde() {
+    let a = Scalar::from(0x0102_0304u64);
+
+    let json = serde_json::to_string(&a).unwrap();
+    assert_eq!(
+        json,
+        "\"0403020100000000000000000000000000000000000000000000000000000000\""
+    );
+    assert_eq!(serde_json::from_str::<Scalar>(&json).unwrap(), a);
+
+    // Upper-case hex digits are accepted as well.
+    assert_eq!(serde_json::from_str::<Scalar>(&json.to_uppercase()).unwrap(), a);
+
+    let cbor = serde_cbor::to_vec(&a).unwrap();
+    // A CBOR byte string of length 32.
+    assert_eq!(&cbor[..2], &[0x58, 32]);
+    assert_eq!(&cbor[2..], &a.to_bytes()[..]);
+    assert_eq!(serde_cbor::from_slice::<Scalar>(&cbor).unwrap(), a);
+
+    // Non-canonical scalars are rejected.
+    let json = format!("\"{}\"", "ff".repeat(32));
+    assert!(serde_json::from_str::<Scalar>(&json).is_err());
+
+    // As are encodings of the wrong length or containing invalid digits.
+    let json = format!("\"{}\"", "00".repeat(31));
+    assert!(serde_json::from_str::<Scalar>(&json).is_err());
+    let json = format!("\"{}\"", "0g".repeat(32));
+    assert!(serde_json::from_str::<Scalar>(&json).is_err());
+    let cbor = serde_cbor::to_vec(&serde_cbor::Value::Bytes(vec![0u8; 33])).unwrap();
+    assert!(serde_cbor::from_slice::<Scalar>(&cbor).is_err());
+}
+
+#[cfg(feature = "groups")]
+#[test]
+fn test_fp_serde() {
+    let a = -Fp::one();
+
+    let json = serde_json::to_string(&a).unwrap();
+    assert_eq!(serde_json::from_str::<Fp>(&json).unwrap(), a);
+
+    let cbor = serde_cbor::to_vec(&a).unwrap();
+    assert_eq!(&cbor[..2], &[0x58, 48]);
+    assert_eq!(&cbor[2..], &a.to_bytes_littleendian()[..]);
+    assert_eq!(serde_cbor::from_slice::<Fp>(&cbor).unwrap(), a);
+}
+
+#[cfg(feature = "groups")]
+#[test]
+fn test_g1_serde() {
+    use crate::G1Projective;
+
+    let points = [
+        G1Affine::identity(),
+        G1Affine::generator(),
+        G1Affine::from(G1Projective::generator() * Scalar::from(5u64)),
+    ];
+
+    for p in points.iter() {
+        let json = serde_json::to_string(p).unwrap();
+        assert_eq!(json.len(), 2 + 2 * 48);
+        assert_eq!(serde_json::from_str::<G1Affine>(&json).unwrap(), *p);
+
+        let cbor = serde_cbor::to_vec(p).unwrap();
+        assert_eq!(&cbor[..2], &[0x58, 48]);
+        assert_eq!(&cbor[2..], &p.to_compressed_le()[..]);
+        assert_eq!(serde_cbor::from_slice::<G1Affine>(&cbor).unwrap(), *p);
+    }
+
+    assert_eq!(
+        serde_json::to_string(&G1Affine::generator()).unwrap(),
+        "\"efe91bb26eb1b9ea4e39cdff121548d55ccb37bdc8828218bb419daa2c1e958554ff87bf2562fcc8670a74fede488880\""
+    );
+
+    // (5, y) is on the curve, but not in the q-order subgroup.
+    let mut x = [0u8; 48];
+    x[0] = 5;
+    assert!(G1Affine::from_compressed_le_unchecked(&x).is_some());
+    let cbor = serde_cbor::to_vec(&serde_cbor::Value::Bytes(x.to_vec())).unwrap();
+    assert!(serde_cbor::from_slice::<G1Affine>(&cbor).is_err());
+}
+
+#[cfg(feature = "groups")]
+#[test]
+fn test_g2_serde() {
+    use crate::G2Projective;
+
+    let points = [
+        G2Affine::identity(),
+        G2Affine::generator(),
+        G2Affine::from(G2Projective::generator() * Scalar::from(5u64)),
+    ];
+
+    for p in points.iter() {
+        let json = serde_json::to_string(p).unwrap();
+        assert_eq!(json.len(), 2 + 2 * 96);
+        assert_eq!(serde_json::from_str::<G2Affine>(&json).unwrap(), *p);
+
+        let cbor = serde_cbor::to_vec(p).unwrap();
+        assert_eq!(&cbor[..2], &[0x58, 96]);
+        assert_eq!(&cbor[2..], &p.to_compressed_le()[..]);
+        assert_eq!(serde_cbor::from_slice::<G2Affine>(&cbor).unwrap(), *p);
+    }
+
+    // (2, y) is on the curve, but not in the q-order subgroup.
+    let mut x = [0u8; 96];
+    x[0] = 2;
+    assert!(G2Affine::from_compressed_le_unchecked(&x).is_some());
+    let json = serde_json::to_string(&hex::encode(&x[..])).unwrap();
+    assert!(serde_json::from_str::<G2Affine>(&json).is_err());
+}
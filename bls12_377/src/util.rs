@@ -19,6 +19,16 @@ pub fn sbb(a: u64, b: u64, borrow: u64) -> (u64, u64) {
     (ret as u64, (ret >> 64) as u64)
 }
 
+/// Overwrites `bytes` with zeros if the `zeroize` feature is enabled, so that
+/// secret material does not linger in memory.
+#[inline(always)]
+pub(crate) fn wipe(bytes: &mut [u8]) {
+    #[cfg(feature = "zeroize")]
+    zeroize::Zeroize::zeroize(bytes);
+    #[cfg(not(feature = "zeroize"))]
+    let _ = bytes;
+}
+
 /// Compute a + (b * c) + carry, returning the result and the new carry over.
 pub fn mac(a: u64, b: u64, c: u64, carry: u64) -> (u64, u64) {
     let ret = (a as u128) + ((b as u128) * (c as u128)) + (carry as u128);
@@ -0,0 +1,360 @@
+//! Statistical timing-leakage tests for the constant-time primitives, after
+//! "dude, is my code constant time?" (Reparaz, Balasch and Verbauwhede).
+//!
+//! Each test times an operation on two classes of inputs, a fixed value and
+//! random values, interleaved in random order, and compares the two timing
+//! distributions with Welch's t-test. A constant-time operation leaves no
+//! difference for the test to find; a data-dependent branch, such as an
+//! early return on zero, drives |t| far above `THRESHOLD`. As in dudect, the
+//! test is repeated on the measurements below a range of percentiles, which
+//! removes the long tail of interrupts and context switches that can hide a
+//! small difference.
+//!
+//! The results depend on the machine, so the tests are ignored by default.
+//! Run them on an otherwise idle machine with
+//!
+//! ```text
+//! make timing
+//! ```
+//!
+//! or `cargo test --release --test timing -- --ignored --test-threads=1`.
+//! `TIMING_SAMPLES` scales the number of measurements; larger values find
+//! smaller leaks. `test_harness_detects_branch` checks that the harness
+//! itself is sensitive enough on the machine at hand.
+
+extern crate bls12_377;
+extern crate criterion;
+extern crate rand_chacha;
+
+use bls12_377::fp::Fp;
+use bls12_377::fp2::Fp2;
+use bls12_377::{G1Affine, G1Projective, G2Affine, G2Projective, Scalar};
+use criterion::black_box;
+use rand_chacha::rand_core::{RngCore, SeedableRng};
+use rand_chacha::ChaChaRng;
+use std::env;
+use std::time::Instant;
+use subtle::{Choice, ConditionallySelectable};
+
+/// The |t| above which the two classes are considered distinguishable. This
+/// is the threshold dudect uses to report a definite leak; 4.5, the usual
+/// TVLA threshold, gives false positives on a desktop machine.
+const THRESHOLD: f64 = 10.0;
+
+/// The number of percentile crops tested in addition to the full sample.
+const CROPS: usize = 20;
+
+/// Online mean and variance of the timings of the two classes.
+#[derive(Default)]
+struct Welch {
+    n: [f64; 2],
+    mean: [f64; 2],
+    m2: [f64; 2],
+}
+
+impl Welch {
+    fn push(&mut self, class: usize, x: f64) {
+        self.n[class] += 1.0;
+        let delta = x - self.mean[class];
+        self.mean[class] += delta / self.n[class];
+        self.m2[class] += delta * (x - self.mean[class]);
+    }
+
+    fn t(&self) -> f64 {
+        if self.n[0] < 2.0 || self.n[1] < 2.0 {
+            return 0.0;
+        }
+        let var0 = self.m2[0] / (self.n[0] - 1.0);
+        let var1 = self.m2[1] / (self.n[1] - 1.0);
+        let se = (var0 / self.n[0] + var1 / self.n[1]).sqrt();
+        if se == 0.0 {
+            return 0.0;
+        }
+        (self.mean[0] - self.mean[1]) / se
+    }
+}
+
+/// Scales a default number of measurements by the `TIMING_SAMPLES`
+/// environment variable, read as a multiplier.
+fn samples(default: usize) -> usize {
+    let scale = env::var("TIMING_SAMPLES")
+        .ok()
+        .and_then(|s| s.parse::<f64>().ok())
+        .unwrap_or(1.0);
+    ((default as f64) * scale) as usize
+}
+
+fn rng() -> ChaChaRng {
+    ChaChaRng::from_seed([0x42; 32])
+}
+
+fn random_fp(rng: &mut ChaChaRng) -> Fp {
+    let mut bytes = [0u8; 64];
+    rng.fill_bytes(&mut bytes);
+    Fp::from_bytes_wide(&bytes)
+}
+
+fn random_fp2(rng: &mut ChaChaRng) -> Fp2 {
+    Fp2 {
+        c0: random_fp(rng),
+        c1: random_fp(rng),
+    }
+}
+
+fn random_scalar(rng: &mut ChaChaRng) -> Scalar {
+    let mut bytes = [0u8; 64];
+    rng.fill_bytes(&mut bytes);
+    Scalar::from_bytes_wide(&bytes)
+}
+
+/// Times `op` on `n` inputs, each drawn from the fixed class 0 or the random
+/// class 1 by `input`, and returns the largest |t| over the full sample and
+/// its percentile crops. Each measurement covers `batch` calls, to lift fast
+/// operations well above the resolution of the clock.
+fn measure<I, O>(
+    name: &str,
+    n: usize,
+    batch: usize,
+    mut input: impl FnMut(usize, &mut ChaChaRng) -> I,
+    op: impl Fn(&I) -> O,
+) -> f64 {
+    let mut rng = rng();
+    let inputs: Vec<(usize, I)> = (0..n)
+        .map(|_| {
+            let class = (rng.next_u32() & 1) as usize;
+            (class, input(class, &mut rng))
+        })
+        .collect();
+
+    // Warm up caches and the branch predictor before measuring.
+    for (_, i) in inputs.iter().take(n / 10 + 1) {
+        black_box(op(black_box(i)));
+    }
+
+    let timings: Vec<f64> = inputs
+        .iter()
+        .map(|(_, i)| {
+            let start = Instant::now();
+            for _ in 0..batch {
+                black_box(op(black_box(i)));
+            }
+            start.elapsed().as_nanos() as f64
+        })
+        .collect();
+
+    // The crops keep the measurements below the percentiles
+    // 1 - 0.5^(10 (k + 1) / CROPS), as in dudect.
+    let mut sorted = timings.clone();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let cutoffs: Vec<f64> = (0..CROPS)
+        .map(|k| {
+            let p = 1.0 - 0.5f64.powf(10.0 * (k + 1) as f64 / CROPS as f64);
+            sorted[((p * n as f64) as usize).min(n - 1)]
+        })
+        .collect();
+
+    let mut full = Welch::default();
+    let mut cropped: Vec<Welch> = (0..CROPS).map(|_| Welch::default()).collect();
+    for ((class, _), &x) in inputs.iter().zip(timings.iter()) {
+        full.push(*class, x);
+        for (w, &cutoff) in cropped.iter_mut().zip(cutoffs.iter()) {
+            if x <= cutoff {
+                w.push(*class, x);
+            }
+        }
+    }
+
+    let max_t = cropped
+        .iter()
+        .map(|w| w.t().abs())
+        .fold(full.t().abs(), f64::max);
+    println!(
+        "{}: n = {}, mean fixed = {:.1} ns, mean random = {:.1} ns, max |t| = {:.2}",
+        name, n, full.mean[0], full.mean[1], max_t
+    );
+    max_t
+}
+
+/// Asserts that `op` shows no timing difference between the two classes.
+fn assert_constant_time<I, O>(
+    name: &str,
+    n: usize,
+    batch: usize,
+    input: impl FnMut(usize, &mut ChaChaRng) -> I,
+    op: impl Fn(&I) -> O,
+) {
+    let t = measure(name, samples(n), batch, input, op);
+    assert!(
+        t < THRESHOLD,
+        "{} leaks timing: |t| = {:.2} >= {}",
+        name,
+        t,
+        THRESHOLD
+    );
+}
+
+/// A multiplication with the kind of shortcut that must not slip into the
+/// crate: an early return when the second operand is zero.
+fn leaky_mul(a: &Fp, b: &Fp) -> Fp {
+    if *b == Fp::zero() {
+        return Fp::zero();
+    }
+    a * b
+}
+
+#[test]
+#[ignore]
+fn test_harness_detects_branch() {
+    let t = measure(
+        "leaky Fp::mul",
+        samples(100_000),
+        8,
+        |class, rng| {
+            let b = if class == 0 { Fp::zero() } else { random_fp(rng) };
+            (random_fp(rng), b)
+        },
+        |(a, b)| leaky_mul(a, b),
+    );
+    assert!(t >= THRESHOLD, "the harness missed a branch on zero: |t| = {:.2}", t);
+}
+
+#[test]
+#[ignore]
+fn test_fp_mul() {
+    assert_constant_time(
+        "Fp::mul",
+        100_000,
+        8,
+        |class, rng| {
+            let b = if class == 0 { Fp::zero() } else { random_fp(rng) };
+            (random_fp(rng), b)
+        },
+        |(a, b)| a * b,
+    );
+}
+
+#[test]
+#[ignore]
+fn test_fp_invert() {
+    assert_constant_time(
+        "Fp::invert",
+        20_000,
+        1,
+        |class, rng| if class == 0 { Fp::zero() } else { random_fp(rng) },
+        |a| a.invert(),
+    );
+}
+
+#[test]
+#[ignore]
+fn test_scalar_from_bytes() {
+    // Random bytes are mostly non-canonical, so this also checks that
+    // rejecting an encoding takes as long as accepting one.
+    assert_constant_time(
+        "Scalar::from_bytes",
+        100_000,
+        8,
+        |class, rng| {
+            let mut bytes = [0u8; 32];
+            if class == 1 {
+                rng.fill_bytes(&mut bytes);
+            }
+            bytes
+        },
+        Scalar::from_bytes,
+    );
+}
+
+// `G1Projective::multiply` and `G2Projective::multiply` are private; they are
+// measured through the `Mul<&Scalar>` impls that wrap them.
+
+#[test]
+#[ignore]
+fn test_g1_multiply() {
+    let p = G1Projective::generator() * Scalar::from(7);
+    assert_constant_time(
+        "G1Projective::multiply",
+        5_000,
+        1,
+        |class, rng| if class == 0 { Scalar::zero() } else { random_scalar(rng) },
+        |s| p * s,
+    );
+}
+
+#[test]
+#[ignore]
+fn test_g2_multiply() {
+    let p = G2Projective::generator() * Scalar::from(7);
+    assert_constant_time(
+        "G2Projective::multiply",
+        2_000,
+        1,
+        |class, rng| if class == 0 { Scalar::zero() } else { random_scalar(rng) },
+        |s| p * s,
+    );
+}
+
+/// Checks `conditional_select` on random operands, with the choice fixed to
+/// zero for class 0 and random for class 1.
+fn assert_select_constant_time<T: ConditionallySelectable>(
+    name: &str,
+    mut random: impl FnMut(&mut ChaChaRng) -> T,
+) {
+    assert_constant_time(
+        name,
+        100_000,
+        8,
+        |class, rng| {
+            let choice = if class == 0 { 0 } else { (rng.next_u32() & 1) as u8 };
+            (random(rng), random(rng), choice)
+        },
+        |(a, b, choice)| T::conditional_select(a, b, Choice::from(*choice)),
+    );
+}
+
+#[test]
+#[ignore]
+fn test_conditional_select() {
+    assert_select_constant_time("Fp::conditional_select", random_fp);
+    assert_select_constant_time("Fp2::conditional_select", random_fp2);
+    assert_select_constant_time("Scalar::conditional_select", random_scalar);
+
+    let g1 = G1Projective::generator();
+    let g2 = G2Projective::generator();
+    let mut g1s = vec![];
+    let mut g2s = vec![];
+    let mut rng = rng();
+    for _ in 0..16 {
+        let s = random_scalar(&mut rng);
+        g1s.push(g1 * s);
+        g2s.push(g2 * s);
+    }
+    let pick = |rng: &mut ChaChaRng| (rng.next_u32() % 16) as usize;
+    assert_select_constant_time("G1Projective::conditional_select", |rng| g1s[pick(rng)]);
+    assert_select_constant_time("G1Affine::conditional_select", |rng| {
+        G1Affine::from(g1s[pick(rng)])
+    });
+    assert_select_constant_time("G2Projective::conditional_select", |rng| g2s[pick(rng)]);
+    assert_select_constant_time("G2Affine::conditional_select", |rng| {
+        G2Affine::from(g2s[pick(rng)])
+    });
+}
+
+#[test]
+fn test_welch() {
+    // Identical classes give t = 0; a shift of one standard deviation with
+    // 100 samples per class gives |t| of about 7.3.
+    let mut w = Welch::default();
+    for i in 0..100 {
+        w.push(0, (i % 10) as f64);
+        w.push(1, (i % 10) as f64);
+    }
+    assert_eq!(w.t(), 0.0);
+
+    let mut w = Welch::default();
+    for i in 0..100 {
+        w.push(0, (i % 10) as f64);
+        w.push(1, (i % 10) as f64 + 3.0);
+    }
+    assert!((w.t() + 7.348).abs() < 0.001);
+}